@@ -0,0 +1,35 @@
+// Monta um payload com json_safe! e imprime o JSON.
+//
+//     cargo run --example payload
+
+use json_safe::{json_safe, money};
+
+#[derive(serde::Serialize)]
+struct Item {
+    sku: String,
+    qty: u32,
+}
+
+fn main() -> serde_json::Result<()> {
+    let itens = vec![
+        Item {
+            sku: "A-1".into(),
+            qty: 2,
+        },
+        Item {
+            sku: "B-7".into(),
+            qty: 1,
+        },
+    ];
+
+    let pedido = json_safe!({
+        "id": 1001,
+        "cliente": { "nome": "Ana", "email": "ana@ex.com" },
+        "itens": itens,
+        "total": (money!(15990, "BRL")),
+        "cupom": null,
+    })?;
+
+    println!("{}", serde_json::to_string_pretty(&pedido)?);
+    Ok(())
+}
@@ -0,0 +1,508 @@
+// json_safe_pm!: mesma sintaxe de json_safe!, como proc-macro, para que
+// entradas malformadas gerem erros no token exato em vez de na invocação
+// inteira. Valores podem ser expressões completas (sem parênteses). As
+// opções da raiz (all, dotted, strict, compact, nan_null, nan_string,
+// int128_string, strict_numbers) chamam os mesmos caminhos do json_safe!.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use std::collections::HashSet;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::spanned::Spanned;
+use syn::{
+    Expr, Ident, Lit, LitStr, Pat, Token, UnOp, braced, bracketed, parenthesized, parse_quote,
+    token,
+};
+
+enum Chave {
+    Fixa(String),
+    // (expr), convertida com Into<String>
+    Calculada(Box<Expr>),
+}
+
+enum Membro {
+    Campo(Chave, Valor),
+    // chave?: expr, com expr: Option; `None` omite a chave
+    Opcional(Chave, Box<Expr>),
+    // chave: skip_if!(expr, predicado); omitida se o predicado aceitar &expr
+    PularSe(Chave, Box<Expr>, Box<Expr>),
+    // ..expr: copia as entradas de um objeto
+    Spread(Box<Expr>),
+    // ..flatten!(expr): idem, com erro próprio, para structs
+    Achatar(Box<Expr>),
+    // if cond => membro
+    Se(Box<Expr>, Box<Membro>),
+}
+
+enum Valor {
+    Null,
+    Objeto(Vec<Membro>),
+    Array(Vec<Elemento>),
+    Expr(Box<Expr>),
+}
+
+// Opções aceitas antes do valor da raiz, como no json_safe!
+enum Opcao {
+    Dotted,
+    Strict,
+    Compact,
+    NanNull,
+    NanString,
+    Int128String,
+    StrictNumbers,
+}
+
+struct Raiz {
+    // all: acumula os erros em vez de parar no primeiro
+    todos: bool,
+    opcao: Option<Opcao>,
+    valor: Valor,
+}
+
+enum Elemento {
+    Valor(Valor),
+    // ..iter: cada item vira um elemento
+    Spread(Box<Expr>),
+    // for pat in iter => valor
+    Para(Box<Pat>, Box<Expr>, Valor),
+    // [valor; n]
+    Repetir(Valor, Box<Expr>),
+}
+
+impl Parse for Valor {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(token::Brace) {
+            let conteudo;
+            braced!(conteudo in input);
+            return objeto(&conteudo);
+        }
+        if input.peek(token::Bracket) {
+            let conteudo;
+            bracketed!(conteudo in input);
+            return array(&conteudo);
+        }
+        if input.peek(Ident) {
+            let fork = input.fork();
+            // `null` seguido de algo que não termina o valor é uma expressão
+            // (ex.: `null.len()`, com uma variável chamada null)
+            if fork.parse::<Ident>()? == "null"
+                && (fork.is_empty() || fork.peek(Token![,]) || fork.peek(Token![;]))
+            {
+                input.parse::<Ident>()?;
+                return Ok(Valor::Null);
+            }
+        }
+        if input.is_empty() {
+            return Err(input.error("esperado um valor"));
+        }
+        Ok(Valor::Expr(Box::new(input.parse()?)))
+    }
+}
+
+fn chave(input: ParseStream) -> syn::Result<Chave> {
+    if input.peek(LitStr) {
+        return Ok(Chave::Fixa(input.parse::<LitStr>()?.value()));
+    }
+    if input.peek(Ident::peek_any) {
+        return Ok(Chave::Fixa(
+            input.call(Ident::parse_any)?.unraw().to_string(),
+        ));
+    }
+    if input.peek(token::Paren) {
+        let conteudo;
+        parenthesized!(conteudo in input);
+        return Ok(Chave::Calculada(Box::new(conteudo.parse()?)));
+    }
+    Err(input.error("esperado chave: identificador, string literal ou (expressão)"))
+}
+
+fn membro(input: ParseStream) -> syn::Result<Membro> {
+    if input.peek(Token![if]) {
+        input.parse::<Token![if]>()?;
+        let condicao = Expr::parse_without_eager_brace(input)?;
+        if !input.peek(Token![=>]) {
+            return Err(input.error("esperado `=>` depois da condição"));
+        }
+        input.parse::<Token![=>]>()?;
+        return Ok(Membro::Se(Box::new(condicao), Box::new(membro(input)?)));
+    }
+    if input.peek(Token![..]) {
+        input.parse::<Token![..]>()?;
+        let fork = input.fork();
+        if fork.parse::<Ident>().is_ok_and(|i| i == "flatten") && fork.peek(Token![!]) {
+            input.parse::<Ident>()?;
+            input.parse::<Token![!]>()?;
+            let conteudo;
+            parenthesized!(conteudo in input);
+            let base = conteudo.parse()?;
+            if conteudo.peek(Token![,]) {
+                conteudo.parse::<Token![,]>()?;
+            }
+            if !conteudo.is_empty() {
+                return Err(conteudo.error("esperado flatten!(valor)"));
+            }
+            return Ok(Membro::Achatar(Box::new(base)));
+        }
+        return Ok(Membro::Spread(Box::new(input.parse()?)));
+    }
+    // Atalho: { foo } equivale a { foo: foo }, serializando por referência
+    let fork = input.fork();
+    if let Ok(ident) = fork.call(Ident::parse_any)
+        && (fork.is_empty() || fork.peek(Token![,]))
+    {
+        input.call(Ident::parse_any)?;
+        let nome = Chave::Fixa(ident.unraw().to_string());
+        return Ok(Membro::Campo(
+            nome,
+            Valor::Expr(Box::new(parse_quote!(&#ident))),
+        ));
+    }
+    let nome = chave(input)?;
+    let opcional = input.peek(Token![?]);
+    if opcional {
+        input.parse::<Token![?]>()?;
+    }
+    if !input.peek(Token![:]) {
+        return Err(input.error("esperado `:` depois da chave"));
+    }
+    input.parse::<Token![:]>()?;
+    if opcional {
+        return Ok(Membro::Opcional(nome, Box::new(input.parse()?)));
+    }
+    let fork = input.fork();
+    if fork.parse::<Ident>().is_ok_and(|i| i == "skip_if") && fork.peek(Token![!]) {
+        input.parse::<Ident>()?;
+        input.parse::<Token![!]>()?;
+        let conteudo;
+        parenthesized!(conteudo in input);
+        let valor = conteudo.parse()?;
+        conteudo.parse::<Token![,]>()?;
+        let predicado = conteudo.parse()?;
+        if conteudo.peek(Token![,]) {
+            conteudo.parse::<Token![,]>()?;
+        }
+        if !conteudo.is_empty() {
+            return Err(conteudo.error("esperado skip_if!(valor, predicado)"));
+        }
+        return Ok(Membro::PularSe(nome, Box::new(valor), Box::new(predicado)));
+    }
+    Ok(Membro::Campo(nome, input.parse()?))
+}
+
+fn objeto(input: ParseStream) -> syn::Result<Valor> {
+    let mut membros = Vec::new();
+    // Chaves fixas já vistas; membros condicionais podem sobrescrever
+    let mut vistas = HashSet::new();
+    while !input.is_empty() {
+        let inicio = input.span();
+        let m = membro(input)?;
+        if let Membro::Campo(Chave::Fixa(k), _)
+        | Membro::Opcional(Chave::Fixa(k), _)
+        | Membro::PularSe(Chave::Fixa(k), _, _) = &m
+            && !vistas.insert(k.clone())
+        {
+            return Err(syn::Error::new(inicio, format!("chave duplicada: `{k}`")));
+        }
+        membros.push(m);
+        separador(input, "`,` ou `}`")?;
+    }
+    Ok(Valor::Objeto(membros))
+}
+
+fn array(input: ParseStream) -> syn::Result<Valor> {
+    let mut itens = Vec::new();
+    while !input.is_empty() {
+        if input.peek(Token![for]) {
+            input.parse::<Token![for]>()?;
+            let padrao = Pat::parse_multi_with_leading_vert(input)?;
+            input.parse::<Token![in]>()?;
+            let iter = Expr::parse_without_eager_brace(input)?;
+            if !input.peek(Token![=>]) {
+                return Err(input.error("esperado `=>` depois do iterador"));
+            }
+            input.parse::<Token![=>]>()?;
+            itens.push(Elemento::Para(
+                Box::new(padrao),
+                Box::new(iter),
+                input.parse()?,
+            ));
+        } else if input.peek(Token![..]) {
+            input.parse::<Token![..]>()?;
+            itens.push(Elemento::Spread(Box::new(input.parse()?)));
+        } else {
+            let valor = input.parse()?;
+            if itens.is_empty() && input.peek(Token![;]) {
+                input.parse::<Token![;]>()?;
+                let n = input.parse()?;
+                if !input.is_empty() {
+                    return Err(input.error("token inesperado depois do tamanho da repetição"));
+                }
+                itens.push(Elemento::Repetir(valor, Box::new(n)));
+                break;
+            }
+            itens.push(Elemento::Valor(valor));
+        }
+        separador(input, "`,` ou `]`")?;
+    }
+    Ok(Valor::Array(itens))
+}
+
+fn separador(input: ParseStream, esperado: &str) -> syn::Result<()> {
+    if input.is_empty() {
+        return Ok(());
+    }
+    if !input.peek(Token![,]) {
+        return Err(input.error(format!("esperado {esperado} depois do valor")));
+    }
+    input.parse::<Token![,]>()?;
+    Ok(())
+}
+
+// Identificador de opção seguido do valor que ela modifica
+fn prefixo(input: ParseStream, nomes: &[&str]) -> Option<Ident> {
+    let fork = input.fork();
+    let ident = fork.parse::<Ident>().ok()?;
+    let valor_segue = !fork.is_empty() && !fork.peek(Token![.]) && !fork.peek(Token![,]);
+    (nomes.iter().any(|n| ident == n) && valor_segue).then_some(ident)
+}
+
+fn raiz(input: ParseStream) -> syn::Result<Raiz> {
+    let todos = prefixo(input, &["all"]).is_some();
+    if todos {
+        input.parse::<Ident>()?;
+    }
+    let nomes = [
+        "dotted",
+        "strict",
+        "compact",
+        "nan_null",
+        "nan_string",
+        "int128_string",
+        "strict_numbers",
+    ];
+    let opcao = match prefixo(input, &nomes) {
+        None => None,
+        Some(ident) => {
+            input.parse::<Ident>()?;
+            let objeto = input.peek(token::Brace);
+            let aceita = match ident.to_string().as_str() {
+                "dotted" | "strict" => objeto,
+                "compact" => objeto || input.peek(token::Bracket),
+                _ => true,
+            };
+            if !aceita {
+                return Err(input.error(format!("{ident} espera um objeto {{ ... }}")));
+            }
+            Some(match ident.to_string().as_str() {
+                "dotted" => Opcao::Dotted,
+                "strict" => Opcao::Strict,
+                "compact" => Opcao::Compact,
+                "nan_null" => Opcao::NanNull,
+                "nan_string" => Opcao::NanString,
+                "int128_string" => Opcao::Int128String,
+                _ => Opcao::StrictNumbers,
+            })
+        }
+    };
+    let valor = input.parse()?;
+    if !input.is_empty() {
+        return Err(input.error("token inesperado depois do valor"));
+    }
+    Ok(Raiz {
+        todos,
+        opcao,
+        valor,
+    })
+}
+
+// Texto original de um literal numérico (com sinal), para a feature
+// "arbitrary-precision" do json_safe
+fn lexema(expr: &Expr) -> Option<String> {
+    let (sinal, lit) = match expr {
+        Expr::Lit(lit) => ("", &lit.lit),
+        Expr::Unary(u) if matches!(u.op, UnOp::Neg(_)) => match &*u.expr {
+            Expr::Lit(lit) => ("-", &lit.lit),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match lit {
+        Lit::Int(n) if n.suffix().is_empty() => Some(format!("{sinal}{}", n.base10_digits())),
+        Lit::Float(f) if f.suffix().is_empty() => Some(format!("{sinal}{}", f.base10_digits())),
+        _ => None,
+    }
+}
+
+fn gerar_chave(chave: &Chave) -> TokenStream2 {
+    match chave {
+        Chave::Fixa(k) => quote!(::std::string::String::from(#k)),
+        Chave::Calculada(expr) => quote_spanned!(expr.span()=>
+            ::std::convert::Into::<::std::string::String>::into(#expr)
+        ),
+    }
+}
+
+// Instruções que inserem o membro em `map`
+fn gerar_membro(membro: &Membro, map: &Ident) -> TokenStream2 {
+    let pv = quote!(::json_safe::__private);
+    let (chave, valor, item) = (
+        Ident::new("chave", Span::mixed_site()),
+        Ident::new("valor", Span::mixed_site()),
+        Ident::new("item", Span::mixed_site()),
+    );
+    match membro {
+        Membro::Campo(k, v) => {
+            let (k, v) = (gerar_chave(k), gerar(v));
+            quote!({
+                let #chave = #k;
+                let #valor = #pv::key(&#chave, || #v)?;
+                #map.insert(#chave, #valor);
+            })
+        }
+        Membro::Opcional(k, expr) => {
+            let k = gerar_chave(k);
+            quote_spanned!(expr.span()=>
+                if let ::std::option::Option::Some(#item) =
+                    ::std::option::Option::as_ref(&(#expr))
+                {
+                    let #chave = #k;
+                    let #valor = #pv::key(&#chave, || #pv::to_value(#item))?;
+                    #map.insert(#chave, #valor);
+                }
+            )
+        }
+        Membro::PularSe(k, expr, predicado) => {
+            let k = gerar_chave(k);
+            quote_spanned!(expr.span()=> {
+                let #item = &(#expr);
+                if !#pv::skip_if(#item, #predicado) {
+                    let #chave = #k;
+                    let #valor = #pv::key(&#chave, || #pv::to_value(#item))?;
+                    #map.insert(#chave, #valor);
+                }
+            })
+        }
+        Membro::Spread(base) => quote_spanned!(base.span()=>
+            #pv::spread(&mut #map, #pv::to_value(&(#base))?)?;
+        ),
+        Membro::Achatar(base) => quote_spanned!(base.span()=>
+            #pv::flatten(&mut #map, #pv::to_value(&(#base))?)?;
+        ),
+        Membro::Se(condicao, membro) => {
+            let membro = gerar_membro(membro, map);
+            quote!(if #condicao { #membro })
+        }
+    }
+}
+
+// Expressão do tipo `Result<Value, JsonSafeError>`; objetos e arrays são
+// closures para que o erro de um campo receba o caminho do campo
+fn gerar(valor: &Valor) -> TokenStream2 {
+    let pv = quote!(::json_safe::__private);
+    let sj = quote!(#pv::serde_json);
+    // Higiene: o `map` interno não pode capturar um `map` das expressões
+    let map = Ident::new("map", Span::mixed_site());
+    match valor {
+        Valor::Null => quote!(#pv::Result::Ok(#sj::Value::Null)),
+        Valor::Objeto(membros) => {
+            let insercoes = membros.iter().map(|m| gerar_membro(m, &map));
+            quote!((|| -> #pv::Result {
+                let mut #map = #sj::Map::new();
+                #(#insercoes)*
+                ::std::result::Result::Ok(#sj::Value::Object(#map))
+            })())
+        }
+        Valor::Array(itens) => {
+            let vec = Ident::new("vec", Span::mixed_site());
+            let pushes = itens.iter().map(|item| match item {
+                Elemento::Valor(v) => {
+                    let v = gerar(v);
+                    quote!(#vec.push(#pv::index(#vec.len(), || #v)?);)
+                }
+                Elemento::Repetir(v, n) => {
+                    let v = gerar(v);
+                    quote!(#vec.extend(::std::iter::repeat_n(#pv::index(0, || #v)?, #n));)
+                }
+                Elemento::Para(padrao, iter, v) => {
+                    let v = gerar(v);
+                    quote!(for #padrao in #iter { #vec.push(#pv::index(#vec.len(), || #v)?); })
+                }
+                Elemento::Spread(iter) => quote_spanned!(iter.span()=>
+                    for item in ::std::iter::IntoIterator::into_iter(#iter) {
+                        #vec.push(#pv::index(#vec.len(), || #pv::to_value(item))?);
+                    }
+                ),
+            });
+            quote!((|| -> #pv::Result {
+                let mut #vec: ::std::vec::Vec<#sj::Value> = ::std::vec::Vec::new();
+                #(#pushes)*
+                ::std::result::Result::Ok(#sj::Value::Array(#vec))
+            })())
+        }
+        Valor::Expr(expr) => match lexema(expr) {
+            Some(texto) => quote_spanned!(expr.span()=> #pv::to_value_lexeme(#texto, #expr)),
+            None => quote_spanned!(expr.span()=> #pv::to_value(#expr)),
+        },
+    }
+}
+
+// A raiz com a opção aplicada, como nas entradas `@value <opção>` do json_safe!
+fn gerar_raiz(raiz: &Raiz) -> TokenStream2 {
+    let js = quote!(::json_safe);
+    let pv = quote!(#js::__private);
+    let valor = Ident::new("valor", Span::mixed_site());
+    let corpo = match (&raiz.opcao, &raiz.valor) {
+        (Some(Opcao::Strict), Valor::Objeto(membros)) => {
+            let map = Ident::new("map", Span::mixed_site());
+            let insercoes = membros.iter().map(|m| gerar_membro(m, &map));
+            quote!((|| -> #pv::Result {
+                #[allow(unused_mut)]
+                let mut #map = #pv::Strict::default();
+                #(#insercoes)*
+                ::std::result::Result::Ok(#map.finish()?)
+            })())
+        }
+        (opcao, v) => {
+            let v = gerar(v);
+            match opcao {
+                None | Some(Opcao::Strict) => v,
+                Some(Opcao::Dotted) => quote!(#v.and_then(|#valor| {
+                    ::std::result::Result::Ok(#js::dotted::expand_dotted_keys(#valor)?)
+                })),
+                Some(Opcao::Compact) => quote!(#v.map(#js::compact::drop_nulls)),
+                Some(Opcao::NanNull) => quote!(#js::non_finite::with_policy(
+                    #js::non_finite::NonFinite::CoerceToNull,
+                    || #v,
+                )),
+                Some(Opcao::NanString) => quote!(#js::non_finite::with_policy(
+                    #js::non_finite::NonFinite::StringifyAsNaN,
+                    || #v,
+                )),
+                Some(Opcao::Int128String) => quote!(#js::int128::with_policy(
+                    #js::int128::Overflow::Stringify,
+                    || #v,
+                )),
+                Some(Opcao::StrictNumbers) => quote!(#js::numeric::strict(|| #v)),
+            }
+        }
+    };
+    if raiz.todos {
+        quote!(#pv::accumulate(|| #corpo))
+    } else {
+        quote!(#pv::isolate(|| #corpo))
+    }
+}
+
+/// Versão proc-macro de `json_safe!`: retorna `Result<Value, JsonSafeError>`
+/// (ou `Result<Value, Vec<JsonSafeError>>` com `all`), com erros de sintaxe
+/// apontando para o token problemático. Chaves fixas repetidas no mesmo
+/// objeto são erro de compilação.
+#[proc_macro]
+pub fn json_safe_pm(input: TokenStream) -> TokenStream {
+    match raiz.parse(input) {
+        Ok(raiz) => gerar_raiz(&raiz).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,297 @@
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::canonical;
+
+// Utilitários sobre arrays de objetos dentro de documentos. `key_path` é um
+// caminho com pontos relativo a cada item (`id`, `user.id`); `pointer` é um
+// JSON Pointer até o array.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+fn chave<'a>(item: &'a Value, key_path: &str) -> &'a Value {
+    key_path
+        .split('.')
+        .try_fold(item, |atual, parte| atual.get(parte))
+        .unwrap_or(&Value::Null)
+}
+
+fn array_em<'a>(doc: &'a mut Value, pointer: &str) -> serde_json::Result<&'a mut Vec<Value>> {
+    match doc.pointer_mut(pointer) {
+        Some(Value::Array(itens)) => Ok(itens),
+        Some(_) => Err(erro(format!("{pointer}: não é um array"))),
+        None => Err(erro(format!("{pointer}: caminho inexistente"))),
+    }
+}
+
+fn ordem_tipo(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// Ordem total entre valores: `null` < booleanos < números < strings <
+/// arrays < objetos; dentro do mesmo tipo, a ordem natural.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => match (x.as_i64(), y.as_i64()) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            _ => match (x.as_u64(), y.as_u64()) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                _ => {
+                    let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+                    x.total_cmp(&y)
+                }
+            },
+        },
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(a, b)| compare_values(a, b))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| x.len().cmp(&y.len())),
+        (Value::Object(_), Value::Object(_)) => {
+            canonical::to_canonical_string(a).cmp(&canonical::to_canonical_string(b))
+        }
+        _ => ordem_tipo(a).cmp(&ordem_tipo(b)),
+    }
+}
+
+/// Ordena (de forma estável) o array em `pointer` pelo valor em `key_path`
+/// de cada item; itens sem a chave vão para o início.
+pub fn sort_array_by(doc: &mut Value, pointer: &str, key_path: &str) -> serde_json::Result<()> {
+    array_em(doc, pointer)?.sort_by(|a, b| compare_values(chave(a, key_path), chave(b, key_path)));
+    Ok(())
+}
+
+/// Remove itens repetidos pelo valor em `key_path`, mantendo a primeira
+/// ocorrência e a ordem original. Itens sem a chave contam como `null`.
+pub fn dedup_array_by(doc: &mut Value, pointer: &str, key_path: &str) -> serde_json::Result<()> {
+    let itens = array_em(doc, pointer)?;
+    let mut vistos = BTreeSet::new();
+    itens.retain(|item| vistos.insert(canonical::to_canonical_string(chave(item, key_path))));
+    Ok(())
+}
+
+fn itens<'a>(array: &'a Value, nome: &str) -> serde_json::Result<&'a Vec<Value>> {
+    array
+        .as_array()
+        .ok_or_else(|| erro(format!("{nome}: esperado um array")))
+}
+
+// Chave de grupo: o JSON canônico do valor, para que `"1"` e `1` (ou
+// `"null"` e null) não caiam no mesmo grupo
+fn rotulo(value: &Value) -> String {
+    canonical::to_canonical_string(value)
+}
+
+/// `{ chave: [itens...] }`, agrupando pelo valor em `key_path`, com o JSON
+/// canônico do valor como chave (`"\"compra\""`, `"1"`; itens sem a chave
+/// ficam em `"null"`).
+pub fn group_by(array: &Value, key_path: &str) -> serde_json::Result<Value> {
+    let mut grupos = Map::new();
+    for item in itens(array, "group_by")? {
+        let grupo = grupos
+            .entry(rotulo(chave(item, key_path)))
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(membros) = grupo {
+            membros.push(item.clone());
+        }
+    }
+    Ok(Value::Object(grupos))
+}
+
+/// Agregação sobre os valores em um caminho; `null`/ausentes são ignorados.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agg<'a> {
+    Count,
+    Sum(&'a str),
+    Min(&'a str),
+    Max(&'a str),
+}
+
+fn agregar(membros: &[&Value], agg: Agg) -> serde_json::Result<Value> {
+    let valores = |caminho: &str| -> Vec<Value> {
+        membros
+            .iter()
+            .map(|item| chave(item, caminho).clone())
+            .filter(|v| !v.is_null())
+            .collect()
+    };
+    match agg {
+        Agg::Count => Ok(Value::from(membros.len())),
+        Agg::Sum(caminho) => {
+            let mut inteiro: Option<i64> = Some(0);
+            let mut real = 0f64;
+            for v in valores(caminho) {
+                let Value::Number(n) = &v else {
+                    return Err(erro(format!("sum({caminho}): valor não numérico {v}")));
+                };
+                inteiro = inteiro.zip(n.as_i64()).and_then(|(a, b)| a.checked_add(b));
+                real += n.as_f64().unwrap_or(0.0);
+            }
+            // Soma inteira enquanto todos forem inteiros e não houver overflow
+            Ok(inteiro
+                .map(Value::from)
+                .unwrap_or_else(|| Value::from(real)))
+        }
+        Agg::Min(caminho) => Ok(valores(caminho)
+            .into_iter()
+            .min_by(compare_values)
+            .unwrap_or(Value::Null)),
+        Agg::Max(caminho) => Ok(valores(caminho)
+            .into_iter()
+            .max_by(compare_values)
+            .unwrap_or(Value::Null)),
+    }
+}
+
+fn agregar_todos(membros: &[&Value], aggs: &[(&str, Agg)]) -> serde_json::Result<Value> {
+    let mut out = Map::new();
+    for (nome, agg) in aggs {
+        out.insert(nome.to_string(), agregar(membros, *agg)?);
+    }
+    Ok(Value::Object(out))
+}
+
+/// `{ nome: resultado }` para cada agregação sobre o array inteiro.
+pub fn aggregate(array: &Value, aggs: &[(&str, Agg)]) -> serde_json::Result<Value> {
+    let membros: Vec<&Value> = itens(array, "aggregate")?.iter().collect();
+    agregar_todos(&membros, aggs)
+}
+
+/// `{ grupo: { nome: resultado } }`, agrupando por `key_path`, com as
+/// chaves de grupo do [`group_by`].
+pub fn aggregate_by(
+    array: &Value,
+    key_path: &str,
+    aggs: &[(&str, Agg)],
+) -> serde_json::Result<Value> {
+    let mut grupos: BTreeMap<String, Vec<&Value>> = BTreeMap::new();
+    for item in itens(array, "aggregate_by")? {
+        grupos
+            .entry(rotulo(chave(item, key_path)))
+            .or_default()
+            .push(item);
+    }
+    let mut out = Map::new();
+    for (grupo, membros) in grupos {
+        out.insert(grupo, agregar_todos(&membros, aggs)?);
+    }
+    Ok(Value::Object(out))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Só itens da esquerda com correspondência.
+    Inner,
+    /// Todos os itens da esquerda; sem correspondência ficam como estão.
+    Left,
+}
+
+/// Junta `left` e `right` (arrays de objetos) pelo valor em `on`. Cada par
+/// correspondente gera um objeto com os campos dos dois lados; em conflito,
+/// vale o da esquerda. Itens sem a chave nunca correspondem.
+pub fn join(left: &Value, right: &Value, on: &str, kind: JoinKind) -> serde_json::Result<Value> {
+    let mut indice: BTreeMap<String, Vec<&Map<String, Value>>> = BTreeMap::new();
+    for (i, item) in itens(right, "join")?.iter().enumerate() {
+        let obj = item
+            .as_object()
+            .ok_or_else(|| erro(format!("join: right[{i}] não é objeto")))?;
+        let k = chave(item, on);
+        if !k.is_null() {
+            indice
+                .entry(canonical::to_canonical_string(k))
+                .or_default()
+                .push(obj);
+        }
+    }
+
+    let mut out = Vec::new();
+    for (i, item) in itens(left, "join")?.iter().enumerate() {
+        let obj = item
+            .as_object()
+            .ok_or_else(|| erro(format!("join: left[{i}] não é objeto")))?;
+        let k = chave(item, on);
+        let pares = (!k.is_null())
+            .then(|| indice.get(&canonical::to_canonical_string(k)))
+            .flatten();
+        match pares {
+            Some(pares) => {
+                for direita in pares {
+                    let mut junto = (*direita).clone();
+                    junto.extend(obj.iter().map(|(k, v)| (k.clone(), v.clone())));
+                    out.push(Value::Object(junto));
+                }
+            }
+            None if kind == JoinKind::Left => out.push(item.clone()),
+            None => {}
+        }
+    }
+    Ok(Value::Array(out))
+}
+
+/// Divide o documento em páginas de até `n` itens do array em `pointer`,
+/// copiando o resto do documento (envelope) em cada página. Um array vazio
+/// gera uma única página.
+pub fn chunk_array_at(value: &Value, pointer: &str, n: usize) -> serde_json::Result<Vec<Value>> {
+    if n == 0 {
+        return Err(erro("tamanho de página deve ser maior que zero".into()));
+    }
+    let itens = match value.pointer(pointer) {
+        Some(Value::Array(itens)) => itens,
+        Some(_) => return Err(erro(format!("{pointer}: não é um array"))),
+        None => return Err(erro(format!("{pointer}: caminho inexistente"))),
+    };
+    if itens.is_empty() {
+        return Ok(vec![value.clone()]);
+    }
+
+    // Envelope sem os itens, para não clonar o array inteiro a cada página
+    let mut envelope = value.clone();
+    if let Some(alvo) = envelope.pointer_mut(pointer) {
+        *alvo = Value::Array(Vec::new());
+    }
+    Ok(itens
+        .chunks(n)
+        .map(|pagina| {
+            let mut doc = envelope.clone();
+            if let Some(alvo) = doc.pointer_mut(pointer) {
+                *alvo = Value::Array(pagina.to_vec());
+            }
+            doc
+        })
+        .collect())
+}
+
+/// [`chunk_array_at`] sobre a raiz, se ela for um array, ou sobre o único
+/// campo de topo que é array; qualquer outro formato é erro.
+pub fn chunk_array(value: &Value, n: usize) -> serde_json::Result<Vec<Value>> {
+    let ponteiro = match value {
+        Value::Array(_) => String::new(),
+        Value::Object(map) => {
+            let mut arrays = map.iter().filter(|(_, v)| v.is_array()).map(|(k, _)| k);
+            match (arrays.next(), arrays.next()) {
+                (Some(chave), None) => format!("/{}", chave.replace('~', "~0").replace('/', "~1")),
+                _ => {
+                    return Err(erro(
+                        "chunk_array: envelope deve ter exatamente um campo array; use chunk_array_at"
+                            .into(),
+                    ));
+                }
+            }
+        }
+        _ => return Err(erro("chunk_array: esperado array ou objeto".into())),
+    };
+    chunk_array_at(value, &ponteiro, n)
+}
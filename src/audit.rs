@@ -0,0 +1,116 @@
+use serde_json::{Map, Value};
+use std::fmt;
+
+use crate::canonical;
+
+// Log de auditoria encadeado: cada entrada `{ ts, actor, action, resource,
+// details, prev_hash }` carrega o hash canônico da entrada anterior, de modo
+// que alterar, remover ou reordenar entradas quebra a cadeia.
+
+/// `prev_hash` da primeira entrada de uma cadeia.
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Produz entradas encadeadas a partir de um hash inicial.
+#[derive(Debug, Clone)]
+pub struct AuditChain {
+    prev_hash: String,
+}
+
+impl Default for AuditChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditChain {
+    /// Nova cadeia, a partir de [`GENESIS`].
+    pub fn new() -> Self {
+        AuditChain {
+            prev_hash: GENESIS.to_string(),
+        }
+    }
+
+    /// Continua uma cadeia já persistida a partir da sua última entrada.
+    pub fn resume(last_entry: &Value) -> serde_json::Result<Self> {
+        if !last_entry["prev_hash"].is_string() {
+            return Err(erro("entrada de auditoria sem prev_hash".into()));
+        }
+        Ok(AuditChain {
+            prev_hash: canonical::canonical_hash(last_entry),
+        })
+    }
+
+    /// Hash que a próxima entrada vai carregar em `prev_hash`.
+    pub fn head(&self) -> &str {
+        &self.prev_hash
+    }
+
+    /// Cria a próxima entrada. `ts` vem do chamador (ex.: RFC 3339), para que
+    /// a entrada seja reprodutível.
+    pub fn append(
+        &mut self,
+        ts: &str,
+        actor: &str,
+        action: &str,
+        resource: &str,
+        details: Value,
+    ) -> serde_json::Result<Value> {
+        for (campo, valor) in [("ts", ts), ("actor", actor), ("action", action)] {
+            if valor.trim().is_empty() {
+                return Err(erro(format!("campo {campo} vazio")));
+            }
+        }
+        let mut map = Map::new();
+        map.insert("ts".into(), Value::from(ts));
+        map.insert("actor".into(), Value::from(actor));
+        map.insert("action".into(), Value::from(action));
+        map.insert("resource".into(), Value::from(resource));
+        map.insert("details".into(), details);
+        map.insert("prev_hash".into(), Value::from(self.prev_hash.as_str()));
+        let entrada = Value::Object(map);
+        self.prev_hash = canonical::canonical_hash(&entrada);
+        Ok(entrada)
+    }
+}
+
+/// Primeiro ponto em que a cadeia não confere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainError {
+    pub index: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "entrada {}: {}", self.index, self.reason)
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// Verifica uma cadeia completa, começando em [`GENESIS`].
+pub fn verify_chain(entries: &[Value]) -> Result<(), ChainError> {
+    verify_chain_from(GENESIS, entries)
+}
+
+/// Verifica um trecho de cadeia cujo `prev_hash` inicial é `start`.
+pub fn verify_chain_from(start: &str, entries: &[Value]) -> Result<(), ChainError> {
+    let mut esperado = start.to_string();
+    for (index, entrada) in entries.iter().enumerate() {
+        let falha = |reason: String| ChainError { index, reason };
+        let Some(prev) = entrada["prev_hash"].as_str() else {
+            return Err(falha("sem prev_hash".into()));
+        };
+        if prev != esperado {
+            return Err(falha(format!(
+                "prev_hash {prev} não confere com o hash da entrada anterior {esperado}"
+            )));
+        }
+        esperado = canonical::canonical_hash(entrada);
+    }
+    Ok(())
+}
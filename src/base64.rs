@@ -0,0 +1,107 @@
+use serde::{Serialize, Serializer};
+
+// Base64 (RFC 4648) padrão com padding e variante URL-safe sem padding
+// (usada em JWS/JWT). `base64!(bytes)` põe binários no payload como string,
+// em vez de um array de números.
+
+// base64!(anexo) -> "aGVsbG8=", base64!(url anexo) -> "aGVsbG8"
+#[macro_export]
+macro_rules! base64 {
+    (url $data:expr) => {
+        $crate::base64::Base64::url($data)
+    };
+    ($data:expr) => {
+        $crate::base64::Base64::new($data)
+    };
+}
+
+const PADRAO: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn codificar(data: &[u8], alfabeto: &[u8; 64], padding: bool) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for grupo in data.chunks(3) {
+        let b = [
+            grupo[0],
+            grupo.get(1).copied().unwrap_or(0),
+            grupo.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= grupo.len() {
+                out.push(alfabeto[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else if padding {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decodificar(texto: &str, alfabeto: &[u8; 64]) -> Option<Vec<u8>> {
+    let texto = texto.trim_end_matches('=');
+    if texto.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(texto.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in texto.bytes() {
+        let v = alfabeto.iter().position(|&a| a == c)? as u32;
+        acc = (acc << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+pub fn encode(data: &[u8]) -> String {
+    codificar(data, PADRAO, true)
+}
+
+/// `None` se houver caracteres fora do alfabeto.
+pub fn decode(text: &str) -> Option<Vec<u8>> {
+    decodificar(text, PADRAO)
+}
+
+pub fn encode_url(data: &[u8]) -> String {
+    codificar(data, URL, false)
+}
+
+pub fn decode_url(text: &str) -> Option<Vec<u8>> {
+    decodificar(text, URL)
+}
+
+/// Bytes (`Vec<u8>`, `&[u8]`, `[u8; N]`) serializados como string base64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base64<T> {
+    data: T,
+    url: bool,
+}
+
+impl<T: AsRef<[u8]>> Base64<T> {
+    /// Alfabeto padrão, com padding.
+    pub fn new(data: T) -> Self {
+        Base64 { data, url: false }
+    }
+
+    /// Alfabeto URL-safe, sem padding.
+    pub fn url(data: T) -> Self {
+        Base64 { data, url: true }
+    }
+}
+
+impl<T: AsRef<[u8]>> Serialize for Base64<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = self.data.as_ref();
+        serializer.serialize_str(&if self.url {
+            encode_url(data)
+        } else {
+            encode(data)
+        })
+    }
+}
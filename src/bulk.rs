@@ -0,0 +1,134 @@
+use serde_json::{Map, Value};
+
+// Corpo NDJSON da API `_bulk` do OpenSearch/Elasticsearch: uma linha de
+// ação seguida (exceto em `delete`) da linha do documento, sempre
+// terminando em `\n`.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Index,
+    Create,
+    Update,
+    Delete,
+}
+
+impl Action {
+    fn nome(self) -> &'static str {
+        match self {
+            Action::Index => "index",
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+        }
+    }
+}
+
+/// Uma operação do `_bulk`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkOp {
+    action: Action,
+    index: String,
+    id: Option<String>,
+    doc: Option<Value>,
+}
+
+impl BulkOp {
+    pub fn index(index: impl Into<String>, doc: Value) -> Self {
+        Self::nova(Action::Index, index.into(), None, Some(doc))
+    }
+
+    pub fn create(index: impl Into<String>, doc: Value) -> Self {
+        Self::nova(Action::Create, index.into(), None, Some(doc))
+    }
+
+    /// `doc` é o documento parcial; vai embrulhado em `{ "doc": ... }`.
+    pub fn update(index: impl Into<String>, id: impl Into<String>, doc: Value) -> Self {
+        Self::nova(Action::Update, index.into(), Some(id.into()), Some(doc))
+    }
+
+    pub fn delete(index: impl Into<String>, id: impl Into<String>) -> Self {
+        Self::nova(Action::Delete, index.into(), Some(id.into()), None)
+    }
+
+    /// Define o `_id` (opcional em `index`/`create`).
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    fn nova(action: Action, index: String, id: Option<String>, doc: Option<Value>) -> Self {
+        BulkOp {
+            action,
+            index,
+            id,
+            doc,
+        }
+    }
+
+    /// Serializa a operação nas suas linhas NDJSON.
+    pub fn to_lines(&self) -> serde_json::Result<String> {
+        if self.index.is_empty() {
+            return Err(erro(format!("{}: _index vazio", self.action.nome())));
+        }
+
+        let mut meta = Map::new();
+        meta.insert("_index".into(), Value::from(self.index.as_str()));
+        if let Some(id) = &self.id {
+            meta.insert("_id".into(), Value::from(id.as_str()));
+        }
+        let mut acao = Map::new();
+        acao.insert(self.action.nome().into(), Value::Object(meta));
+
+        let mut out = serde_json::to_string(&Value::Object(acao))?;
+        out.push('\n');
+
+        if let Some(doc) = &self.doc {
+            if !doc.is_object() {
+                return Err(erro(format!(
+                    "{}: documento deve ser um objeto JSON",
+                    self.action.nome()
+                )));
+            }
+            let corpo = if self.action == Action::Update {
+                let mut parcial = Map::new();
+                parcial.insert("doc".into(), doc.clone());
+                serde_json::to_string(&Value::Object(parcial))?
+            } else {
+                serde_json::to_string(doc)?
+            };
+            out.push_str(&corpo);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Monta o corpo `_bulk` dividido em blocos de no máximo `max_bytes`,
+/// sem nunca separar a linha de ação da linha do documento.
+pub fn to_chunks(ops: &[BulkOp], max_bytes: usize) -> serde_json::Result<Vec<String>> {
+    let mut blocos = Vec::new();
+    let mut atual = String::new();
+
+    for (i, op) in ops.iter().enumerate() {
+        let linhas = op.to_lines()?;
+        if linhas.len() > max_bytes {
+            return Err(erro(format!(
+                "operação {i} tem {} bytes, acima do limite de {max_bytes}",
+                linhas.len()
+            )));
+        }
+        if atual.len() + linhas.len() > max_bytes {
+            blocos.push(std::mem::take(&mut atual));
+        }
+        atual.push_str(&linhas);
+    }
+
+    if !atual.is_empty() {
+        blocos.push(atual);
+    }
+    Ok(blocos)
+}
@@ -0,0 +1,49 @@
+use serde_json::Value;
+
+use crate::digest;
+
+// Forma canônica de um documento: JSON compacto com as chaves de todos os
+// objetos em ordem lexicográfica (por bytes UTF-8), independente da ordem
+// de inserção. Números e strings seguem a serialização do serde_json.
+
+/// Serializa `value` na forma canônica.
+pub fn to_canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    escrever(value, &mut out);
+    out
+}
+
+fn escrever(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut chaves: Vec<&String> = map.keys().collect();
+            chaves.sort();
+            out.push('{');
+            for (i, chave) in chaves.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(chave.clone()).to_string());
+                out.push(':');
+                escrever(&map[chave], out);
+            }
+            out.push('}');
+        }
+        Value::Array(itens) => {
+            out.push('[');
+            for (i, item) in itens.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                escrever(item, out);
+            }
+            out.push(']');
+        }
+        escalar => out.push_str(&escalar.to_string()),
+    }
+}
+
+/// SHA-256 (hex) da forma canônica de `value`.
+pub fn canonical_hash(value: &Value) -> String {
+    digest::hex(&digest::sha256(to_canonical_string(value).as_bytes()))
+}
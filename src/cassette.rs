@@ -0,0 +1,188 @@
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::canonical;
+use crate::profile::Rules;
+
+// Gravação/reprodução de interações HTTP em JSON: o cliente registra cada
+// par requisição/resposta, os corpos passam pelas regras de redação antes
+// de gravar e os testes reproduzem as respostas a partir do arquivo. As
+// requisições são comparadas pela forma canônica, já redigida.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Uma interação gravada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub method: String,
+    pub path: String,
+    pub request: Option<Value>,
+    pub status: u16,
+    pub response: Value,
+}
+
+impl Interaction {
+    fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("method".into(), Value::from(self.method.clone()));
+        map.insert("path".into(), Value::from(self.path.clone()));
+        map.insert(
+            "request".into(),
+            self.request.clone().unwrap_or(Value::Null),
+        );
+        map.insert("status".into(), Value::from(self.status));
+        map.insert("response".into(), self.response.clone());
+        Value::Object(map)
+    }
+
+    fn from_value(i: usize, value: &Value) -> serde_json::Result<Self> {
+        let texto = |campo: &str| {
+            value[campo]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| erro(format!("interactions[{i}]: sem `{campo}`")))
+        };
+        let status = value["status"]
+            .as_u64()
+            .and_then(|s| u16::try_from(s).ok())
+            .ok_or_else(|| erro(format!("interactions[{i}]: `status` inválido")))?;
+        Ok(Interaction {
+            method: texto("method")?,
+            path: texto("path")?,
+            request: Some(value["request"].clone()).filter(|r| !r.is_null()),
+            status,
+            response: value["response"].clone(),
+        })
+    }
+}
+
+/// Conjunto de interações, gravadas ou carregadas de um arquivo.
+#[derive(Debug, Clone, Default)]
+pub struct Cassette {
+    rules: Rules,
+    interactions: Vec<Interaction>,
+    usadas: Vec<bool>,
+}
+
+impl Cassette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Regras de redação aplicadas aos corpos ao gravar e ao comparar
+    /// requisições na reprodução.
+    pub fn redact_with(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn interactions(&self) -> &[Interaction] {
+        &self.interactions
+    }
+
+    /// Grava uma interação, com os corpos já redigidos.
+    pub fn record(
+        &mut self,
+        method: &str,
+        path: &str,
+        request: Option<&Value>,
+        status: u16,
+        response: &Value,
+    ) {
+        self.interactions.push(Interaction {
+            method: method.to_ascii_uppercase(),
+            path: path.to_string(),
+            request: request.map(|r| self.rules.apply(r)),
+            status,
+            response: self.rules.apply(response),
+        });
+        self.usadas.push(false);
+    }
+
+    /// Resposta gravada para a requisição: a próxima ainda não reproduzida
+    /// com o mesmo método, caminho e corpo; esgotadas, repete a última.
+    pub fn replay(
+        &mut self,
+        method: &str,
+        path: &str,
+        request: Option<&Value>,
+    ) -> Option<(u16, &Value)> {
+        let corpo = request.map(|r| canonical::to_canonical_string(&self.rules.apply(r)));
+        let candidatas: Vec<usize> = (0..self.interactions.len())
+            .filter(|&i| {
+                let gravada = &self.interactions[i];
+                gravada.method.eq_ignore_ascii_case(method)
+                    && gravada.path == path
+                    && gravada.request.as_ref().map(canonical::to_canonical_string) == corpo
+            })
+            .collect();
+        let escolhida = candidatas
+            .iter()
+            .copied()
+            .find(|&i| !self.usadas[i])
+            .or(candidatas.last().copied())?;
+        self.usadas[escolhida] = true;
+        let gravada = &self.interactions[escolhida];
+        Some((gravada.status, &gravada.response))
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        map.insert(
+            "interactions".into(),
+            Value::Array(
+                self.interactions
+                    .iter()
+                    .map(Interaction::to_value)
+                    .collect(),
+            ),
+        );
+        Value::Object(map)
+    }
+
+    pub fn from_value(value: &Value) -> serde_json::Result<Self> {
+        let interactions = value["interactions"]
+            .as_array()
+            .ok_or_else(|| erro("cassette sem `interactions`".into()))?
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Interaction::from_value(i, v))
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        Ok(Cassette {
+            rules: Rules::new(),
+            usadas: vec![false; interactions.len()],
+            interactions,
+        })
+    }
+
+    /// Grava em `path` (JSON indentado, chaves ordenadas).
+    pub fn save(&self, path: &Path) -> serde_json::Result<()> {
+        let mut texto = serde_json::to_string_pretty(&self.to_value())?;
+        texto.push('\n');
+        std::fs::write(path, texto).map_err(serde_json::Error::io)
+    }
+
+    /// Carrega de `path`; use [`redact_with`](Self::redact_with) com as
+    /// mesmas regras da gravação para que as requisições casem.
+    pub fn load(path: &Path) -> serde_json::Result<Self> {
+        let texto = std::fs::read_to_string(path).map_err(serde_json::Error::io)?;
+        Self::from_value(&serde_json::from_str(&texto)?)
+    }
+
+    /// Servidor de fixtures que responde as interações na ordem gravada.
+    #[cfg(feature = "dev")]
+    pub fn to_mock_server(&self) -> crate::mock_server::MockServer {
+        use crate::mock_server::{MockServer, Response};
+        self.interactions
+            .iter()
+            .fold(MockServer::new(), |servidor, i| {
+                servidor.route(
+                    &i.method,
+                    &i.path,
+                    Response::json(i.response.clone()).status(i.status),
+                )
+            })
+    }
+}
@@ -0,0 +1,333 @@
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Fontes de tempo, ids e sequência usadas por `now!()`, `today!()`,
+// `new_uuid!()` e `seq!()`. Em produção vêm do sistema; em testes,
+// `set_clock`/`set_id_gen`/`set_sequence` trocam por fontes determinísticas,
+// para que payloads com timestamps e ids fiquem estáveis em snapshots.
+
+// now!() -> "2024-05-01T12:00:00.000Z"
+#[macro_export]
+macro_rules! now {
+    () => {
+        $crate::clock::Now
+    };
+}
+
+// today!() -> "2024-05-01" (UTC)
+#[macro_export]
+macro_rules! today {
+    () => {
+        $crate::clock::Today
+    };
+}
+
+// seq!() -> 1, 2, 3, ... (contador atômico do processo)
+#[macro_export]
+macro_rules! seq {
+    () => {
+        $crate::clock::Seq
+    };
+}
+
+// new_uuid!() -> UUID v4 em texto
+#[macro_export]
+macro_rules! new_uuid {
+    () => {
+        $crate::clock::NewUuid
+    };
+}
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+pub trait IdGen: Send + Sync {
+    /// UUID em texto, minúsculo e com hífens.
+    fn new_id(&self) -> String;
+}
+
+/// Relógio do sistema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Relógio parado em um instante, que avança `step` a cada leitura.
+#[derive(Debug)]
+pub struct FixedClock {
+    inicio: SystemTime,
+    step: Duration,
+    leituras: AtomicU64,
+}
+
+impl FixedClock {
+    /// Parado em `unix_millis` (milissegundos desde a época).
+    pub fn at(unix_millis: u64) -> Self {
+        FixedClock {
+            inicio: UNIX_EPOCH + Duration::from_millis(unix_millis),
+            step: Duration::ZERO,
+            leituras: AtomicU64::new(0),
+        }
+    }
+
+    pub fn step(mut self, step: Duration) -> Self {
+        self.step = step;
+        self
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        let n = self.leituras.fetch_add(1, Ordering::SeqCst);
+        self.inicio + self.step * u32::try_from(n).unwrap_or(u32::MAX)
+    }
+}
+
+fn uuid_v4(bytes: [u8; 16]) -> String {
+    let mut b = bytes;
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h: String = b.iter().map(|x| format!("{x:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &h[..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..]
+    )
+}
+
+/// UUIDs v4 aleatórios (semente de `RandomState`, sem dependências).
+#[derive(Debug, Default)]
+pub struct RandomIds {
+    contador: AtomicU64,
+}
+
+impl IdGen for RandomIds {
+    fn new_id(&self) -> String {
+        let n = self.contador.fetch_add(1, Ordering::SeqCst);
+        let metade = |i: u64| {
+            let mut h = RandomState::new().build_hasher();
+            h.write_u64(n);
+            h.write_u64(i);
+            h.finish().to_be_bytes()
+        };
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&metade(0));
+        bytes[8..].copy_from_slice(&metade(1));
+        uuid_v4(bytes)
+    }
+}
+
+/// UUIDs v4 válidos e previsíveis: o n-ésimo id carrega `n` nos últimos
+/// bytes (`00000000-0000-4000-8000-000000000001`, ...).
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    contador: AtomicU64,
+}
+
+impl IdGen for SequentialIds {
+    fn new_id(&self) -> String {
+        let n = self.contador.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut bytes = [0; 16];
+        bytes[8..].copy_from_slice(&n.to_be_bytes());
+        uuid_v4(bytes)
+    }
+}
+
+/// Formato de `now!()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimePolicy {
+    /// `2024-05-01T12:00:00.000Z`
+    #[default]
+    Rfc3339Millis,
+    /// `2024-05-01T12:00:00Z`
+    Rfc3339Seconds,
+    /// Segundos desde a época, inteiro.
+    UnixSeconds,
+    /// Milissegundos desde a época, inteiro.
+    UnixMillis,
+}
+
+static RELOGIO: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+static POLITICA: RwLock<TimePolicy> = RwLock::new(TimePolicy::Rfc3339Millis);
+static SEQUENCIA: AtomicU64 = AtomicU64::new(1);
+static IDS: RwLock<Option<Arc<dyn IdGen>>> = RwLock::new(None);
+
+// Trocas só da thread atual (`with_clock`/`with_id_gen`), que vencem as do
+// processo: testes em paralelo não disputam o relógio global
+thread_local! {
+    static RELOGIO_LOCAL: RefCell<Option<Arc<dyn Clock>>> = const { RefCell::new(None) };
+    static IDS_LOCAL: RefCell<Option<Arc<dyn IdGen>>> = const { RefCell::new(None) };
+}
+
+/// Troca o relógio do processo.
+pub fn set_clock(clock: impl Clock + 'static) {
+    *RELOGIO.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(clock));
+}
+
+/// Troca o gerador de ids do processo.
+pub fn set_id_gen(ids: impl IdGen + 'static) {
+    *IDS.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(ids));
+}
+
+/// Executa `f` com `clock` na thread atual.
+pub fn with_clock<T>(clock: impl Clock + 'static, f: impl FnOnce() -> T) -> T {
+    crate::scoped::trocar(&RELOGIO_LOCAL, Some(Arc::new(clock)), f).0
+}
+
+/// Executa `f` com `ids` na thread atual.
+pub fn with_id_gen<T>(ids: impl IdGen + 'static, f: impl FnOnce() -> T) -> T {
+    crate::scoped::trocar(&IDS_LOCAL, Some(Arc::new(ids)), f).0
+}
+
+/// Define o formato de `now!()` para o processo.
+pub fn set_time_policy(policy: TimePolicy) {
+    *POLITICA.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Próximo valor que `seq!()` vai gerar.
+pub fn set_sequence(next: u64) {
+    SEQUENCIA.store(next, Ordering::SeqCst);
+}
+
+/// Volta para o relógio do sistema, ids aleatórios, o formato padrão e a
+/// sequência a partir de 1.
+pub fn reset() {
+    *RELOGIO.write().unwrap_or_else(|e| e.into_inner()) = None;
+    *IDS.write().unwrap_or_else(|e| e.into_inner()) = None;
+    set_time_policy(TimePolicy::default());
+    set_sequence(1);
+}
+
+/// Próximo número da sequência do processo.
+pub fn next_seq() -> u64 {
+    SEQUENCIA.fetch_add(1, Ordering::SeqCst)
+}
+
+/// Instante atual segundo o relógio configurado.
+pub fn now() -> SystemTime {
+    // Chamado fora do lock: o relógio pode chamar `set_clock`
+    let relogio = RELOGIO_LOCAL
+        .with(|r| r.borrow().clone())
+        .or_else(|| RELOGIO.read().unwrap_or_else(|e| e.into_inner()).clone());
+    match relogio {
+        Some(relogio) => relogio.now(),
+        None => SystemTime::now(),
+    }
+}
+
+/// Novo id segundo o gerador configurado.
+pub fn new_id() -> String {
+    static PADRAO: OnceLock<RandomIds> = OnceLock::new();
+    let ids = IDS_LOCAL
+        .with(|i| i.borrow().clone())
+        .or_else(|| IDS.read().unwrap_or_else(|e| e.into_inner()).clone());
+    if let Some(ids) = ids {
+        return ids.new_id();
+    }
+    PADRAO.get_or_init(RandomIds::default).new_id()
+}
+
+// Dias desde 1970-01-01 -> (ano, mês, dia), algoritmo de Howard Hinnant
+fn civil(dias: i64) -> (i64, u32, u32) {
+    let z = dias + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let dia = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let mes = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let ano = yoe + era * 400 + i64::from(mes <= 2);
+    (ano, mes, dia)
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_millis() as i64,
+        Err(e) => -(e.duration().as_millis() as i64),
+    }
+}
+
+/// Data UTC `2024-05-01`.
+pub fn date(time: SystemTime) -> String {
+    let (ano, mes, dia) = civil(unix_millis(time).div_euclid(86_400_000));
+    format!("{ano:04}-{mes:02}-{dia:02}")
+}
+
+/// RFC 3339 em UTC com milissegundos (`2024-05-01T12:00:00.000Z`).
+pub fn rfc3339(time: SystemTime) -> String {
+    let millis = unix_millis(time);
+    let (segundos, ms) = (millis.div_euclid(1000), millis.rem_euclid(1000));
+    let resto = segundos.rem_euclid(86_400);
+    format!(
+        "{}T{:02}:{:02}:{:02}.{ms:03}Z",
+        date(time),
+        resto / 3600,
+        resto % 3600 / 60,
+        resto % 60
+    )
+}
+
+/// Marcador de `now!()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Now;
+
+impl Serialize for Now {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let agora = now();
+        match *POLITICA.read().unwrap_or_else(|e| e.into_inner()) {
+            TimePolicy::Rfc3339Millis => serializer.serialize_str(&rfc3339(agora)),
+            TimePolicy::Rfc3339Seconds => {
+                let texto = rfc3339(agora);
+                serializer.serialize_str(&format!("{}Z", &texto[..texto.len() - 5]))
+            }
+            TimePolicy::UnixSeconds => {
+                serializer.serialize_i64(unix_millis(agora).div_euclid(1000))
+            }
+            TimePolicy::UnixMillis => serializer.serialize_i64(unix_millis(agora)),
+        }
+    }
+}
+
+/// Marcador de `today!()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Today;
+
+impl Serialize for Today {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date(now()))
+    }
+}
+
+/// Marcador de `seq!()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seq;
+
+impl Serialize for Seq {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(next_seq())
+    }
+}
+
+/// Marcador de `new_uuid!()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewUuid;
+
+impl Serialize for NewUuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&new_id())
+    }
+}
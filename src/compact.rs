@@ -0,0 +1,20 @@
+use serde_json::Value;
+
+// Saída sem nulls explícitos, para APIs que rejeitam `null`: campos cujo
+// valor é `null` somem em qualquer profundidade. Itens `null` de arrays são
+// mantidos, para não deslocar posições. Via `drop_nulls` ou
+// `json_safe!(compact { ... })`.
+
+/// Remove, recursivamente, os campos de objetos com valor `null`.
+pub fn drop_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, drop_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(itens) => Value::Array(itens.into_iter().map(drop_nulls).collect()),
+        outro => outro,
+    }
+}
@@ -0,0 +1,162 @@
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::inspect;
+
+// Contrato inferido dos payloads produzidos: para cada caminho (`$.a.b`,
+// `$.itens[]`) os tipos JSON observados. Um snapshot gravado em disco é
+// comparado com o contrato atual para pegar mudanças que quebram
+// consumidores (campo removido, tipo trocado).
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Caminhos e tipos observados em uma ou mais amostras.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Contract {
+    paths: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Contract {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acumula os caminhos/tipos de uma amostra.
+    pub fn observe(mut self, sample: &Value) -> Self {
+        self.percorrer("$".to_string(), sample);
+        self
+    }
+
+    fn percorrer(&mut self, caminho: String, value: &Value) {
+        self.paths
+            .entry(caminho.clone())
+            .or_default()
+            .insert(inspect::type_name(value).to_string());
+        match value {
+            Value::Object(map) => {
+                for (chave, filho) in map {
+                    self.percorrer(format!("{caminho}.{chave}"), filho);
+                }
+            }
+            Value::Array(itens) => {
+                for item in itens {
+                    self.percorrer(format!("{caminho}[]"), item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `{ caminho: [tipos...] }`, o formato do snapshot.
+    pub fn to_value(&self) -> Value {
+        Value::Object(
+            self.paths
+                .iter()
+                .map(|(caminho, tipos)| {
+                    let tipos: Vec<&str> = tipos.iter().map(String::as_str).collect();
+                    (caminho.clone(), Value::from(tipos))
+                })
+                .collect::<Map<_, _>>(),
+        )
+    }
+
+    pub fn from_value(value: &Value) -> serde_json::Result<Self> {
+        let Value::Object(map) = value else {
+            return Err(erro("snapshot de contrato deve ser um objeto".into()));
+        };
+        let mut paths = BTreeMap::new();
+        for (caminho, tipos) in map {
+            let tipos = tipos
+                .as_array()
+                .and_then(|t| t.iter().map(|v| v.as_str().map(String::from)).collect())
+                .ok_or_else(|| erro(format!("{caminho}: esperado array de tipos")))?;
+            paths.insert(caminho.clone(), tipos);
+        }
+        Ok(Contract { paths })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Caminho que existia e sumiu (quebra).
+    Removed { path: String },
+    /// Tipos novos que o consumidor não conhecia (quebra).
+    TypeChanged {
+        path: String,
+        before: Vec<String>,
+        after: Vec<String>,
+    },
+    /// Caminho novo (compatível).
+    Added { path: String },
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        !matches!(self, Change::Added { .. })
+    }
+}
+
+/// Diferenças de `current` em relação a `baseline`. Um tipo só conta como
+/// mudança se não era aceito antes (`integer` cabe em `number`; deixar de
+/// produzir `null` é compatível).
+pub fn compare(baseline: &Contract, current: &Contract) -> Vec<Change> {
+    let mut out = Vec::new();
+    for (caminho, antes) in &baseline.paths {
+        let Some(depois) = current.paths.get(caminho) else {
+            // Filhos de um caminho removido não precisam ser listados
+            let pai_removido = out.iter().any(|c| match c {
+                Change::Removed { path } => {
+                    caminho.starts_with(path.as_str())
+                        && matches!(caminho.as_bytes().get(path.len()), Some(b'.' | b'['))
+                }
+                _ => false,
+            });
+            if !pai_removido {
+                out.push(Change::Removed {
+                    path: caminho.clone(),
+                });
+            }
+            continue;
+        };
+        let aceito = |t: &String| antes.contains(t) || (t == "integer" && antes.contains("number"));
+        if !depois.iter().all(aceito) {
+            out.push(Change::TypeChanged {
+                path: caminho.clone(),
+                before: antes.iter().cloned().collect(),
+                after: depois.iter().cloned().collect(),
+            });
+        }
+    }
+    for caminho in current.paths.keys() {
+        if !baseline.paths.contains_key(caminho) {
+            out.push(Change::Added {
+                path: caminho.clone(),
+            });
+        }
+    }
+    out
+}
+
+/// Compara `current` com o snapshot em `path` e devolve só as mudanças que
+/// quebram. Se o arquivo não existe (primeira execução) ou se a variável
+/// `UPDATE_CONTRACTS` está definida, o snapshot é (re)gravado.
+pub fn check_snapshot(path: &Path, current: &Contract) -> serde_json::Result<Vec<Change>> {
+    let gravar = || {
+        let mut texto = serde_json::to_string_pretty(&current.to_value())?;
+        texto.push('\n');
+        std::fs::write(path, texto).map_err(serde_json::Error::io)
+    };
+    if std::env::var_os("UPDATE_CONTRACTS").is_some() || !path.exists() {
+        gravar()?;
+        return Ok(Vec::new());
+    }
+    let texto = std::fs::read_to_string(path).map_err(serde_json::Error::io)?;
+    let baseline = Contract::from_value(&serde_json::from_str(&texto)?)?;
+    Ok(compare(&baseline, current)
+        .into_iter()
+        .filter(Change::is_breaking)
+        .collect())
+}
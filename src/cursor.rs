@@ -0,0 +1,49 @@
+use serde_json::Value;
+
+use crate::jws::{Hs256, Signer};
+use crate::{base64, canonical};
+
+// Cursores de paginação com estado estruturado (`{ after: 42, sort: "id" }`):
+// base64url da forma canônica, para que o mesmo estado gere o mesmo cursor
+// em qualquer serviço. Com segredo, o cursor leva um HMAC-SHA256
+// (`estado.assinatura`) e adulterações são rejeitadas na leitura.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Cursor sem proteção: o cliente consegue ler e alterar o estado.
+pub fn encode(state: &Value) -> String {
+    base64::encode_url(canonical::to_canonical_string(state).as_bytes())
+}
+
+/// Estado de um cursor de [`encode`].
+pub fn decode(cursor: &str) -> serde_json::Result<Value> {
+    let bytes = base64::decode_url(cursor.trim())
+        .ok_or_else(|| erro("cursor: base64url inválido".into()))?;
+    serde_json::from_slice(&bytes).map_err(|e| erro(format!("cursor: JSON inválido: {e}")))
+}
+
+/// Cursor assinado com HMAC-SHA256 sobre o estado codificado.
+pub fn encode_signed(state: &Value, secret: &[u8]) -> String {
+    let estado = encode(state);
+    let assinatura = Hs256::new(secret)
+        .sign(estado.as_bytes())
+        .unwrap_or_default();
+    format!("{estado}.{}", base64::encode_url(&assinatura))
+}
+
+/// Estado de um cursor de [`encode_signed`]; assinatura ausente ou que não
+/// confere é erro.
+pub fn decode_signed(cursor: &str, secret: &[u8]) -> serde_json::Result<Value> {
+    let (estado, assinatura) = cursor
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| erro("cursor: assinatura ausente".into()))?;
+    let assinatura = base64::decode_url(assinatura)
+        .ok_or_else(|| erro("cursor: assinatura em base64url inválido".into()))?;
+    if !Hs256::new(secret).verify(estado.as_bytes(), &assinatura) {
+        return Err(erro("cursor: assinatura inválida".into()));
+    }
+    decode(estado)
+}
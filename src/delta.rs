@@ -0,0 +1,485 @@
+use serde_json::{Map, Value};
+
+// Sincronização incremental de documentos: `diff` gera as operações RFC 6902
+// (JSON Patch) entre duas versões, `feed` as embala em `{ since, ops }` para
+// endpoints de long-polling/delta sync, e o cliente aplica o feed com
+// `apply_feed`. `Tracked` guarda o histórico de versões do lado do servidor.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+fn escapar(segmento: &str) -> String {
+    segmento.replace('~', "~0").replace('/', "~1")
+}
+
+fn op(nome: &str, path: &str, value: Option<Value>) -> Value {
+    let mut map = Map::new();
+    map.insert("op".into(), Value::from(nome));
+    map.insert("path".into(), Value::from(path));
+    if let Some(v) = value {
+        map.insert("value".into(), v);
+    }
+    Value::Object(map)
+}
+
+/// Operações JSON Patch que levam `old` a `new`. Arrays são comparados
+/// posição a posição; itens a mais viram `add` e a menos, `remove` (do fim
+/// para o começo).
+pub fn diff(old: &Value, new: &Value) -> Vec<Value> {
+    let mut ops = Vec::new();
+    comparar(old, new, String::new(), &mut ops);
+    ops
+}
+
+fn comparar(old: &Value, new: &Value, caminho: String, ops: &mut Vec<Value>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, va) in a {
+                let filho = format!("{caminho}/{}", escapar(k));
+                match b.get(k) {
+                    Some(vb) => comparar(va, vb, filho, ops),
+                    None => ops.push(op("remove", &filho, None)),
+                }
+            }
+            for (k, vb) in b {
+                if !a.contains_key(k) {
+                    ops.push(op(
+                        "add",
+                        &format!("{caminho}/{}", escapar(k)),
+                        Some(vb.clone()),
+                    ));
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (va, vb)) in a.iter().zip(b).enumerate() {
+                comparar(va, vb, format!("{caminho}/{i}"), ops);
+            }
+            for (i, vb) in b.iter().enumerate().skip(a.len()) {
+                ops.push(op("add", &format!("{caminho}/{i}"), Some(vb.clone())));
+            }
+            for i in (b.len()..a.len()).rev() {
+                ops.push(op("remove", &format!("{caminho}/{i}"), None));
+            }
+        }
+        (a, b) if a == b => {}
+        (_, b) => ops.push(op("replace", &caminho, Some(b.clone()))),
+    }
+}
+
+// Pai e último segmento de `pointer`
+fn separar(pointer: &str) -> serde_json::Result<(&str, String)> {
+    let (pai, ultimo) = pointer
+        .rsplit_once('/')
+        .ok_or_else(|| erro(format!("patch: caminho inválido: {pointer:?}")))?;
+    Ok((pai, ultimo.replace("~1", "/").replace("~0", "~")))
+}
+
+fn remover(doc: &mut Value, pointer: &str) -> serde_json::Result<Value> {
+    if pointer.is_empty() {
+        return Ok(std::mem::take(doc));
+    }
+    let ausente = || erro(format!("patch: caminho ausente: {pointer:?}"));
+    let (pai, chave) = separar(pointer)?;
+    match doc.pointer_mut(pai) {
+        Some(Value::Object(map)) => map.remove(&chave).ok_or_else(ausente),
+        Some(Value::Array(itens)) => match chave.parse::<usize>() {
+            Ok(i) if i < itens.len() => Ok(itens.remove(i)),
+            _ => Err(ausente()),
+        },
+        _ => Err(ausente()),
+    }
+}
+
+fn adicionar(doc: &mut Value, pointer: &str, valor: Value) -> serde_json::Result<()> {
+    if pointer.is_empty() {
+        *doc = valor;
+        return Ok(());
+    }
+    let (pai, chave) = separar(pointer)?;
+    match doc.pointer_mut(pai) {
+        Some(Value::Object(map)) => {
+            map.insert(chave, valor);
+            Ok(())
+        }
+        Some(Value::Array(itens)) => {
+            let i = match chave.as_str() {
+                "-" => itens.len(),
+                _ => chave
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|i| *i <= itens.len())
+                    .ok_or_else(|| erro(format!("patch: índice inválido: {pointer:?}")))?,
+            };
+            itens.insert(i, valor);
+            Ok(())
+        }
+        _ => Err(erro(format!("patch: pai ausente: {pointer:?}"))),
+    }
+}
+
+/// Aplica operações RFC 6902 (`add`, `remove`, `replace`, `move`, `copy`,
+/// `test`) em ordem. Em caso de erro, `doc` fica como estava.
+pub fn apply_patch(doc: &mut Value, ops: &[Value]) -> serde_json::Result<()> {
+    let mut copia = doc.clone();
+    for (i, operacao) in ops.iter().enumerate() {
+        let campo = |nome: &str| {
+            operacao
+                .get(nome)
+                .and_then(Value::as_str)
+                .ok_or_else(|| erro(format!("patch: operação {i} sem `{nome}`")))
+        };
+        let valor = || {
+            operacao
+                .get("value")
+                .cloned()
+                .ok_or_else(|| erro(format!("patch: operação {i} sem `value`")))
+        };
+        let path = campo("path")?;
+        match campo("op")? {
+            "add" => adicionar(&mut copia, path, valor()?)?,
+            "remove" => {
+                remover(&mut copia, path)?;
+            }
+            "replace" => {
+                remover(&mut copia, path)?;
+                adicionar(&mut copia, path, valor()?)?;
+            }
+            "move" => {
+                let from = campo("from")?;
+                let movido = remover(&mut copia, from)?;
+                adicionar(&mut copia, path, movido)?;
+            }
+            "copy" => {
+                let from = campo("from")?;
+                let copiado = copia
+                    .pointer(from)
+                    .cloned()
+                    .ok_or_else(|| erro(format!("patch: caminho ausente: {from:?}")))?;
+                adicionar(&mut copia, path, copiado)?;
+            }
+            "test" => {
+                if copia.pointer(path) != Some(&valor()?) {
+                    return Err(erro(format!("patch: test falhou em {path:?}")));
+                }
+            }
+            outra => return Err(erro(format!("patch: operação desconhecida: {outra:?}"))),
+        }
+    }
+    *doc = copia;
+    Ok(())
+}
+
+/// Feed `{ since, ops }`: o que mudou de `old` (a versão `since`) a `new`.
+pub fn feed(since: impl Into<Value>, old: &Value, new: &Value) -> Value {
+    let mut map = Map::new();
+    map.insert("since".into(), since.into());
+    map.insert("ops".into(), Value::Array(diff(old, new)));
+    Value::Object(map)
+}
+
+/// Lado do cliente: aplica um feed de [`feed`] sobre `doc`.
+pub fn apply_feed(doc: &mut Value, feed: &Value) -> serde_json::Result<()> {
+    let ops = feed
+        .get("ops")
+        .and_then(Value::as_array)
+        .ok_or_else(|| erro("feed: `ops` ausente ou não é array".into()))?;
+    apply_patch(doc, ops)
+}
+
+/// Histórico de versões de um documento no servidor. Versões começam em 0
+/// e só as `capacity` mais recentes ficam guardadas.
+#[derive(Debug, Clone)]
+pub struct Tracked {
+    // (versão, documento), da mais antiga para a atual
+    versoes: Vec<(u64, Value)>,
+    capacidade: usize,
+}
+
+impl Tracked {
+    pub fn new(doc: Value) -> Self {
+        Tracked {
+            versoes: vec![(0, doc)],
+            capacidade: 64,
+        }
+    }
+
+    /// Quantas versões guardar (mínimo 1).
+    pub fn capacity(mut self, versions: usize) -> Self {
+        self.capacidade = versions.max(1);
+        self.podar();
+        self
+    }
+
+    fn podar(&mut self) {
+        let excesso = self.versoes.len().saturating_sub(self.capacidade);
+        self.versoes.drain(..excesso);
+    }
+
+    pub fn version(&self) -> u64 {
+        self.versoes.last().map_or(0, |(v, _)| *v)
+    }
+
+    pub fn current(&self) -> &Value {
+        &self.versoes.last().expect("sempre há uma versão").1
+    }
+
+    /// Registra uma nova versão, se o documento mudou; devolve a versão atual.
+    pub fn update(&mut self, doc: Value) -> u64 {
+        if doc != *self.current() {
+            let proxima = self.version() + 1;
+            self.versoes.push((proxima, doc));
+            self.podar();
+        }
+        self.version()
+    }
+
+    /// Feed da versão `since` até a atual; `None` se `since` não está mais
+    /// no histórico (o cliente precisa baixar o documento inteiro).
+    pub fn feed_since(&self, since: u64) -> Option<Value> {
+        let (_, antigo) = self.versoes.iter().find(|(v, _)| *v == since)?;
+        let mut saida = feed(since, antigo, self.current());
+        saida["version"] = Value::from(self.version());
+        Some(saida)
+    }
+}
+
+// Delta binário para documentos grandes: o cliente manda o `Manifest` com o
+// hash de cada subárvore (até uma profundidade); o servidor responde só com
+// as subárvores que mudaram. Formato: `JSD1`, modo (0 = documento inteiro,
+// 1 = delta) e, no delta, o hash da base, o hash do resultado e as
+// operações `[tipo u8][ponteiro][valor]`, com tamanhos em u32 LE.
+
+const MAGIC_DELTA: &[u8; 4] = b"JSD1";
+const MAGIC_MANIFEST: &[u8; 4] = b"JSM1";
+
+fn hash(value: &Value) -> [u8; 32] {
+    crate::digest::sha256(crate::canonical::to_canonical_string(value).as_bytes())
+}
+
+fn escrever_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+// Leitor sequencial do formato binário
+struct Leitor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Leitor<'a> {
+    fn fixo<const N: usize>(&mut self) -> serde_json::Result<[u8; N]> {
+        if self.bytes.len() < N {
+            return Err(erro("delta: dados truncados".into()));
+        }
+        let (cabeca, resto) = self.bytes.split_at(N);
+        self.bytes = resto;
+        Ok(cabeca.try_into().expect("tamanho conferido"))
+    }
+
+    fn bloco(&mut self) -> serde_json::Result<&'a [u8]> {
+        let tamanho = u32::from_le_bytes(self.fixo()?) as usize;
+        if self.bytes.len() < tamanho {
+            return Err(erro("delta: dados truncados".into()));
+        }
+        let (cabeca, resto) = self.bytes.split_at(tamanho);
+        self.bytes = resto;
+        Ok(cabeca)
+    }
+
+    fn texto(&mut self) -> serde_json::Result<&'a str> {
+        std::str::from_utf8(self.bloco()?).map_err(|_| erro("delta: UTF-8 inválido".into()))
+    }
+}
+
+/// Hashes das subárvores de um documento, por JSON Pointer, descendo até
+/// `depth` níveis de objetos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    depth: usize,
+    // ponteiro -> (é objeto, hash)
+    nos: std::collections::BTreeMap<String, (bool, [u8; 32])>,
+}
+
+impl Manifest {
+    pub fn of(doc: &Value, depth: usize) -> Self {
+        let mut manifest = Manifest {
+            depth,
+            nos: Default::default(),
+        };
+        manifest.registrar(doc, String::new(), 0);
+        manifest
+    }
+
+    fn registrar(&mut self, value: &Value, caminho: String, nivel: usize) {
+        self.nos
+            .insert(caminho.clone(), (value.is_object(), hash(value)));
+        if let Value::Object(map) = value
+            && nivel < self.depth
+        {
+            for (k, v) in map {
+                self.registrar(v, format!("{caminho}/{}", escapar(k)), nivel + 1);
+            }
+        }
+    }
+
+    // Filhos diretos de `caminho` registrados no manifesto
+    fn filhos<'a>(&'a self, caminho: &'a str) -> impl Iterator<Item = &'a str> {
+        let prefixo = format!("{caminho}/");
+        self.nos
+            .range(prefixo.clone()..)
+            .map(|(k, _)| k.as_str())
+            .take_while(move |k| k.starts_with(&prefixo))
+            .filter(move |k| !k[caminho.len() + 1..].contains('/'))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC_MANIFEST.to_vec();
+        out.extend_from_slice(&(self.depth as u32).to_le_bytes());
+        out.extend_from_slice(&(self.nos.len() as u32).to_le_bytes());
+        for (caminho, (objeto, h)) in &self.nos {
+            escrever_bytes(&mut out, caminho.as_bytes());
+            out.push(u8::from(*objeto));
+            out.extend_from_slice(h);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> serde_json::Result<Self> {
+        let mut leitor = Leitor { bytes };
+        if leitor.fixo::<4>()? != *MAGIC_MANIFEST {
+            return Err(erro("manifesto: cabeçalho inválido".into()));
+        }
+        let depth = u32::from_le_bytes(leitor.fixo()?) as usize;
+        let total = u32::from_le_bytes(leitor.fixo()?);
+        let mut nos = std::collections::BTreeMap::new();
+        for _ in 0..total {
+            let caminho = leitor.texto()?.to_string();
+            let [objeto] = leitor.fixo()?;
+            nos.insert(caminho, (objeto == 1, leitor.fixo()?));
+        }
+        if !nos.contains_key("") {
+            return Err(erro("manifesto: sem a raiz".into()));
+        }
+        Ok(Manifest { depth, nos })
+    }
+}
+
+enum OpBinaria {
+    Set(String, Value),
+    Remove(String),
+}
+
+fn mudancas(
+    base: &Manifest,
+    value: &Value,
+    caminho: String,
+    nivel: usize,
+    ops: &mut Vec<OpBinaria>,
+) {
+    let anterior = base.nos.get(&caminho);
+    if anterior.is_some_and(|(_, h)| *h == hash(value)) {
+        return;
+    }
+    match value {
+        Value::Object(map) if anterior.is_some_and(|(objeto, _)| *objeto) && nivel < base.depth => {
+            for filho in base.filhos(&caminho) {
+                let chave = filho[caminho.len() + 1..]
+                    .replace("~1", "/")
+                    .replace("~0", "~");
+                if !map.contains_key(&chave) {
+                    ops.push(OpBinaria::Remove(filho.to_string()));
+                }
+            }
+            for (k, v) in map {
+                mudancas(base, v, format!("{caminho}/{}", escapar(k)), nivel + 1, ops);
+            }
+        }
+        _ => ops.push(OpBinaria::Set(caminho, value.clone())),
+    }
+}
+
+/// Delta binário de `new` em relação ao documento descrito por `base`.
+/// Se o delta não for menor que o documento inteiro, leva o documento.
+pub fn binary_delta(base: &Manifest, new: &Value) -> Vec<u8> {
+    let mut ops = Vec::new();
+    mudancas(base, new, String::new(), 0, &mut ops);
+
+    let mut delta = MAGIC_DELTA.to_vec();
+    delta.push(1);
+    delta.extend_from_slice(&base.nos[""].1);
+    delta.extend_from_slice(&hash(new));
+    for op in &ops {
+        match op {
+            OpBinaria::Set(caminho, valor) => {
+                delta.push(1);
+                escrever_bytes(&mut delta, caminho.as_bytes());
+                escrever_bytes(
+                    &mut delta,
+                    crate::canonical::to_canonical_string(valor).as_bytes(),
+                );
+            }
+            OpBinaria::Remove(caminho) => {
+                delta.push(2);
+                escrever_bytes(&mut delta, caminho.as_bytes());
+            }
+        }
+    }
+
+    let inteiro = crate::canonical::to_canonical_string(new);
+    if delta.len() >= inteiro.len() + 5 {
+        let mut out = MAGIC_DELTA.to_vec();
+        out.push(0);
+        out.extend_from_slice(inteiro.as_bytes());
+        return out;
+    }
+    delta
+}
+
+/// Aplica um delta de [`binary_delta`]. O delta só vale para a mesma base:
+/// se `doc` não for o documento do manifesto, ou o resultado não conferir,
+/// é erro e `doc` fica como estava.
+pub fn apply_binary_delta(doc: &mut Value, delta: &[u8]) -> serde_json::Result<()> {
+    let mut leitor = Leitor { bytes: delta };
+    if leitor.fixo::<4>()? != *MAGIC_DELTA {
+        return Err(erro("delta: cabeçalho inválido".into()));
+    }
+    match leitor.fixo::<1>()? {
+        [0] => {
+            *doc = serde_json::from_slice(leitor.bytes)?;
+            return Ok(());
+        }
+        [1] => {}
+        [modo] => return Err(erro(format!("delta: modo desconhecido: {modo}"))),
+    }
+    if leitor.fixo::<32>()? != hash(doc) {
+        return Err(erro(
+            "delta: documento diferente da base do manifesto".into(),
+        ));
+    }
+    let esperado = leitor.fixo::<32>()?;
+    let mut copia = doc.clone();
+    while !leitor.bytes.is_empty() {
+        let [tipo] = leitor.fixo()?;
+        let caminho = leitor.texto()?;
+        match tipo {
+            1 => {
+                // Só a raiz ou membros de objetos: `add` substitui
+                let valor: Value = serde_json::from_slice(leitor.bloco()?)?;
+                adicionar(&mut copia, caminho, valor)?;
+            }
+            2 => {
+                remover(&mut copia, caminho)?;
+            }
+            outro => return Err(erro(format!("delta: operação desconhecida: {outro}"))),
+        }
+    }
+    if hash(&copia) != esperado {
+        return Err(erro(
+            "delta: resultado não confere com o hash esperado".into(),
+        ));
+    }
+    *doc = copia;
+    Ok(())
+}
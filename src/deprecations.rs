@@ -0,0 +1,173 @@
+use serde_json::Value;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+// Campos obsoletos: caminhos em JSON Pointer (com `*` casando qualquer chave
+// ou índice) marcados como deprecated. Documentos que ainda usam esses
+// campos geram avisos, para acompanhar a remoção gradual entre serviços.
+
+/// Aviso de uso de campo obsoleto, com o caminho concreto encontrado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+struct Regra {
+    segmentos: Vec<String>,
+    mensagem: String,
+}
+
+fn segmentos(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn escapar(segmento: &str) -> String {
+    segmento.replace('~', "~0").replace('/', "~1")
+}
+
+/// Conjunto de caminhos obsoletos.
+#[derive(Debug, Clone, Default)]
+pub struct Deprecations {
+    regras: Vec<Regra>,
+}
+
+impl Deprecations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marca `pointer` (ex.: `/user/legacy_id`, `/items/*/sku`) como obsoleto.
+    pub fn deprecate(mut self, pointer: &str, message: impl Into<String>) -> Self {
+        self.regras.push(Regra {
+            segmentos: segmentos(pointer),
+            mensagem: message.into(),
+        });
+        self
+    }
+
+    /// Avisos para cada campo obsoleto presente em `doc`.
+    pub fn check(&self, doc: &Value) -> Vec<Warning> {
+        let mut out = Vec::new();
+        for regra in &self.regras {
+            buscar(
+                doc,
+                &regra.segmentos,
+                String::new(),
+                &regra.mensagem,
+                &mut out,
+            );
+        }
+        out
+    }
+}
+
+fn buscar(
+    value: &Value,
+    resto: &[String],
+    caminho: String,
+    mensagem: &str,
+    out: &mut Vec<Warning>,
+) {
+    let Some((seg, resto)) = resto.split_first() else {
+        out.push(Warning {
+            path: caminho,
+            message: mensagem.to_string(),
+        });
+        return;
+    };
+    match value {
+        Value::Object(map) => {
+            for (chave, filho) in map {
+                if seg == "*" || seg == chave {
+                    buscar(
+                        filho,
+                        resto,
+                        format!("{caminho}/{}", escapar(chave)),
+                        mensagem,
+                        out,
+                    );
+                }
+            }
+        }
+        Value::Array(itens) => {
+            for (i, filho) in itens.iter().enumerate() {
+                if seg == "*" || *seg == i.to_string() {
+                    buscar(filho, resto, format!("{caminho}/{i}"), mensagem, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+type Sink = Arc<dyn Fn(&Warning) + Send + Sync>;
+
+struct Global {
+    deprecations: RwLock<Deprecations>,
+    coletados: Mutex<Vec<Warning>>,
+    sink: RwLock<Option<Sink>>,
+}
+
+fn global() -> &'static Global {
+    static GLOBAL: OnceLock<Global> = OnceLock::new();
+    GLOBAL.get_or_init(|| Global {
+        deprecations: RwLock::new(Deprecations::new()),
+        coletados: Mutex::new(Vec::new()),
+        sink: RwLock::new(None),
+    })
+}
+
+/// Marca um caminho como obsoleto no registro global do processo.
+pub fn deprecate(pointer: &str, message: impl Into<String>) {
+    let mut regras = global()
+        .deprecations
+        .write()
+        .unwrap_or_else(|e| e.into_inner());
+    *regras = std::mem::take(&mut *regras).deprecate(pointer, message);
+}
+
+/// Função chamada a cada aviso (ex.: para logar), além da coleta.
+pub fn on_warning<F>(sink: F)
+where
+    F: Fn(&Warning) + Send + Sync + 'static,
+{
+    *global().sink.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(sink));
+}
+
+/// Confere `doc` contra o registro global, coletando os avisos, e devolve o
+/// documento intacto: `deprecations::checked(json_safe!({ ... }))`.
+pub fn checked<E>(doc: Result<Value, E>) -> Result<Value, E> {
+    let doc = doc?;
+    let avisos = global()
+        .deprecations
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .check(&doc);
+    if !avisos.is_empty() {
+        // Fora do lock: o sink pode chamar `on_warning`
+        let sink = global()
+            .sink
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(sink) = sink {
+            avisos.iter().for_each(|aviso| sink(aviso));
+        }
+        global()
+            .coletados
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(avisos);
+    }
+    Ok(doc)
+}
+
+/// Retira os avisos coletados até agora.
+pub fn take_warnings() -> Vec<Warning> {
+    std::mem::take(&mut *global().coletados.lock().unwrap_or_else(|e| e.into_inner()))
+}
@@ -0,0 +1,105 @@
+// SHA-256 (FIPS 180-4) implementado localmente: o crate não depende de
+// nenhuma biblioteca de criptografia.
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+fn comprimir(estado: &mut [u32; 8], bloco: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, palavra) in bloco.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([palavra[0], palavra[1], palavra[2], palavra[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *estado;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (s, v) in estado.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// SHA-256 de `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut estado = H0;
+    let mut blocos = data.chunks_exact(64);
+    for bloco in &mut blocos {
+        comprimir(&mut estado, bloco);
+    }
+
+    // Padding: 0x80, zeros e o tamanho em bits (big-endian, 64 bits)
+    let resto = blocos.remainder();
+    let mut final_ = [0u8; 128];
+    final_[..resto.len()].copy_from_slice(resto);
+    final_[resto.len()] = 0x80;
+    let n = if resto.len() < 56 { 64 } else { 128 };
+    final_[n - 8..n].copy_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for bloco in final_[..n].chunks_exact(64) {
+        comprimir(&mut estado, bloco);
+    }
+
+    let mut out = [0u8; 32];
+    for (i, palavra) in estado.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&palavra.to_be_bytes());
+    }
+    out
+}
+
+/// Representação hexadecimal minúscula.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// HMAC-SHA256 (RFC 2104).
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut bloco = [0u8; 64];
+    if key.len() > 64 {
+        bloco[..32].copy_from_slice(&sha256(key));
+    } else {
+        bloco[..key.len()].copy_from_slice(key);
+    }
+
+    let mut interno: Vec<u8> = bloco.iter().map(|b| b ^ 0x36).collect();
+    interno.extend_from_slice(message);
+    let mut externo: Vec<u8> = bloco.iter().map(|b| b ^ 0x5c).collect();
+    externo.extend_from_slice(&sha256(&interno));
+    sha256(&externo)
+}
@@ -0,0 +1,200 @@
+use serde_json::{Map, Value};
+
+// Builders de embeds do Discord (webhooks) com validação dos limites de
+// tamanho da API, inclusive o total de 6000 caracteres por mensagem.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+fn limitar(onde: &str, texto: &str, max: usize) -> serde_json::Result<usize> {
+    let n = texto.chars().count();
+    if n > max {
+        return Err(erro(format!("{onde}: {n} caracteres, máximo é {max}")));
+    }
+    Ok(n)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Embed {
+    title: Option<String>,
+    description: Option<String>,
+    url: Option<String>,
+    color: Option<u32>,
+    // (name, value, inline)
+    fields: Vec<(String, String, bool)>,
+    footer: Option<String>,
+    author: Option<String>,
+}
+
+impl Embed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Cor em RGB (`0xRRGGBB`).
+    pub fn color(mut self, color: u32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn field(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        inline: bool,
+    ) -> Self {
+        self.fields.push((name.into(), value.into(), inline));
+        self
+    }
+
+    pub fn footer(mut self, text: impl Into<String>) -> Self {
+        self.footer = Some(text.into());
+        self
+    }
+
+    pub fn author(mut self, name: impl Into<String>) -> Self {
+        self.author = Some(name.into());
+        self
+    }
+
+    // Retorna o embed e quantos caracteres contam para o limite total
+    fn to_value(&self, onde: &str) -> serde_json::Result<(Value, usize)> {
+        let mut total = 0;
+        let mut map = Map::new();
+
+        if let Some(title) = &self.title {
+            total += limitar(&format!("{onde}.title"), title, 256)?;
+            map.insert("title".into(), Value::from(title.as_str()));
+        }
+        if let Some(description) = &self.description {
+            total += limitar(&format!("{onde}.description"), description, 4096)?;
+            map.insert("description".into(), Value::from(description.as_str()));
+        }
+        if let Some(url) = &self.url {
+            map.insert("url".into(), Value::from(url.as_str()));
+        }
+        if let Some(color) = self.color {
+            if color > 0xFF_FFFF {
+                return Err(erro(format!("{onde}.color: fora de 0xRRGGBB")));
+            }
+            map.insert("color".into(), Value::from(color));
+        }
+        if self.fields.len() > 25 {
+            return Err(erro(format!("{onde}.fields: máximo de 25 campos")));
+        }
+        if !self.fields.is_empty() {
+            let mut campos = Vec::new();
+            for (i, (nome, valor, inline)) in self.fields.iter().enumerate() {
+                let campo = format!("{onde}.fields[{i}]");
+                if nome.is_empty() || valor.is_empty() {
+                    return Err(erro(format!("{campo}: name e value são obrigatórios")));
+                }
+                total += limitar(&format!("{campo}.name"), nome, 256)?;
+                total += limitar(&format!("{campo}.value"), valor, 1024)?;
+                let mut m = Map::new();
+                m.insert("name".into(), Value::from(nome.as_str()));
+                m.insert("value".into(), Value::from(valor.as_str()));
+                m.insert("inline".into(), Value::from(*inline));
+                campos.push(Value::Object(m));
+            }
+            map.insert("fields".into(), Value::Array(campos));
+        }
+        if let Some(footer) = &self.footer {
+            total += limitar(&format!("{onde}.footer.text"), footer, 2048)?;
+            let mut m = Map::new();
+            m.insert("text".into(), Value::from(footer.as_str()));
+            map.insert("footer".into(), Value::Object(m));
+        }
+        if let Some(author) = &self.author {
+            total += limitar(&format!("{onde}.author.name"), author, 256)?;
+            let mut m = Map::new();
+            m.insert("name".into(), Value::from(author.as_str()));
+            map.insert("author".into(), Value::Object(m));
+        }
+
+        if map.is_empty() {
+            return Err(erro(format!("{onde}: embed vazio")));
+        }
+        Ok((Value::Object(map), total))
+    }
+}
+
+/// Corpo de webhook do Discord.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookMessage {
+    content: Option<String>,
+    username: Option<String>,
+    embeds: Vec<Embed>,
+}
+
+impl WebhookMessage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    pub fn embed(mut self, embed: Embed) -> Self {
+        self.embeds.push(embed);
+        self
+    }
+
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        let mut map = Map::new();
+        if let Some(content) = &self.content {
+            limitar("content", content, 2000)?;
+            map.insert("content".into(), Value::from(content.as_str()));
+        }
+        if let Some(username) = &self.username {
+            limitar("username", username, 80)?;
+            map.insert("username".into(), Value::from(username.as_str()));
+        }
+        if self.embeds.len() > 10 {
+            return Err(erro("embeds: máximo de 10 embeds".into()));
+        }
+
+        let mut total = 0;
+        let mut embeds = Vec::new();
+        for (i, embed) in self.embeds.iter().enumerate() {
+            let (valor, n) = embed.to_value(&format!("embeds[{i}]"))?;
+            total += n;
+            embeds.push(valor);
+        }
+        if total > 6000 {
+            return Err(erro(format!(
+                "embeds: {total} caracteres no total, máximo é 6000"
+            )));
+        }
+        if !embeds.is_empty() {
+            map.insert("embeds".into(), Value::Array(embeds));
+        }
+        if map.get("content").is_none() && map.get("embeds").is_none() {
+            return Err(erro("mensagem precisa de content ou embeds".into()));
+        }
+        Ok(Value::Object(map))
+    }
+}
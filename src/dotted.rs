@@ -0,0 +1,69 @@
+use serde_json::{Map, Value};
+
+// Expansão de chaves com pontos em objetos aninhados, para APIs de
+// configuração que só aceitam JSON aninhado:
+// `{ "config.server.port": 8080 }` -> `{ "config": { "server": { "port": 8080 } } }`.
+// Opt-in: via `expand_dotted_keys` ou `json_safe!(dotted { ... })`.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+/// Expande as chaves com `.` de todos os objetos de `value` (inclusive
+/// dentro de arrays). Prefixos repetidos são mesclados; um prefixo que já
+/// tem um valor que não é objeto é erro.
+pub fn expand_dotted_keys(value: Value) -> serde_json::Result<Value> {
+    match value {
+        Value::Object(map) => {
+            let mut novo = Map::new();
+            for (chave, valor) in map {
+                let valor = expand_dotted_keys(valor)?;
+                let segmentos: Vec<&str> = chave.split('.').collect();
+                if segmentos.iter().any(|s| s.is_empty()) {
+                    return Err(erro(format!("chave com segmento vazio: {chave:?}")));
+                }
+                inserir(&mut novo, &segmentos, valor, &chave)?;
+            }
+            Ok(Value::Object(novo))
+        }
+        Value::Array(itens) => itens
+            .into_iter()
+            .map(expand_dotted_keys)
+            .collect::<serde_json::Result<_>>()
+            .map(Value::Array),
+        outro => Ok(outro),
+    }
+}
+
+fn inserir(
+    map: &mut Map<String, Value>,
+    segmentos: &[&str],
+    valor: Value,
+    chave: &str,
+) -> serde_json::Result<()> {
+    let conflito = || erro(format!("{chave:?}: conflito com um valor que não é objeto"));
+    let [primeiro, resto @ ..] = segmentos else {
+        return Ok(());
+    };
+    if resto.is_empty() {
+        match (map.get_mut(*primeiro), valor) {
+            (None, valor) => {
+                map.insert(primeiro.to_string(), valor);
+            }
+            (Some(Value::Object(existente)), Value::Object(novo)) => {
+                for (k, v) in novo {
+                    inserir(existente, &[k.as_str()], v, chave)?;
+                }
+            }
+            _ => return Err(conflito()),
+        }
+        return Ok(());
+    }
+    match map
+        .entry(primeiro.to_string())
+        .or_insert_with(|| Value::Object(Map::new()))
+    {
+        Value::Object(filho) => inserir(filho, resto, valor, chave),
+        _ => Err(conflito()),
+    }
+}
@@ -0,0 +1,64 @@
+use serde_json::Value;
+
+use crate::stream::{Limits, Parser, Posicao};
+
+// Chaves duplicadas na entrada: o serde_json fica com a última ocorrência
+// em silêncio, e dois serviços que discordam sobre qual vale (um pega a
+// primeira, outro a última) abrem brecha de segurança. Estes parses, sem os
+// limites do perfil de `untrusted`, recusam a duplicata ou avisam por
+// callback.
+
+/// Chave repetida em um objeto da entrada.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey {
+    pub key: String,
+    /// JSON Pointer da chave repetida (`/itens/0/id`).
+    pub pointer: String,
+    /// Posição da segunda ocorrência: linha e coluna a partir de 1, byte
+    /// a partir de 0.
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl DuplicateKey {
+    pub(crate) fn erro(&self) -> serde_json::Error {
+        let pos = Posicao {
+            linha: self.line,
+            coluna: self.column,
+            byte: self.offset,
+        };
+        pos.erro(format!(
+            "chave duplicada {:?} em {}",
+            self.key, self.pointer
+        ))
+    }
+}
+
+pub(crate) fn sem_limites() -> Limits {
+    Limits::new()
+        .max_bytes(usize::MAX)
+        .max_depth(usize::MAX)
+        .max_string_len(usize::MAX)
+        .max_number_len(usize::MAX)
+        .max_entries(usize::MAX)
+}
+
+/// Parse que falha na primeira chave duplicada.
+pub fn parse_strict(bytes: &[u8]) -> serde_json::Result<Value> {
+    let parser = Parser::new(bytes, &sem_limites());
+    crate::untrusted::montar(parser, |duplicada| Err(duplicada.erro()))
+}
+
+/// Parse que chama `on_duplicate` a cada chave duplicada; a última
+/// ocorrência vence, como no serde_json.
+pub fn parse_warn(
+    bytes: &[u8],
+    mut on_duplicate: impl FnMut(&DuplicateKey),
+) -> serde_json::Result<Value> {
+    let parser = Parser::new(bytes, &sem_limites());
+    crate::untrusted::montar(parser, |duplicada| {
+        on_duplicate(duplicada);
+        Ok(())
+    })
+}
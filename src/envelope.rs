@@ -0,0 +1,349 @@
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+use crate::{canonical, clock};
+
+// Envelopes de eventos de nuvem em volta de um payload montado com a macro:
+// EventBridge (PutEvents), SNS/SQS (MessageAttributes), Event Grid e o
+// envelope reenviável `{ id, attempt, payload_hash, payload }` das filas.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+fn exigir(servico: &str, campo: &str, valor: &str) -> serde_json::Result<()> {
+    if valor.trim().is_empty() {
+        return Err(erro(format!("{servico}: campo obrigatório vazio: {campo}")));
+    }
+    Ok(())
+}
+
+// Limite de tamanho de uma entrada do PutEvents
+const EVENTBRIDGE_MAX_BYTES: usize = 256 * 1024;
+
+/// Entrada de `PutEvents` do EventBridge.
+#[derive(Debug, Clone, Default)]
+pub struct EventBridge {
+    source: String,
+    detail_type: String,
+    event_bus_name: Option<String>,
+    resources: Vec<String>,
+}
+
+impl EventBridge {
+    pub fn new(source: impl Into<String>, detail_type: impl Into<String>) -> Self {
+        EventBridge {
+            source: source.into(),
+            detail_type: detail_type.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn event_bus(mut self, name: impl Into<String>) -> Self {
+        self.event_bus_name = Some(name.into());
+        self
+    }
+
+    pub fn resource(mut self, arn: impl Into<String>) -> Self {
+        self.resources.push(arn.into());
+        self
+    }
+
+    /// Embrulha `detail` (um objeto) na entrada; `Detail` vai como string JSON.
+    pub fn wrap(&self, detail: Value) -> serde_json::Result<Value> {
+        exigir("EventBridge", "Source", &self.source)?;
+        exigir("EventBridge", "DetailType", &self.detail_type)?;
+        if !detail.is_object() {
+            return Err(erro("EventBridge: Detail deve ser um objeto JSON".into()));
+        }
+
+        let mut map = Map::new();
+        map.insert("Source".into(), Value::from(self.source.as_str()));
+        map.insert("DetailType".into(), Value::from(self.detail_type.as_str()));
+        map.insert(
+            "Detail".into(),
+            Value::from(serde_json::to_string(&detail)?),
+        );
+        if let Some(bus) = &self.event_bus_name {
+            map.insert("EventBusName".into(), Value::from(bus.as_str()));
+        }
+        if !self.resources.is_empty() {
+            map.insert("Resources".into(), Value::from(self.resources.clone()));
+        }
+
+        let entrada = Value::Object(map);
+        let tamanho = serde_json::to_vec(&entrada)?.len();
+        if tamanho > EVENTBRIDGE_MAX_BYTES {
+            return Err(erro(format!(
+                "EventBridge: entrada com {tamanho} bytes excede 256 KiB"
+            )));
+        }
+        Ok(entrada)
+    }
+}
+
+/// `MessageAttributes` de SNS/SQS (no máximo 10).
+#[derive(Debug, Clone, Default)]
+pub struct MessageAttributes {
+    // (nome, DataType, StringValue)
+    atributos: Vec<(String, &'static str, String)>,
+}
+
+impl MessageAttributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn string(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.atributos.push((name.into(), "String", value.into()));
+        self
+    }
+
+    pub fn number(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.atributos
+            .push((name.into(), "Number", value.to_string()));
+        self
+    }
+
+    /// Valida nomes e valores e monta o objeto `MessageAttributes`.
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        if self.atributos.len() > 10 {
+            return Err(erro(format!(
+                "MessageAttributes: {} atributos, máximo é 10",
+                self.atributos.len()
+            )));
+        }
+
+        let mut map = Map::new();
+        for (nome, tipo, valor) in &self.atributos {
+            let nome_valido = !nome.is_empty()
+                && nome.len() <= 256
+                && !nome.starts_with('.')
+                && !nome.ends_with('.')
+                && !nome.contains("..")
+                && !nome.to_ascii_lowercase().starts_with("aws.")
+                && !nome.to_ascii_lowercase().starts_with("amazon.")
+                && nome
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+            if !nome_valido {
+                return Err(erro(format!("MessageAttributes: nome inválido: {nome:?}")));
+            }
+            if map.contains_key(nome) {
+                return Err(erro(format!("MessageAttributes: nome duplicado: {nome:?}")));
+            }
+            if *tipo == "Number" && valor.parse::<f64>().is_err() {
+                return Err(erro(format!(
+                    "MessageAttributes: {nome:?} não é um número: {valor:?}"
+                )));
+            }
+
+            let mut atributo = Map::new();
+            atributo.insert("DataType".into(), Value::from(*tipo));
+            atributo.insert("StringValue".into(), Value::from(valor.as_str()));
+            map.insert(nome.clone(), Value::Object(atributo));
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// Corpo de `Publish` do SNS; `message` é serializada como string JSON.
+pub fn sns_publish(
+    topic_arn: &str,
+    message: &Value,
+    attributes: &MessageAttributes,
+) -> serde_json::Result<Value> {
+    exigir("SNS", "TopicArn", topic_arn)?;
+    let mut map = Map::new();
+    map.insert("TopicArn".into(), Value::from(topic_arn));
+    map.insert(
+        "Message".into(),
+        Value::from(serde_json::to_string(message)?),
+    );
+    map.insert("MessageAttributes".into(), attributes.to_value()?);
+    Ok(Value::Object(map))
+}
+
+/// Corpo de `SendMessage` do SQS; `body` é serializado como string JSON.
+pub fn sqs_send(
+    queue_url: &str,
+    body: &Value,
+    attributes: &MessageAttributes,
+) -> serde_json::Result<Value> {
+    exigir("SQS", "QueueUrl", queue_url)?;
+    let mut map = Map::new();
+    map.insert("QueueUrl".into(), Value::from(queue_url));
+    map.insert(
+        "MessageBody".into(),
+        Value::from(serde_json::to_string(body)?),
+    );
+    map.insert("MessageAttributes".into(), attributes.to_value()?);
+    Ok(Value::Object(map))
+}
+
+/// Evento no schema do Event Grid.
+#[derive(Debug, Clone)]
+pub struct EventGrid {
+    id: String,
+    event_type: String,
+    subject: String,
+    event_time: String,
+    data_version: String,
+}
+
+impl EventGrid {
+    /// `event_time` deve estar em RFC 3339 (ex.: `2024-05-01T12:00:00Z`).
+    pub fn new(
+        id: impl Into<String>,
+        event_type: impl Into<String>,
+        subject: impl Into<String>,
+        event_time: impl Into<String>,
+    ) -> Self {
+        EventGrid {
+            id: id.into(),
+            event_type: event_type.into(),
+            subject: subject.into(),
+            event_time: event_time.into(),
+            data_version: "1.0".into(),
+        }
+    }
+
+    pub fn data_version(mut self, version: impl Into<String>) -> Self {
+        self.data_version = version.into();
+        self
+    }
+
+    pub fn wrap(&self, data: Value) -> serde_json::Result<Value> {
+        exigir("EventGrid", "id", &self.id)?;
+        exigir("EventGrid", "eventType", &self.event_type)?;
+        exigir("EventGrid", "subject", &self.subject)?;
+        exigir("EventGrid", "dataVersion", &self.data_version)?;
+        if !parece_rfc3339(&self.event_time) {
+            return Err(erro(format!(
+                "EventGrid: eventTime não está em RFC 3339: {:?}",
+                self.event_time
+            )));
+        }
+
+        let mut map = Map::new();
+        map.insert("id".into(), Value::from(self.id.as_str()));
+        map.insert("eventType".into(), Value::from(self.event_type.as_str()));
+        map.insert("subject".into(), Value::from(self.subject.as_str()));
+        map.insert("eventTime".into(), Value::from(self.event_time.as_str()));
+        map.insert(
+            "dataVersion".into(),
+            Value::from(self.data_version.as_str()),
+        );
+        map.insert("data".into(), data);
+        Ok(Value::Object(map))
+    }
+}
+
+// Checagem de forma: AAAA-MM-DDTHH:MM:SS[.fração](Z|±HH:MM)
+fn parece_rfc3339(s: &str) -> bool {
+    let b = s.as_bytes();
+    let digitos =
+        |r: std::ops::Range<usize>| b.get(r).is_some_and(|d| d.iter().all(u8::is_ascii_digit));
+    if b.len() < 20
+        || !digitos(0..4)
+        || b[4] != b'-'
+        || !digitos(5..7)
+        || b[7] != b'-'
+        || !digitos(8..10)
+        || !matches!(b[10], b'T' | b't')
+        || !digitos(11..13)
+        || b[13] != b':'
+        || !digitos(14..16)
+        || b[16] != b':'
+        || !digitos(17..19)
+    {
+        return false;
+    }
+    let mut resto = &s[19..];
+    if let Some(fracao) = resto.strip_prefix('.') {
+        let n = fracao.bytes().take_while(u8::is_ascii_digit).count();
+        if n == 0 {
+            return false;
+        }
+        resto = &fracao[n..];
+    }
+    match resto.as_bytes() {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', h1, h2, b':', m1, m2] => [h1, h2, m1, m2].iter().all(|d| d.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+/// Envelope reenviável: id novo (de `clock::new_id`), tentativa 1 e o hash
+/// canônico do payload.
+pub fn retryable(payload: Value) -> Value {
+    let mut map = Map::new();
+    map.insert("id".into(), Value::from(clock::new_id()));
+    map.insert("attempt".into(), Value::from(1));
+    map.insert(
+        "payload_hash".into(),
+        Value::from(canonical::canonical_hash(&payload)),
+    );
+    map.insert("payload".into(), payload);
+    Value::Object(map)
+}
+
+/// Mesmo envelope com a tentativa seguinte, para reenvio.
+pub fn next_attempt(envelope: &Value) -> serde_json::Result<Value> {
+    let atual = verify_retryable(envelope)?.attempt;
+    let mut proximo = envelope.clone();
+    proximo["attempt"] = Value::from(atual + 1);
+    Ok(proximo)
+}
+
+/// Envelope reenviável já verificado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Retryable<'a> {
+    pub id: &'a str,
+    pub attempt: u64,
+    pub payload: &'a Value,
+}
+
+/// Lado do consumidor: valida a forma do envelope e confere o hash do
+/// payload, recusando mensagens alteradas no caminho.
+pub fn verify_retryable(envelope: &Value) -> serde_json::Result<Retryable<'_>> {
+    let id = envelope["id"]
+        .as_str()
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| erro("envelope reenviável sem id".into()))?;
+    let attempt = envelope["attempt"]
+        .as_u64()
+        .filter(|a| *a >= 1)
+        .ok_or_else(|| erro(format!("envelope {id}: attempt inválido")))?;
+    let payload = envelope
+        .get("payload")
+        .ok_or_else(|| erro(format!("envelope {id}: sem payload")))?;
+    let hash = envelope["payload_hash"].as_str().unwrap_or_default();
+    if hash != canonical::canonical_hash(payload) {
+        return Err(erro(format!(
+            "envelope {id}: payload_hash não confere com o payload"
+        )));
+    }
+    Ok(Retryable {
+        id,
+        attempt,
+        payload,
+    })
+}
+
+/// Ids já processados, para descartar reentregas.
+#[derive(Debug, Clone, Default)]
+pub struct Dedup {
+    vistos: HashSet<String>,
+}
+
+impl Dedup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `true` na primeira vez que o id aparece; reentregas dão `false`.
+    pub fn first_delivery(&mut self, message: &Retryable) -> bool {
+        self.vistos.insert(message.id.to_string())
+    }
+}
@@ -0,0 +1,214 @@
+use serde_json::Value;
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::numeric::NumericError;
+
+// Erro de `json_safe!`: o erro do serde_json mais o caminho do campo em que
+// a conversão falhou (`dados[1].valor`). Converte de e para
+// `serde_json::Error`, para que `?` funcione em funções que retornam
+// `serde_json::Result`. No modo `all`, os erros são coletados (com o
+// caminho atual, mantido em uma pilha por thread) em vez de interromper.
+
+/// Um passo do caminho até o campo que falhou.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug)]
+pub struct JsonSafeError {
+    // Da raiz para o campo
+    path: Vec<PathSegment>,
+    error: serde_json::Error,
+    numeric: Option<NumericError>,
+}
+
+fn identificador(chave: &str) -> bool {
+    let mut chars = chave.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl JsonSafeError {
+    /// Caminho no formato `dados[1].valor`; chaves que não são
+    /// identificadores saem entre colchetes (`["content-type"]`). Vazio se o
+    /// erro foi na raiz.
+    pub fn path(&self) -> String {
+        let mut out = String::new();
+        for segmento in &self.path {
+            match segmento {
+                PathSegment::Key(k) if identificador(k) => {
+                    if !out.is_empty() {
+                        out.push('.');
+                    }
+                    out.push_str(k);
+                }
+                PathSegment::Key(k) => out.push_str(&format!("[{k:?}]")),
+                PathSegment::Index(i) => out.push_str(&format!("[{i}]")),
+            }
+        }
+        out
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Conversão numérica recusada (modo estrito, ou i128/u128 fora de
+    /// i64/u64), se foi esse o erro.
+    pub fn numeric(&self) -> Option<&NumericError> {
+        self.numeric.as_ref()
+    }
+
+    pub fn inner(&self) -> &serde_json::Error {
+        &self.error
+    }
+
+    pub fn into_inner(self) -> serde_json::Error {
+        self.error
+    }
+}
+
+impl fmt::Display for JsonSafeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            return write!(f, "{}", self.error);
+        }
+        write!(f, "{}: {}", self.path(), self.error)
+    }
+}
+
+impl std::error::Error for JsonSafeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<serde_json::Error> for JsonSafeError {
+    fn from(error: serde_json::Error) -> Self {
+        JsonSafeError {
+            path: Vec::new(),
+            error,
+            numeric: None,
+        }
+    }
+}
+
+/// O caminho vai para o início da mensagem.
+impl From<JsonSafeError> for serde_json::Error {
+    fn from(e: JsonSafeError) -> Self {
+        if e.path.is_empty() {
+            return e.error;
+        }
+        <serde_json::Error as serde::ser::Error>::custom(e.to_string())
+    }
+}
+
+pub(crate) fn com_caminho(
+    path: Vec<PathSegment>,
+    error: serde_json::Error,
+    numeric: Option<NumericError>,
+) -> JsonSafeError {
+    JsonSafeError {
+        path,
+        error,
+        numeric,
+    }
+}
+
+// Coleta do modo `all`: caminho atual e erros encontrados até aqui
+struct Coleta {
+    caminho: Vec<PathSegment>,
+    erros: Vec<JsonSafeError>,
+}
+
+thread_local! {
+    static COLETA: RefCell<Option<Coleta>> = const { RefCell::new(None) };
+}
+
+fn coletando() -> bool {
+    COLETA.with(|c| c.borrow().is_some())
+}
+
+// Guarda o erro com o caminho atual; devolve-o se não houver coleta ativa
+pub(crate) fn registrar(mut erro: JsonSafeError) -> Option<JsonSafeError> {
+    COLETA.with(|c| match c.borrow_mut().as_mut() {
+        Some(coleta) => {
+            let mut caminho = coleta.caminho.clone();
+            caminho.append(&mut erro.path);
+            erro.path = caminho;
+            coleta.erros.push(erro);
+            None
+        }
+        None => Some(erro),
+    })
+}
+
+// Avalia `f` dentro de `segmento`: na coleta, o erro fica registrado e o
+// valor vira `null`; fora dela, o segmento entra no caminho do erro
+pub(crate) fn em(
+    segmento: PathSegment,
+    f: impl FnOnce() -> Result<Value, JsonSafeError>,
+) -> Result<Value, JsonSafeError> {
+    if !coletando() {
+        return f().map_err(|mut e| {
+            e.path.insert(0, segmento);
+            e
+        });
+    }
+    let empilhar = |s: Option<PathSegment>| {
+        COLETA.with(|c| {
+            if let Some(coleta) = c.borrow_mut().as_mut() {
+                match s {
+                    Some(s) => coleta.caminho.push(s),
+                    None => {
+                        coleta.caminho.pop();
+                    }
+                }
+            }
+        })
+    };
+    empilhar(Some(segmento));
+    let resultado = f();
+    let resultado = match resultado {
+        Ok(v) => Ok(v),
+        Err(e) => registrar(e).map_or(Ok(Value::Null), Err),
+    };
+    empilhar(None);
+    resultado
+}
+
+// Troca a coleta da thread durante `f`, restaurando a anterior no fim
+fn com_coleta<T>(coleta: Option<Coleta>, f: impl FnOnce() -> T) -> (T, Option<Coleta>) {
+    crate::scoped::trocar(&COLETA, coleta, f)
+}
+
+pub(crate) fn accumulate(
+    f: impl FnOnce() -> Result<Value, JsonSafeError>,
+) -> Result<Value, Vec<JsonSafeError>> {
+    let nova = Coleta {
+        caminho: Vec::new(),
+        erros: Vec::new(),
+    };
+    let (resultado, coleta) = com_coleta(Some(nova), f);
+    let mut erros = coleta.map(|c| c.erros).unwrap_or_default();
+    match resultado {
+        Ok(v) if erros.is_empty() => Ok(v),
+        Ok(_) => Err(erros),
+        Err(e) => {
+            erros.push(e);
+            Err(erros)
+        }
+    }
+}
+
+pub(crate) fn isolate(
+    f: impl FnOnce() -> Result<Value, JsonSafeError>,
+) -> Result<Value, JsonSafeError> {
+    if !coletando() {
+        return f();
+    }
+    com_coleta(None, f).0
+}
@@ -0,0 +1,232 @@
+use serde_json::{Map, Value};
+use std::error::Error;
+
+// Payloads de evento de erro para Sentry (store/envelope) e Rollbar
+// (`item`), montados a partir de erros Rust e extras da macro.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Fatal,
+    Error,
+    Warning,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn sentry(self) -> &'static str {
+        match self {
+            Level::Fatal => "fatal",
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+
+    fn rollbar(self) -> &'static str {
+        match self {
+            Level::Fatal => "critical",
+            outro => outro.sentry(),
+        }
+    }
+}
+
+/// Evento de erro independente de provedor.
+#[derive(Debug, Clone)]
+pub struct ErrorEvent {
+    level: Level,
+    // Do erro mais externo para a causa raiz: (tipo, mensagem)
+    exceptions: Vec<(String, String)>,
+    breadcrumbs: Vec<(String, String)>,
+    tags: Map<String, Value>,
+    contexts: Map<String, Value>,
+    extra: Map<String, Value>,
+}
+
+// `source()` só expõe `dyn Error`; o nome vem do início do `Debug`, que
+// para tipos com derive é o nome do tipo/variante.
+fn nome_debug(e: &dyn Error) -> String {
+    let debug = format!("{e:?}");
+    let nome: String = debug
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | ':'))
+        .collect();
+    if nome.is_empty() {
+        "Error".into()
+    } else {
+        nome
+    }
+}
+
+impl ErrorEvent {
+    /// Monta o evento percorrendo a cadeia de `source()` de `error`.
+    pub fn from_error<E: Error + 'static>(error: &E) -> Self {
+        let mut exceptions = vec![(std::any::type_name::<E>().to_string(), error.to_string())];
+        let mut atual = error.source();
+        while let Some(causa) = atual {
+            exceptions.push((nome_debug(causa), causa.to_string()));
+            atual = causa.source();
+        }
+        ErrorEvent {
+            level: Level::Error,
+            exceptions,
+            breadcrumbs: Vec::new(),
+            tags: Map::new(),
+            contexts: Map::new(),
+            extra: Map::new(),
+        }
+    }
+
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), Value::String(value.into()));
+        self
+    }
+
+    pub fn context(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.contexts.insert(name.into(), value);
+        self
+    }
+
+    pub fn breadcrumb(mut self, category: impl Into<String>, message: impl Into<String>) -> Self {
+        self.breadcrumbs.push((category.into(), message.into()));
+        self
+    }
+
+    /// Mescla `extra` (um objeto, tipicamente de `json_safe!`) nos extras.
+    pub fn extra(mut self, extra: Value) -> serde_json::Result<Self> {
+        match extra {
+            Value::Object(map) => {
+                self.extra.extend(map);
+                Ok(self)
+            }
+            _ => Err(erro("extra deve ser um objeto JSON".into())),
+        }
+    }
+
+    fn validar(&self) -> serde_json::Result<()> {
+        for (chave, valor) in &self.tags {
+            if chave.is_empty() || chave.chars().count() > 32 {
+                return Err(erro(format!("tag com chave inválida: {chave:?}")));
+            }
+            if valor.as_str().is_some_and(|v| v.chars().count() > 200) {
+                return Err(erro(format!("tag {chave:?} excede 200 caracteres")));
+            }
+        }
+        for (nome, valor) in &self.contexts {
+            if !valor.is_object() {
+                return Err(erro(format!("context {nome:?} deve ser um objeto")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Evento no formato do Sentry; exceções vão da causa raiz para a mais
+    /// externa, como o Sentry espera.
+    pub fn to_sentry(&self) -> serde_json::Result<Value> {
+        self.validar()?;
+        let excecoes: Vec<Value> = self
+            .exceptions
+            .iter()
+            .rev()
+            .map(|(tipo, mensagem)| {
+                let mut map = Map::new();
+                map.insert("type".into(), Value::from(tipo.as_str()));
+                map.insert("value".into(), Value::from(mensagem.as_str()));
+                Value::Object(map)
+            })
+            .collect();
+        let migalhas: Vec<Value> = self
+            .breadcrumbs
+            .iter()
+            .map(|(categoria, mensagem)| {
+                let mut map = Map::new();
+                map.insert("category".into(), Value::from(categoria.as_str()));
+                map.insert("message".into(), Value::from(mensagem.as_str()));
+                Value::Object(map)
+            })
+            .collect();
+
+        let mut map = Map::new();
+        map.insert("platform".into(), Value::from("rust"));
+        map.insert("level".into(), Value::from(self.level.sentry()));
+        map.insert("exception".into(), valores(excecoes));
+        if !migalhas.is_empty() {
+            map.insert("breadcrumbs".into(), valores(migalhas));
+        }
+        inserir_nao_vazio(&mut map, "tags", &self.tags);
+        inserir_nao_vazio(&mut map, "contexts", &self.contexts);
+        inserir_nao_vazio(&mut map, "extra", &self.extra);
+        Ok(Value::Object(map))
+    }
+
+    /// Item no formato da API do Rollbar (`{ "data": { ... } }`), com a
+    /// cadeia em `trace_chain` (mais recente primeiro).
+    pub fn to_rollbar(&self, environment: &str) -> serde_json::Result<Value> {
+        self.validar()?;
+        if environment.is_empty() {
+            return Err(erro("Rollbar: environment obrigatório".into()));
+        }
+        let cadeia: Vec<Value> = self
+            .exceptions
+            .iter()
+            .map(|(tipo, mensagem)| {
+                let mut excecao = Map::new();
+                excecao.insert("class".into(), Value::from(tipo.as_str()));
+                excecao.insert("message".into(), Value::from(mensagem.as_str()));
+                let mut trace = Map::new();
+                trace.insert("frames".into(), Value::Array(Vec::new()));
+                trace.insert("exception".into(), Value::Object(excecao));
+                Value::Object(trace)
+            })
+            .collect();
+
+        let mut corpo = Map::new();
+        corpo.insert("trace_chain".into(), Value::Array(cadeia));
+
+        let mut custom = self.extra.clone();
+        custom.extend(self.contexts.clone());
+
+        let mut data = Map::new();
+        data.insert("environment".into(), Value::from(environment));
+        data.insert("level".into(), Value::from(self.level.rollbar()));
+        data.insert("platform".into(), Value::from("rust"));
+        data.insert("language".into(), Value::from("rust"));
+        data.insert("body".into(), Value::Object(corpo));
+        inserir_nao_vazio(&mut data, "custom", &custom);
+        if !self.tags.is_empty() {
+            // Rollbar não tem tags; vão como lista "chave:valor" em `context`
+            let tags: Vec<String> = self
+                .tags
+                .iter()
+                .map(|(k, v)| format!("{k}:{}", v.as_str().unwrap_or_default()))
+                .collect();
+            data.insert("context".into(), Value::from(tags.join(",")));
+        }
+
+        let mut item = Map::new();
+        item.insert("data".into(), Value::Object(data));
+        Ok(Value::Object(item))
+    }
+}
+
+fn valores(itens: Vec<Value>) -> Value {
+    let mut map = Map::new();
+    map.insert("values".into(), Value::Array(itens));
+    Value::Object(map)
+}
+
+fn inserir_nao_vazio(map: &mut Map<String, Value>, chave: &str, valor: &Map<String, Value>) {
+    if !valor.is_empty() {
+        map.insert(chave.into(), Value::Object(valor.clone()));
+    }
+}
@@ -0,0 +1,175 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+// Registro de schemas nomeados para eventos de analytics (exposição de
+// experimento, conversão...). `emit_checked` valida o documento antes de
+// serializá-lo, pegando drift de schema no produtor.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object,
+    Array,
+}
+
+impl FieldType {
+    pub(crate) fn aceita(self, value: &Value) -> bool {
+        match self {
+            FieldType::String => value.is_string(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::Number => value.is_number(),
+            FieldType::Boolean => value.is_boolean(),
+            FieldType::Object => value.is_object(),
+            FieldType::Array => value.is_array(),
+        }
+    }
+
+    pub(crate) fn nome(self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Integer => "integer",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::Object => "object",
+            FieldType::Array => "array",
+        }
+    }
+}
+
+/// Schema de um evento: campos de topo com tipo e obrigatoriedade.
+#[derive(Debug, Clone, Default)]
+pub struct EventSchema {
+    // nome -> (tipo, obrigatório)
+    pub(crate) fields: BTreeMap<String, (FieldType, bool)>,
+    pub(crate) allow_additional: bool,
+}
+
+impl EventSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn required(mut self, name: impl Into<String>, kind: FieldType) -> Self {
+        self.fields.insert(name.into(), (kind, true));
+        self
+    }
+
+    pub fn optional(mut self, name: impl Into<String>, kind: FieldType) -> Self {
+        self.fields.insert(name.into(), (kind, false));
+        self
+    }
+
+    /// Aceita campos não declarados (padrão: rejeita).
+    pub fn allow_additional(mut self, allow: bool) -> Self {
+        self.allow_additional = allow;
+        self
+    }
+
+    /// Lista todas as violações de `value` contra o schema.
+    pub fn violations(&self, value: &Value) -> Vec<String> {
+        let Value::Object(map) = value else {
+            return vec!["evento deve ser um objeto".into()];
+        };
+
+        let mut out = Vec::new();
+        for (nome, (tipo, obrigatorio)) in &self.fields {
+            match map.get(nome) {
+                None | Some(Value::Null) if *obrigatorio => {
+                    out.push(format!("campo obrigatório ausente: {nome}"));
+                }
+                None | Some(Value::Null) => {}
+                Some(v) if !tipo.aceita(v) => {
+                    out.push(format!("campo {nome} deve ser {}", tipo.nome()));
+                }
+                Some(_) => {}
+            }
+        }
+        if !self.allow_additional {
+            for nome in map.keys().filter(|k| !self.fields.contains_key(*k)) {
+                out.push(format!("campo não declarado: {nome}"));
+            }
+        }
+        out
+    }
+}
+
+/// Registro de schemas por nome.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<String, EventSchema>,
+}
+
+impl SchemaRegistry {
+    /// Registro vazio.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registro com os presets `experiment_exposure` e `conversion`.
+    pub fn with_presets() -> Self {
+        Self::new()
+            .register(
+                "experiment_exposure",
+                EventSchema::new()
+                    .required("experiment_id", FieldType::String)
+                    .required("variant", FieldType::String)
+                    .required("user_id", FieldType::String)
+                    .required("timestamp", FieldType::String)
+                    .optional("context", FieldType::Object),
+            )
+            .register(
+                "conversion",
+                EventSchema::new()
+                    .required("experiment_id", FieldType::String)
+                    .required("user_id", FieldType::String)
+                    .required("event", FieldType::String)
+                    .required("timestamp", FieldType::String)
+                    .optional("value", FieldType::Number)
+                    .optional("context", FieldType::Object),
+            )
+    }
+
+    pub fn register(mut self, name: impl Into<String>, schema: EventSchema) -> Self {
+        self.schemas.insert(name.into(), schema);
+        self
+    }
+
+    /// Valida `value` contra o schema `name` e retorna os bytes JSON.
+    pub fn emit_checked(&self, name: &str, value: &Value) -> serde_json::Result<Vec<u8>> {
+        let schema = self
+            .schemas
+            .get(name)
+            .ok_or_else(|| erro(format!("schema não registrado: {name}")))?;
+        let violacoes = schema.violations(value);
+        if !violacoes.is_empty() {
+            return Err(erro(format!("{name}: {}", violacoes.join("; "))));
+        }
+        serde_json::to_vec(value)
+    }
+}
+
+fn global() -> &'static RwLock<SchemaRegistry> {
+    static REGISTRO: OnceLock<RwLock<SchemaRegistry>> = OnceLock::new();
+    REGISTRO.get_or_init(|| RwLock::new(SchemaRegistry::with_presets()))
+}
+
+/// Registra (ou substitui) um schema no registro global do processo, que já
+/// vem com os presets de [`SchemaRegistry::with_presets`].
+pub fn register(name: impl Into<String>, schema: EventSchema) {
+    let mut registro = global().write().unwrap_or_else(|e| e.into_inner());
+    registro.schemas.insert(name.into(), schema);
+}
+
+/// [`SchemaRegistry::emit_checked`] sobre o registro global.
+pub fn emit_checked(name: &str, value: Value) -> serde_json::Result<Vec<u8>> {
+    let registro = global().read().unwrap_or_else(|e| e.into_inner());
+    registro.emit_checked(name, &value)
+}
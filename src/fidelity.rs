@@ -0,0 +1,126 @@
+use serde_json::{Number, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::duplicate_keys::sem_limites;
+use crate::stream::Parser;
+use crate::untrusted::montar_com;
+
+// Fidelidade numérica: o documento lido guarda, ao lado do `Value`, o texto
+// original de cada número (`1.10`, `1e2`, `-0.0`), endereçado por JSON
+// Pointer. Na volta para texto, um número que não mudou sai exatamente como
+// entrou — assinaturas e valores financeiros sobrevivem ao round trip. Um
+// número alterado pelo `value_mut` sai na formatação do serde_json.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Documento com o texto original dos números.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    value: Value,
+    lexemas: BTreeMap<String, (String, Number)>,
+}
+
+/// Parse de `bytes` guardando o texto de cada número.
+pub fn parse(bytes: &[u8]) -> serde_json::Result<Document> {
+    let mut lexemas = BTreeMap::new();
+    let mut numero = |ponteiro: String, lexema: &str| {
+        if let Ok(n) = serde_json::from_str::<Number>(lexema) {
+            lexemas.insert(ponteiro, (lexema.to_string(), n));
+        }
+    };
+    let value = montar_com(
+        Parser::new(bytes, &sem_limites()),
+        |_| Ok(()),
+        Some(&mut numero),
+    )?;
+    Ok(Document { value, lexemas })
+}
+
+impl Document {
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Edições nos números descartam o texto original deles.
+    pub fn value_mut(&mut self) -> &mut Value {
+        &mut self.value
+    }
+
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+
+    /// Texto original do número em `pointer`, se ele não foi alterado.
+    pub fn lexeme(&self, pointer: &str) -> Option<&str> {
+        match (self.lexemas.get(pointer), self.value.pointer(pointer)) {
+            (Some((lexema, n)), Some(Value::Number(atual))) if atual == n => Some(lexema),
+            _ => None,
+        }
+    }
+
+    /// Troca o número em `pointer` por `lexeme`, que sai escrito assim.
+    pub fn with_number(mut self, pointer: &str, lexeme: &str) -> serde_json::Result<Self> {
+        let n: Number = match serde_json::from_str(lexeme) {
+            Ok(Value::Number(n)) => n,
+            _ => return Err(erro(format!("fidelity: número inválido {lexeme:?}"))),
+        };
+        let alvo = self
+            .value
+            .pointer_mut(pointer)
+            .ok_or_else(|| erro(format!("fidelity: caminho inexistente {pointer}")))?;
+        *alvo = Value::Number(n.clone());
+        self.lexemas
+            .insert(pointer.to_string(), (lexeme.to_string(), n));
+        Ok(self)
+    }
+
+    fn escrever(&self, value: &Value, ponteiro: &mut String, out: &mut String) {
+        let tamanho = ponteiro.len();
+        match value {
+            Value::Object(map) => {
+                out.push('{');
+                for (i, (chave, item)) in map.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&Value::String(chave.clone()).to_string());
+                    out.push(':');
+                    ponteiro.push('/');
+                    ponteiro.push_str(&chave.replace('~', "~0").replace('/', "~1"));
+                    self.escrever(item, ponteiro, out);
+                    ponteiro.truncate(tamanho);
+                }
+                out.push('}');
+            }
+            Value::Array(itens) => {
+                out.push('[');
+                for (i, item) in itens.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    ponteiro.push_str(&format!("/{i}"));
+                    self.escrever(item, ponteiro, out);
+                    ponteiro.truncate(tamanho);
+                }
+                out.push(']');
+            }
+            Value::Number(n) => match self.lexemas.get(ponteiro.as_str()) {
+                Some((lexema, original)) if original == n => out.push_str(lexema),
+                _ => out.push_str(&n.to_string()),
+            },
+            escalar => out.push_str(&escalar.to_string()),
+        }
+    }
+}
+
+/// JSON compacto, com os números no texto original.
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        self.escrever(&self.value, &mut String::new(), &mut out);
+        f.write_str(&out)
+    }
+}
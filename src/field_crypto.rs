@@ -0,0 +1,68 @@
+use serde_json::Value;
+
+use crate::base64;
+
+// Criptografia por campo: os valores apontados por JSON Pointers (RFC 6901)
+// são serializados, cifrados e trocados por strings `enc:v1:<base64>`; o
+// resto do documento continua legível e consultável.
+
+/// Prefixo das strings cifradas.
+pub const TAG: &str = "enc:v1:";
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+/// Algoritmo de cifra plugável (AES-GCM, KMS, ...). O crate não traz
+/// nenhuma implementação.
+pub trait Cipher {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, String>;
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// Cifra os valores em `pointers`. Caminhos ausentes são ignorados e
+/// valores já cifrados são mantidos.
+pub fn encrypt_paths(
+    doc: &mut Value,
+    pointers: &[&str],
+    cipher: &dyn Cipher,
+) -> serde_json::Result<()> {
+    for &ponteiro in pointers {
+        let Some(alvo) = doc.pointer_mut(ponteiro) else {
+            continue;
+        };
+        if alvo.as_str().is_some_and(|s| s.starts_with(TAG)) {
+            continue;
+        }
+        let claro = serde_json::to_vec(alvo)?;
+        let cifrado = cipher
+            .encrypt(&claro)
+            .map_err(|e| erro(format!("{ponteiro}: {e}")))?;
+        *alvo = Value::String(format!("{TAG}{}", base64::encode(&cifrado)));
+    }
+    Ok(())
+}
+
+/// Inverso de [`encrypt_paths`]. Caminhos ausentes são ignorados; um valor
+/// presente que não esteja cifrado é erro.
+pub fn decrypt_paths(
+    doc: &mut Value,
+    pointers: &[&str],
+    cipher: &dyn Cipher,
+) -> serde_json::Result<()> {
+    for &ponteiro in pointers {
+        let Some(alvo) = doc.pointer_mut(ponteiro) else {
+            continue;
+        };
+        let Some(b64) = alvo.as_str().and_then(|s| s.strip_prefix(TAG)) else {
+            return Err(erro(format!("{ponteiro}: valor não está cifrado")));
+        };
+        let cifrado =
+            base64::decode(b64).ok_or_else(|| erro(format!("{ponteiro}: base64 inválido")))?;
+        let claro = cipher
+            .decrypt(&cifrado)
+            .map_err(|e| erro(format!("{ponteiro}: {e}")))?;
+        *alvo = serde_json::from_slice(&claro)?;
+    }
+    Ok(())
+}
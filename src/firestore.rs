@@ -0,0 +1,144 @@
+use serde_json::{Map, Value};
+
+// Codificação de valores tipados das APIs REST do Google (Firestore,
+// Datastore): `{"stringValue": "..."}`, `{"mapValue": {"fields": {...}}}`.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+fn tipado(tipo: &str, valor: Value) -> Value {
+    let mut map = Map::new();
+    map.insert(tipo.into(), valor);
+    Value::Object(map)
+}
+
+/// Converte um `Value` para a forma tipada. Inteiros vão como string
+/// (`integerValue`), como a API espera; inteiros fora de i64 são erro.
+pub fn to_typed(value: &Value) -> serde_json::Result<Value> {
+    Ok(match value {
+        Value::Null => tipado("nullValue", Value::Null),
+        Value::Bool(b) => tipado("booleanValue", Value::Bool(*b)),
+        Value::Number(n) if n.is_i64() => tipado("integerValue", Value::String(n.to_string())),
+        Value::Number(n) if n.is_u64() => {
+            return Err(erro(format!("inteiro fora do intervalo de int64: {n}")));
+        }
+        Value::Number(n) => tipado("doubleValue", Value::Number(n.clone())),
+        Value::String(s) => tipado("stringValue", Value::String(s.clone())),
+        Value::Array(itens) => {
+            let mut array = Map::new();
+            if !itens.is_empty() {
+                let valores = itens
+                    .iter()
+                    .map(to_typed)
+                    .collect::<serde_json::Result<Vec<_>>>()?;
+                array.insert("values".into(), Value::Array(valores));
+            }
+            tipado("arrayValue", Value::Object(array))
+        }
+        Value::Object(map) => {
+            let mut mapa = Map::new();
+            if !map.is_empty() {
+                mapa.insert("fields".into(), campos(map)?);
+            }
+            tipado("mapValue", Value::Object(mapa))
+        }
+    })
+}
+
+fn campos(map: &Map<String, Value>) -> serde_json::Result<Value> {
+    let mut out = Map::new();
+    for (chave, valor) in map {
+        out.insert(chave.clone(), to_typed(valor)?);
+    }
+    Ok(Value::Object(out))
+}
+
+/// Monta o corpo de documento `{ "fields": { ... } }` a partir de um objeto.
+pub fn to_fields(value: &Value) -> serde_json::Result<Value> {
+    match value {
+        Value::Object(map) => {
+            let mut doc = Map::new();
+            doc.insert("fields".into(), campos(map)?);
+            Ok(Value::Object(doc))
+        }
+        _ => Err(erro("documento Firestore deve ser um objeto".into())),
+    }
+}
+
+/// Converte um valor tipado de volta para JSON comum. `timestampValue`,
+/// `referenceValue` e `bytesValue` viram strings; `geoPointValue` vira
+/// `{ latitude, longitude }`.
+pub fn from_typed(value: &Value) -> serde_json::Result<Value> {
+    let Some((tipo, valor)) = value
+        .as_object()
+        .and_then(|m| if m.len() == 1 { m.iter().next() } else { None })
+    else {
+        return Err(erro(format!(
+            "valor tipado deve ter exatamente uma chave: {value}"
+        )));
+    };
+
+    match (tipo.as_str(), valor) {
+        ("nullValue", Value::Null) => Ok(Value::Null),
+        ("booleanValue", Value::Bool(b)) => Ok(Value::Bool(*b)),
+        ("integerValue", Value::String(s)) => s
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| erro(format!("integerValue inválido: {s:?}"))),
+        ("integerValue", Value::Number(n)) if n.is_i64() => Ok(Value::Number(n.clone())),
+        ("doubleValue", Value::Number(n)) => Ok(Value::Number(n.clone())),
+        ("doubleValue", Value::String(s)) => {
+            Err(erro(format!("doubleValue não representável em JSON: {s}")))
+        }
+        ("stringValue" | "timestampValue" | "referenceValue" | "bytesValue", Value::String(s)) => {
+            Ok(Value::String(s.clone()))
+        }
+        ("geoPointValue", Value::Object(ponto)) => {
+            let mut out = Map::new();
+            for chave in ["latitude", "longitude"] {
+                out.insert(
+                    chave.into(),
+                    ponto.get(chave).cloned().unwrap_or(Value::from(0.0)),
+                );
+            }
+            Ok(Value::Object(out))
+        }
+        ("arrayValue", Value::Object(array)) => match array.get("values") {
+            None => Ok(Value::Array(Vec::new())),
+            Some(Value::Array(itens)) => itens
+                .iter()
+                .map(from_typed)
+                .collect::<serde_json::Result<Vec<_>>>()
+                .map(Value::Array),
+            Some(_) => Err(erro("arrayValue.values deve ser um array".into())),
+        },
+        ("mapValue", Value::Object(mapa)) => match mapa.get("fields") {
+            None => Ok(Value::Object(Map::new())),
+            Some(fields) => from_campos(fields),
+        },
+        (tipo, _) => Err(erro(format!("valor tipado não suportado: {tipo}"))),
+    }
+}
+
+fn from_campos(fields: &Value) -> serde_json::Result<Value> {
+    match fields {
+        Value::Object(map) => {
+            let mut out = Map::new();
+            for (chave, valor) in map {
+                out.insert(chave.clone(), from_typed(valor)?);
+            }
+            Ok(Value::Object(out))
+        }
+        _ => Err(erro("'fields' deve ser um objeto".into())),
+    }
+}
+
+/// Inverso de [`to_fields`]: lê `fields` de um documento Firestore.
+pub fn from_fields(doc: &Value) -> serde_json::Result<Value> {
+    match doc.get("fields") {
+        Some(fields) => from_campos(fields),
+        None if doc.is_object() => Ok(Value::Object(Map::new())),
+        None => Err(erro("documento Firestore deve ser um objeto".into())),
+    }
+}
@@ -0,0 +1,187 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// Documento de contexto para avaliação de feature flags: atributos planos,
+// chaves normalizadas e apenas tipos que os provedores aceitam (string,
+// número, booleano e arrays desses). Atributos privados são listados em
+// `_meta.privateAttributes` e podem ser removidos para logs.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStyle {
+    Snake,
+    Camel,
+    Preserve,
+}
+
+fn palavras(chave: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut atual = String::new();
+    let mut anterior_minuscula = false;
+    for c in chave.trim().chars() {
+        if !c.is_alphanumeric() {
+            if !atual.is_empty() {
+                out.push(std::mem::take(&mut atual));
+            }
+            anterior_minuscula = false;
+            continue;
+        }
+        if c.is_uppercase() && anterior_minuscula && !atual.is_empty() {
+            out.push(std::mem::take(&mut atual));
+        }
+        anterior_minuscula = c.is_lowercase() || c.is_ascii_digit();
+        atual.extend(c.to_lowercase());
+    }
+    if !atual.is_empty() {
+        out.push(atual);
+    }
+    out
+}
+
+fn normalizar(chave: &str, estilo: KeyStyle) -> String {
+    match estilo {
+        KeyStyle::Preserve => chave.trim().to_string(),
+        KeyStyle::Snake => palavras(chave).join("_"),
+        KeyStyle::Camel => palavras(chave)
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                if i == 0 {
+                    return p.clone();
+                }
+                let mut chars = p.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            })
+            .collect(),
+    }
+}
+
+/// Builder do contexto de avaliação.
+#[derive(Debug, Clone)]
+pub struct FlagContext {
+    key: String,
+    style: KeyStyle,
+    attributes: Map<String, Value>,
+    private: Vec<String>,
+}
+
+impl FlagContext {
+    /// `key` é o identificador estável do usuário/contexto.
+    pub fn new(key: impl Into<String>) -> Self {
+        FlagContext {
+            key: key.into(),
+            style: KeyStyle::Snake,
+            attributes: Map::new(),
+            private: Vec::new(),
+        }
+    }
+
+    /// Estilo de normalização das chaves (padrão: snake_case). Aplica-se aos
+    /// atributos adicionados depois da chamada.
+    pub fn key_style(mut self, style: KeyStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Adiciona um atributo. Objetos são achatados (`endereco.cidade` vira
+    /// `endereco_cidade` em snake_case); `null` é omitido.
+    pub fn attr<T: Serialize>(mut self, name: &str, value: T) -> serde_json::Result<Self> {
+        let valor = serde_json::to_value(value)?;
+        self.inserir(name, valor, false)?;
+        Ok(self)
+    }
+
+    /// Como [`attr`](Self::attr), mas marca o atributo como privado.
+    pub fn private_attr<T: Serialize>(mut self, name: &str, value: T) -> serde_json::Result<Self> {
+        let valor = serde_json::to_value(value)?;
+        self.inserir(name, valor, true)?;
+        Ok(self)
+    }
+
+    fn inserir(&mut self, nome: &str, valor: Value, privado: bool) -> serde_json::Result<()> {
+        let chave = normalizar(nome, self.style);
+        if chave.is_empty() {
+            return Err(erro(format!("nome de atributo inválido: {nome:?}")));
+        }
+        match valor {
+            Value::Null => Ok(()),
+            Value::Object(map) => {
+                // O separador é reaproveitado pela normalização do nome composto
+                let separador = match self.style {
+                    KeyStyle::Snake => "_",
+                    KeyStyle::Camel => " ",
+                    KeyStyle::Preserve => ".",
+                };
+                for (filho, v) in map {
+                    self.inserir(&format!("{chave}{separador}{filho}"), v, privado)?;
+                }
+                Ok(())
+            }
+            Value::Array(itens) => {
+                if let Some(i) = itens.iter().position(|v| !escalar(v)) {
+                    return Err(erro(format!(
+                        "atributo {chave:?}: item {i} do array não é string/número/booleano"
+                    )));
+                }
+                self.gravar(chave, Value::Array(itens), privado)
+            }
+            escalar_valor => self.gravar(chave, escalar_valor, privado),
+        }
+    }
+
+    fn gravar(&mut self, chave: String, valor: Value, privado: bool) -> serde_json::Result<()> {
+        if chave == "key" || chave.starts_with('_') {
+            return Err(erro(format!("atributo reservado: {chave:?}")));
+        }
+        if self.attributes.contains_key(&chave) {
+            return Err(erro(format!(
+                "atributo duplicado após normalização: {chave:?}"
+            )));
+        }
+        if privado {
+            self.private.push(chave.clone());
+        }
+        self.attributes.insert(chave, valor);
+        Ok(())
+    }
+
+    /// Documento completo, com `_meta.privateAttributes` quando houver.
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        if self.key.trim().is_empty() {
+            return Err(erro("contexto sem key".into()));
+        }
+        let mut map = self.attributes.clone();
+        map.insert("key".into(), Value::from(self.key.as_str()));
+        if !self.private.is_empty() {
+            let mut meta = Map::new();
+            meta.insert(
+                "privateAttributes".into(),
+                Value::from(self.private.clone()),
+            );
+            map.insert("_meta".into(), Value::Object(meta));
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// Documento sem os atributos privados, seguro para logs.
+    pub fn to_redacted_value(&self) -> serde_json::Result<Value> {
+        let mut doc = self.to_value()?;
+        if let Value::Object(map) = &mut doc {
+            for chave in &self.private {
+                map.remove(chave);
+            }
+            map.remove("_meta");
+        }
+        Ok(doc)
+    }
+}
+
+fn escalar(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_))
+}
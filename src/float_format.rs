@@ -0,0 +1,134 @@
+use serde_json::Value;
+
+// Formatação de floats na saída, para consumidores com parsers exigentes:
+// a faixa de expoentes escrita sem notação científica e o mínimo de casas
+// decimais. Os dígitos são sempre os mais curtos que voltam ao mesmo f64;
+// só a notação muda. Inteiros saem como estão. O padrão reproduz o
+// serde_json (`0.00001`, `1e-6`, `1e16`, `1.0`).
+
+/// Como escrever floats. `new()` equivale ao serde_json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloatFormat {
+    min_exponent: i32,
+    max_exponent: i32,
+    min_fraction_digits: usize,
+}
+
+impl Default for FloatFormat {
+    fn default() -> Self {
+        FloatFormat {
+            min_exponent: -5,
+            max_exponent: 15,
+            min_fraction_digits: 1,
+        }
+    }
+}
+
+impl FloatFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Menor expoente decimal escrito por extenso; abaixo dele, `1e-6`.
+    pub fn min_exponent(mut self, exponent: i32) -> Self {
+        self.min_exponent = exponent;
+        self
+    }
+
+    /// Maior expoente decimal escrito por extenso; acima dele, `1e16`.
+    pub fn max_exponent(mut self, exponent: i32) -> Self {
+        self.max_exponent = exponent;
+        self
+    }
+
+    /// Casas decimais mínimas na notação por extenso (`2` → `1.50`); com
+    /// `0`, floats inteiros saem sem ponto (`3`).
+    pub fn min_fraction_digits(mut self, digits: usize) -> Self {
+        self.min_fraction_digits = digits;
+        self
+    }
+
+    /// Texto de `x`; `None` para NaN e infinitos, que não são JSON.
+    pub fn format(&self, x: f64) -> Option<String> {
+        if !x.is_finite() {
+            return None;
+        }
+        // `{:e}` dá os dígitos mais curtos: "-1.25e-7"
+        let cientifico = format!("{:e}", x.abs());
+        let (mantissa, expoente) = cientifico.split_once('e')?;
+        let expoente: i32 = expoente.parse().ok()?;
+        let digitos = mantissa.replace('.', "");
+        let mut out = String::from(if x.is_sign_negative() { "-" } else { "" });
+
+        if expoente < self.min_exponent || expoente > self.max_exponent {
+            out.push_str(&digitos[..1]);
+            if digitos.len() > 1 {
+                out.push('.');
+                out.push_str(&digitos[1..]);
+            }
+            out.push_str(&format!("e{expoente}"));
+            return Some(out);
+        }
+
+        let (inteira, mut fracao) = if expoente < 0 {
+            let zeros = "0".repeat((-expoente - 1) as usize);
+            ("0".to_string(), format!("{zeros}{digitos}"))
+        } else {
+            let ponto = expoente as usize + 1;
+            if digitos.len() > ponto {
+                (digitos[..ponto].to_string(), digitos[ponto..].to_string())
+            } else {
+                let zeros = "0".repeat(ponto - digitos.len());
+                (format!("{digitos}{zeros}"), String::new())
+            }
+        };
+        while fracao.len() < self.min_fraction_digits {
+            fracao.push('0');
+        }
+        out.push_str(&inteira);
+        if !fracao.is_empty() {
+            out.push('.');
+            out.push_str(&fracao);
+        }
+        Some(out)
+    }
+
+    /// JSON compacto de `value` com os floats neste formato.
+    pub fn to_string(&self, value: &Value) -> String {
+        let mut out = String::new();
+        self.escrever(value, &mut out);
+        out
+    }
+
+    fn escrever(&self, value: &Value, out: &mut String) {
+        match value {
+            Value::Object(map) => {
+                out.push('{');
+                for (i, (chave, item)) in map.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&Value::String(chave.clone()).to_string());
+                    out.push(':');
+                    self.escrever(item, out);
+                }
+                out.push('}');
+            }
+            Value::Array(itens) => {
+                out.push('[');
+                for (i, item) in itens.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    self.escrever(item, out);
+                }
+                out.push(']');
+            }
+            Value::Number(n) if n.is_f64() => match n.as_f64().and_then(|x| self.format(x)) {
+                Some(texto) => out.push_str(&texto),
+                None => out.push_str(&n.to_string()),
+            },
+            escalar => out.push_str(&escalar.to_string()),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use serde_json::{Number, Value};
+
+// Redução de precisão de coordenadas em caminhos configurados, para payloads
+// de localização que não podem expor a posição exata. Reconhece objetos
+// `{lat, lon}` (também `lng`, `latitude`/`longitude`), posições `[lon, lat]`
+// e geometrias GeoJSON (todas as posições de `coordinates`).
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoFuzz {
+    /// Arredonda para `n` casas decimais (2 casas ≈ 1,1 km no equador).
+    Round(u32),
+    /// Encaixa no centro de uma grade com células de `n` graus; a latitude
+    /// fica em [-90, 90] e a longitude dá a volta em ±180.
+    Grid(f64),
+}
+
+#[derive(Clone, Copy)]
+enum Eixo {
+    Lat,
+    Lon,
+}
+
+impl GeoFuzz {
+    fn aplicar(self, x: f64, eixo: Eixo) -> f64 {
+        let bruto = match self {
+            GeoFuzz::Round(casas) => {
+                let fator = 10f64.powi(casas as i32);
+                (x * fator).round() / fator
+            }
+            GeoFuzz::Grid(celula) => ((x / celula).floor() + 0.5) * celula,
+        };
+        // O centro da célula pode passar do polo ou do antimeridiano
+        let bruto = match eixo {
+            Eixo::Lat => bruto.clamp(-90.0, 90.0),
+            Eixo::Lon if !(-180.0..=180.0).contains(&bruto) => {
+                (bruto + 180.0).rem_euclid(360.0) - 180.0
+            }
+            Eixo::Lon => bruto,
+        };
+        // Remove ruído de ponto flutuante (0.30000000000000004)
+        (bruto * 1e9).round() / 1e9
+    }
+}
+
+const PARES: [(&str, &str); 3] = [("lat", "lon"), ("lat", "lng"), ("latitude", "longitude")];
+
+/// Aplica `fuzz` às localizações em `pointers`. Caminhos ausentes são
+/// ignorados; um valor que não é uma localização reconhecida é erro.
+pub fn fuzz_locations(doc: &mut Value, pointers: &[&str], fuzz: GeoFuzz) -> serde_json::Result<()> {
+    if let GeoFuzz::Grid(celula) = fuzz
+        && !(celula.is_finite() && celula > 0.0)
+    {
+        return Err(erro(format!("célula de grade inválida: {celula}")));
+    }
+    for &ponteiro in pointers {
+        let Some(alvo) = doc.pointer_mut(ponteiro) else {
+            continue;
+        };
+        localizacao(alvo, fuzz).map_err(|e| erro(format!("{ponteiro}: {e}")))?;
+    }
+    Ok(())
+}
+
+fn localizacao(value: &mut Value, fuzz: GeoFuzz) -> Result<(), String> {
+    match value {
+        Value::Array(_) => posicoes(value, fuzz),
+        Value::Object(map) if map.contains_key("coordinates") => {
+            posicoes(&mut map["coordinates"], fuzz)
+        }
+        Value::Object(map)
+            if map.get("type").and_then(Value::as_str) == Some("GeometryCollection") =>
+        {
+            match map.get_mut("geometries") {
+                Some(Value::Array(geometrias)) => {
+                    geometrias.iter_mut().try_for_each(|g| localizacao(g, fuzz))
+                }
+                _ => Err("GeometryCollection sem geometries".into()),
+            }
+        }
+        Value::Object(map) => {
+            let Some((lat, lon)) = PARES
+                .iter()
+                .find(|(lat, lon)| map.contains_key(*lat) && map.contains_key(*lon))
+            else {
+                return Err("objeto sem par lat/lon".into());
+            };
+            coordenada(&mut map[*lat], fuzz, Eixo::Lat)?;
+            coordenada(&mut map[*lon], fuzz, Eixo::Lon)
+        }
+        _ => Err("valor não é uma localização".into()),
+    }
+}
+
+// Posição [lon, lat, ...] ou arrays aninhados de posições
+fn posicoes(value: &mut Value, fuzz: GeoFuzz) -> Result<(), String> {
+    let Value::Array(itens) = value else {
+        return Err("coordinates deve ser array".into());
+    };
+    if itens.first().is_some_and(Value::is_number) {
+        // Altitude (3º elemento) é mantida
+        return itens
+            .iter_mut()
+            .zip([Eixo::Lon, Eixo::Lat])
+            .try_for_each(|(c, eixo)| coordenada(c, fuzz, eixo));
+    }
+    itens.iter_mut().try_for_each(|item| posicoes(item, fuzz))
+}
+
+fn coordenada(value: &mut Value, fuzz: GeoFuzz, eixo: Eixo) -> Result<(), String> {
+    let x = value
+        .as_f64()
+        .ok_or_else(|| format!("coordenada não numérica: {value}"))?;
+    let novo = Number::from_f64(fuzz.aplicar(x, eixo)).ok_or("coordenada não finita")?;
+    *value = Value::Number(novo);
+    Ok(())
+}
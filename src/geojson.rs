@@ -0,0 +1,322 @@
+use serde_json::{Map, Value};
+
+// Builders e validador de GeoJSON (RFC 7946).
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+const GEOMETRIAS: [&str; 7] = [
+    "Point",
+    "MultiPoint",
+    "LineString",
+    "MultiLineString",
+    "Polygon",
+    "MultiPolygon",
+    "GeometryCollection",
+];
+
+// point!(lon, lat) ou point!(lon, lat, alt)
+#[macro_export]
+macro_rules! point {
+    ($($coord:expr),+ $(,)?) => {
+        $crate::geojson::point(&[$(::std::convert::Into::<f64>::into($coord)),+])
+    };
+}
+
+// feature!(geometria) ou feature!(geometria, { props... })
+#[macro_export]
+macro_rules! feature {
+    ($geometry:expr) => {
+        $crate::geojson::feature($geometry, $crate::__private::serde_json::Value::Null)
+    };
+    ($geometry:expr, $props:tt) => {
+        $crate::json_safe!($props)
+            .map_err($crate::__private::serde_json::Error::from)
+            .and_then(|props| $crate::geojson::feature($geometry, props))
+    };
+}
+
+// feature_collection!(f1, f2, ...)
+#[macro_export]
+macro_rules! feature_collection {
+    ($($feature:expr),* $(,)?) => {
+        $crate::geojson::feature_collection(::std::vec![$($feature),*])
+    };
+}
+
+/// Monta um `Point` a partir de `[lon, lat]` ou `[lon, lat, alt]`.
+pub fn point(position: &[f64]) -> serde_json::Result<Value> {
+    if !(2..=3).contains(&position.len()) {
+        return Err(erro(format!(
+            "posição deve ter 2 ou 3 coordenadas, recebeu {}",
+            position.len()
+        )));
+    }
+    let coords = position
+        .iter()
+        .map(|c| {
+            serde_json::Number::from_f64(*c)
+                .map(Value::Number)
+                .ok_or_else(|| erro(format!("coordenada não finita: {c}")))
+        })
+        .collect::<serde_json::Result<Vec<_>>>()?;
+
+    let mut map = Map::new();
+    map.insert("type".into(), Value::from("Point"));
+    map.insert("coordinates".into(), Value::Array(coords));
+    Ok(Value::Object(map))
+}
+
+/// Monta uma `Feature`; `geometry` deve ser uma geometria ou `null`, e
+/// `properties` um objeto ou `null`.
+pub fn feature(geometry: Value, properties: Value) -> serde_json::Result<Value> {
+    if !geometry.is_null() && !tipo_em(&geometry, &GEOMETRIAS) {
+        return Err(erro(
+            "geometry de Feature não é uma geometria GeoJSON".into(),
+        ));
+    }
+    if !properties.is_null() && !properties.is_object() {
+        return Err(erro("properties de Feature deve ser objeto ou null".into()));
+    }
+
+    let mut map = Map::new();
+    map.insert("type".into(), Value::from("Feature"));
+    map.insert("geometry".into(), geometry);
+    map.insert("properties".into(), properties);
+    Ok(Value::Object(map))
+}
+
+/// Monta uma `FeatureCollection`; todos os itens devem ser `Feature`.
+pub fn feature_collection(features: Vec<Value>) -> serde_json::Result<Value> {
+    if let Some(i) = features.iter().position(|f| !tipo_em(f, &["Feature"])) {
+        return Err(erro(format!("item {i} da FeatureCollection não é Feature")));
+    }
+
+    let mut map = Map::new();
+    map.insert("type".into(), Value::from("FeatureCollection"));
+    map.insert("features".into(), Value::Array(features));
+    Ok(Value::Object(map))
+}
+
+fn tipo_em(value: &Value, tipos: &[&str]) -> bool {
+    value
+        .get("type")
+        .and_then(Value::as_str)
+        .is_some_and(|t| tipos.contains(&t))
+}
+
+/// Problema encontrado pelo validador, com o JSON Pointer do ponto exato.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub path: String,
+    pub message: String,
+}
+
+/// Valida regras de geometria do GeoJSON: estrutura das posições, anéis
+/// fechados, orientação dos anéis (exterior anti-horário, buracos
+/// horário) e `bbox`. Retorna todos os problemas encontrados.
+pub fn validate(value: &Value) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    validar_objeto(value, "", &mut issues);
+    issues
+}
+
+fn reportar(issues: &mut Vec<Issue>, path: &str, message: impl Into<String>) {
+    issues.push(Issue {
+        path: path.to_string(),
+        message: message.into(),
+    });
+}
+
+fn validar_objeto(value: &Value, path: &str, issues: &mut Vec<Issue>) {
+    let Some(tipo) = value.get("type").and_then(Value::as_str) else {
+        reportar(issues, path, "objeto GeoJSON sem 'type'");
+        return;
+    };
+
+    if let Some(bbox) = value.get("bbox") {
+        validar_bbox(bbox, &format!("{path}/bbox"), issues);
+    }
+
+    match tipo {
+        "FeatureCollection" => match value.get("features") {
+            Some(Value::Array(features)) => {
+                for (i, f) in features.iter().enumerate() {
+                    let p = format!("{path}/features/{i}");
+                    if tipo_em(f, &["Feature"]) {
+                        validar_objeto(f, &p, issues);
+                    } else {
+                        reportar(issues, &p, "item não é Feature");
+                    }
+                }
+            }
+            _ => reportar(issues, path, "'features' deve ser um array"),
+        },
+        "Feature" => {
+            match value.get("geometry") {
+                Some(Value::Null) => {}
+                Some(g) => validar_objeto(g, &format!("{path}/geometry"), issues),
+                None => reportar(issues, path, "Feature sem 'geometry'"),
+            }
+            match value.get("properties") {
+                Some(Value::Null | Value::Object(_)) => {}
+                _ => reportar(issues, path, "'properties' deve ser objeto ou null"),
+            }
+        }
+        "GeometryCollection" => match value.get("geometries") {
+            Some(Value::Array(geometrias)) => {
+                for (i, g) in geometrias.iter().enumerate() {
+                    validar_objeto(g, &format!("{path}/geometries/{i}"), issues);
+                }
+            }
+            _ => reportar(issues, path, "'geometries' deve ser um array"),
+        },
+        tipo if GEOMETRIAS.contains(&tipo) => {
+            let p = format!("{path}/coordinates");
+            match value.get("coordinates") {
+                Some(coords) => validar_coordenadas(tipo, coords, &p, issues),
+                None => reportar(issues, path, "geometria sem 'coordinates'"),
+            }
+        }
+        outro => reportar(issues, path, format!("tipo GeoJSON desconhecido: {outro}")),
+    }
+}
+
+fn validar_coordenadas(tipo: &str, coords: &Value, path: &str, issues: &mut Vec<Issue>) {
+    match tipo {
+        "Point" => {
+            posicao(coords, path, issues);
+        }
+        "MultiPoint" => {
+            for_each(coords, path, issues, |c, p, issues| {
+                posicao(c, p, issues);
+            });
+        }
+        "LineString" => linha(coords, path, issues),
+        "MultiLineString" => for_each(coords, path, issues, linha),
+        "Polygon" => poligono(coords, path, issues),
+        "MultiPolygon" => for_each(coords, path, issues, poligono),
+        _ => {}
+    }
+}
+
+fn for_each(
+    coords: &Value,
+    path: &str,
+    issues: &mut Vec<Issue>,
+    mut f: impl FnMut(&Value, &str, &mut Vec<Issue>),
+) {
+    match coords {
+        Value::Array(itens) => {
+            for (i, item) in itens.iter().enumerate() {
+                f(item, &format!("{path}/{i}"), issues);
+            }
+        }
+        _ => reportar(issues, path, "esperava um array"),
+    }
+}
+
+fn posicao(value: &Value, path: &str, issues: &mut Vec<Issue>) -> Option<(f64, f64)> {
+    let numeros: Option<Vec<f64>> = value
+        .as_array()
+        .map(|a| a.iter().map(Value::as_f64).collect())
+        .unwrap_or(None);
+
+    match numeros.as_deref() {
+        Some(&[lon, lat]) | Some(&[lon, lat, _]) => {
+            if !(-180.0..=180.0).contains(&lon) {
+                reportar(
+                    issues,
+                    path,
+                    format!("longitude fora de [-180, 180]: {lon}"),
+                );
+            }
+            if !(-90.0..=90.0).contains(&lat) {
+                reportar(issues, path, format!("latitude fora de [-90, 90]: {lat}"));
+            }
+            Some((lon, lat))
+        }
+        _ => {
+            reportar(
+                issues,
+                path,
+                "posição deve ser [lon, lat] ou [lon, lat, alt]",
+            );
+            None
+        }
+    }
+}
+
+fn linha(coords: &Value, path: &str, issues: &mut Vec<Issue>) {
+    let mut n = 0;
+    for_each(coords, path, issues, |c, p, issues| {
+        n += 1;
+        posicao(c, p, issues);
+    });
+    if coords.is_array() && n < 2 {
+        reportar(issues, path, "LineString precisa de ao menos 2 posições");
+    }
+}
+
+fn poligono(coords: &Value, path: &str, issues: &mut Vec<Issue>) {
+    let mut indice = 0;
+    for_each(coords, path, issues, |anel, p, issues| {
+        validar_anel(anel, p, indice == 0, issues);
+        indice += 1;
+    });
+}
+
+fn validar_anel(anel: &Value, path: &str, exterior: bool, issues: &mut Vec<Issue>) {
+    let mut pontos = Vec::new();
+    let mut completo = true;
+    for_each(anel, path, issues, |c, p, issues| {
+        match posicao(c, p, issues) {
+            Some(ponto) => pontos.push(ponto),
+            None => completo = false,
+        }
+    });
+    if !anel.is_array() || !completo {
+        return;
+    }
+
+    if pontos.len() < 4 {
+        reportar(issues, path, "anel linear precisa de ao menos 4 posições");
+        return;
+    }
+    if pontos.first() != pontos.last() {
+        reportar(issues, path, "anel linear não está fechado");
+        return;
+    }
+
+    // Área com sinal (shoelace): positiva = anti-horário
+    let area: f64 = pontos
+        .windows(2)
+        .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+        .sum();
+    if exterior && area < 0.0 {
+        reportar(
+            issues,
+            path,
+            "anel exterior deve estar em sentido anti-horário",
+        );
+    } else if !exterior && area > 0.0 {
+        reportar(issues, path, "buraco deve estar em sentido horário");
+    }
+}
+
+fn validar_bbox(bbox: &Value, path: &str, issues: &mut Vec<Issue>) {
+    let numeros: Option<Vec<f64>> = bbox
+        .as_array()
+        .map(|a| a.iter().map(Value::as_f64).collect())
+        .unwrap_or(None);
+
+    match numeros.as_deref() {
+        Some([_, sul, _, norte]) | Some([_, sul, _, _, norte, _]) => {
+            if sul > norte {
+                reportar(issues, path, "bbox com sul maior que norte");
+            }
+        }
+        _ => reportar(issues, path, "bbox deve ter 4 ou 6 números"),
+    }
+}
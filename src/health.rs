@@ -0,0 +1,185 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Payload de health check/readiness no formato `{ status, checks: {...} }`.
+// As checagens rodam em paralelo, cada uma em sua thread e com seu
+// próprio timeout (não há runtime async entre as dependências do crate).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn nome(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+}
+
+/// Resultado de uma checagem individual.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub status: Status,
+    pub latency: Duration,
+    pub error: Option<String>,
+}
+
+/// Relatório agregado; o status geral é o pior entre as checagens.
+#[derive(Debug, Clone, Default)]
+pub struct HealthReport {
+    checks: BTreeMap<String, CheckResult>,
+}
+
+impl HealthReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra um resultado já obtido.
+    pub fn record(mut self, name: impl Into<String>, result: CheckResult) -> Self {
+        self.checks.insert(name.into(), result);
+        self
+    }
+
+    pub fn status(&self) -> Status {
+        self.checks
+            .values()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(Status::Pass)
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut checks = Map::new();
+        for (nome, resultado) in &self.checks {
+            let mut map = Map::new();
+            map.insert("status".into(), Value::from(resultado.status.nome()));
+            map.insert(
+                "latency_ms".into(),
+                Value::from(resultado.latency.as_millis() as u64),
+            );
+            if let Some(erro) = &resultado.error {
+                map.insert("error".into(), Value::from(erro.as_str()));
+            }
+            checks.insert(nome.clone(), Value::Object(map));
+        }
+
+        let mut map = Map::new();
+        map.insert("status".into(), Value::from(self.status().nome()));
+        map.insert("checks".into(), Value::Object(checks));
+        Value::Object(map)
+    }
+}
+
+type Checagem = Box<dyn FnOnce() -> Result<(), String> + Send + 'static>;
+
+struct Pendente {
+    nome: String,
+    timeout: Duration,
+    // Falha em checagem opcional vira `warn` em vez de `fail`
+    opcional: bool,
+    checagem: Checagem,
+}
+
+/// Conjunto de checagens a executar.
+#[derive(Default)]
+pub struct HealthChecks {
+    pendentes: Vec<Pendente>,
+}
+
+impl HealthChecks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checagem obrigatória: erro ou timeout deixa o relatório em `fail`.
+    pub fn check<F>(self, name: impl Into<String>, timeout: Duration, check: F) -> Self
+    where
+        F: FnOnce() -> Result<(), String> + Send + 'static,
+    {
+        self.adicionar(name.into(), timeout, false, Box::new(check))
+    }
+
+    /// Checagem opcional: erro ou timeout rebaixa para `warn`.
+    pub fn optional_check<F>(self, name: impl Into<String>, timeout: Duration, check: F) -> Self
+    where
+        F: FnOnce() -> Result<(), String> + Send + 'static,
+    {
+        self.adicionar(name.into(), timeout, true, Box::new(check))
+    }
+
+    fn adicionar(
+        mut self,
+        nome: String,
+        timeout: Duration,
+        opcional: bool,
+        checagem: Checagem,
+    ) -> Self {
+        self.pendentes.push(Pendente {
+            nome,
+            timeout,
+            opcional,
+            checagem,
+        });
+        self
+    }
+
+    /// Executa todas as checagens em paralelo. Uma checagem que estoura o
+    /// timeout é reportada como falha; sua thread segue até terminar, mas o
+    /// resultado é descartado.
+    pub fn run(self) -> HealthReport {
+        let em_andamento: Vec<_> = self
+            .pendentes
+            .into_iter()
+            .map(|p| {
+                let (tx, rx) = mpsc::channel();
+                let checagem = p.checagem;
+                thread::spawn(move || {
+                    let inicio = Instant::now();
+                    let resultado = checagem();
+                    let _ = tx.send((resultado, inicio.elapsed()));
+                });
+                (p.nome, p.timeout, p.opcional, rx, Instant::now())
+            })
+            .collect();
+
+        let mut relatorio = HealthReport::new();
+        for (nome, timeout, opcional, rx, inicio) in em_andamento {
+            let restante = timeout.saturating_sub(inicio.elapsed());
+            let (erro, latencia) = match rx.recv_timeout(restante) {
+                Ok((Ok(()), latencia)) => (None, latencia),
+                Ok((Err(e), latencia)) => (Some(e), latencia),
+                Err(mpsc::RecvTimeoutError::Timeout) => (
+                    Some(format!("timeout após {}ms", timeout.as_millis())),
+                    timeout,
+                ),
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    (Some("checagem abortou (panic)".into()), inicio.elapsed())
+                }
+            };
+            let status = match (&erro, opcional) {
+                (None, _) => Status::Pass,
+                (Some(_), true) => Status::Warn,
+                (Some(_), false) => Status::Fail,
+            };
+            relatorio = relatorio.record(
+                nome,
+                CheckResult {
+                    status,
+                    latency: latencia,
+                    error: erro,
+                },
+            );
+        }
+        relatorio
+    }
+}
@@ -0,0 +1,64 @@
+use serde_json::{Map, Value};
+
+use crate::canonical;
+
+// Chaves de idempotência derivadas do conteúdo: o mesmo payload (pela forma
+// canônica, ou só pelos caminhos escolhidos) gera sempre a mesma chave, para
+// reenviar POSTs com segurança.
+
+/// O que entra no cálculo da chave. Caminhos são JSON Pointers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyPolicy<'a> {
+    /// O documento inteiro.
+    Whole,
+    /// Só estes caminhos; ausentes contam como `null`.
+    Paths(&'a [&'a str]),
+    /// O documento sem estes caminhos (ex.: timestamps, ids de requisição).
+    Excluding(&'a [&'a str]),
+}
+
+fn remover(doc: &mut Value, pointer: &str) {
+    let Some((pai, ultimo)) = pointer.rsplit_once('/') else {
+        return;
+    };
+    let chave = ultimo.replace("~1", "/").replace("~0", "~");
+    match doc.pointer_mut(pai) {
+        Some(Value::Object(map)) => {
+            map.remove(&chave);
+        }
+        Some(Value::Array(itens)) => {
+            if let Ok(i) = chave.parse::<usize>()
+                && i < itens.len()
+            {
+                itens.remove(i);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// SHA-256 (hex) da parte de `value` selecionada por `policy`.
+pub fn idempotency_key(value: &Value, policy: KeyPolicy) -> String {
+    match policy {
+        KeyPolicy::Whole => canonical::canonical_hash(value),
+        KeyPolicy::Paths(pointers) => {
+            let selecao: Map<String, Value> = pointers
+                .iter()
+                .map(|p| {
+                    (
+                        p.to_string(),
+                        value.pointer(p).cloned().unwrap_or(Value::Null),
+                    )
+                })
+                .collect();
+            canonical::canonical_hash(&Value::Object(selecao))
+        }
+        KeyPolicy::Excluding(pointers) => {
+            let mut copia = value.clone();
+            for p in pointers {
+                remover(&mut copia, p);
+            }
+            canonical::canonical_hash(&copia)
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use serde_json::{Map, Value};
+
+// Visões reduzidas de documentos para logs e relatórios de erro: sem
+// carregar o payload inteiro (ou o conteúdo dele) para a saída.
+
+/// Limites de [`truncate_for_debug`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    max_string: usize,
+    max_items: usize,
+    max_keys: usize,
+    max_depth: usize,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            max_string: 256,
+            max_items: 20,
+            max_keys: 50,
+            max_depth: 8,
+        }
+    }
+}
+
+impl Budget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Máximo de caracteres por string.
+    pub fn max_string(mut self, chars: usize) -> Self {
+        self.max_string = chars;
+        self
+    }
+
+    /// Máximo de itens por array (metade do início, metade do fim).
+    pub fn max_items(mut self, items: usize) -> Self {
+        self.max_items = items;
+        self
+    }
+
+    /// Máximo de chaves por objeto.
+    pub fn max_keys(mut self, keys: usize) -> Self {
+        self.max_keys = keys;
+        self
+    }
+
+    /// Profundidade máxima; abaixo dela objetos/arrays viram um resumo.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+}
+
+fn marcador(restantes: usize) -> Value {
+    Value::from(format!("…truncated ({restantes} more)"))
+}
+
+/// Cópia de `value` dentro de `budget`, ainda JSON válido: strings
+/// cortadas, arrays com início/fim e um marcador `…truncated (N more)`,
+/// objetos com as primeiras chaves e estruturas profundas resumidas.
+pub fn truncate_for_debug(value: &Value, budget: Budget) -> Value {
+    truncar(value, &budget, 0)
+}
+
+fn truncar(value: &Value, budget: &Budget, profundidade: usize) -> Value {
+    match value {
+        Value::String(s) => {
+            let total = s.chars().count();
+            if total <= budget.max_string {
+                return value.clone();
+            }
+            let inicio: String = s.chars().take(budget.max_string).collect();
+            Value::from(format!(
+                "{inicio}…truncated ({} more)",
+                total - budget.max_string
+            ))
+        }
+        Value::Array(itens) if profundidade >= budget.max_depth => {
+            Value::from(format!("…[{} items]", itens.len()))
+        }
+        Value::Object(map) if profundidade >= budget.max_depth => {
+            Value::from(format!("…{{{} keys}}", map.len()))
+        }
+        Value::Array(itens) => {
+            let filho = |v: &Value| truncar(v, budget, profundidade + 1);
+            if itens.len() <= budget.max_items {
+                return Value::Array(itens.iter().map(filho).collect());
+            }
+            let cabeca = budget.max_items.div_ceil(2);
+            let cauda = budget.max_items - cabeca;
+            let mut out: Vec<Value> = itens[..cabeca].iter().map(filho).collect();
+            out.push(marcador(itens.len() - cabeca - cauda));
+            out.extend(itens[itens.len() - cauda..].iter().map(filho));
+            Value::Array(out)
+        }
+        Value::Object(map) => {
+            let mut out: Map<String, Value> = map
+                .iter()
+                .take(budget.max_keys)
+                .map(|(k, v)| (k.clone(), truncar(v, budget, profundidade + 1)))
+                .collect();
+            if map.len() > budget.max_keys {
+                out.insert("…".into(), marcador(map.len() - budget.max_keys));
+            }
+            Value::Object(out)
+        }
+        escalar => escalar.clone(),
+    }
+}
+
+/// Nome do tipo JSON, distinguindo `integer` de `number`.
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Esqueleto de `value` até `depth` níveis: chaves e tipos, sem valores.
+/// Arrays aparecem como `array(N)<tipos>` ou, com objetos dentro, como
+/// `{ "array(N)": <esqueleto da união das chaves dos itens> }`; abaixo do
+/// limite, objetos viram `object(N keys)`.
+pub fn outline(value: &Value, depth: usize) -> Value {
+    match value {
+        Value::Object(map) if depth == 0 => Value::from(format!("object({} keys)", map.len())),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), outline(v, depth - 1)))
+                .collect(),
+        ),
+        Value::Array(itens) => {
+            let mut tipos: Vec<&str> = Vec::new();
+            for item in itens {
+                if !tipos.contains(&type_name(item)) {
+                    tipos.push(type_name(item));
+                }
+            }
+            let rotulo = format!("array({})", itens.len());
+            let de_objetos =
+                tipos.contains(&"object") && tipos.iter().all(|t| matches!(*t, "object" | "null"));
+            if depth == 0 || !de_objetos {
+                return Value::from(format!("{rotulo}<{}>", tipos.join("|")));
+            }
+            // União das chaves dos itens, pelo primeiro item que tem cada chave
+            let mut uniao = Map::new();
+            for item in itens.iter().filter_map(Value::as_object) {
+                for (k, v) in item {
+                    if !uniao.contains_key(k) {
+                        uniao.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+            let mut map = Map::new();
+            map.insert(rotulo, outline(&Value::Object(uniao), depth - 1));
+            Value::Object(map)
+        }
+        escalar => Value::from(type_name(escalar)),
+    }
+}
+
+/// Mesma estrutura de `value`, com cada escalar trocado pelo nome do seu
+/// tipo (`"string"`, `"integer"`, `"number"`, `"boolean"`, `"null"`), para
+/// comparar o formato de dois documentos independente dos dados.
+pub fn type_map(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            Value::Object(map.iter().map(|(k, v)| (k.clone(), type_map(v))).collect())
+        }
+        Value::Array(itens) => Value::Array(itens.iter().map(type_map).collect()),
+        escalar => Value::from(type_name(escalar)),
+    }
+}
@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+
+use crate::scoped::Politica;
+
+// i128/u128 em `json_safe!`: saem como número quando cabem em i64/u64. Fora
+// dessa faixa, o padrão é erro; com `Overflow::Stringify` saem como string
+// decimal ("340282366920938463463374607431768211455"), que não perde dígitos
+// em nenhum parser. No processo todo (`set_policy`) ou por invocação:
+// `json_safe!(int128_string { ... })`. O modo `strict_numbers` sempre recusa.
+// Com a feature "arbitrary-precision" e sem política explícita, saem como
+// número exato.
+
+/// O que fazer com um i128/u128 fora de i64/u64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Erro na conversão (padrão).
+    #[default]
+    Error,
+    /// Vira string decimal.
+    Stringify,
+}
+
+thread_local! {
+    static ATUAL: RefCell<Option<Overflow>> = const { RefCell::new(None) };
+}
+
+static POLITICA: Politica<Overflow> = Politica::new(&ATUAL);
+
+/// Política padrão do processo.
+pub fn set_policy(policy: Overflow) {
+    POLITICA.set(policy);
+}
+
+/// Política em vigor na thread atual.
+pub fn policy() -> Overflow {
+    POLITICA.get()
+}
+
+// Política definida por `set_policy` ou `with_policy`, se houver
+pub(crate) fn explicit_policy() -> Option<Overflow> {
+    POLITICA.explicita()
+}
+
+/// Executa `f` com `policy` na thread atual.
+pub fn with_policy<T>(policy: Overflow, f: impl FnOnce() -> T) -> T {
+    POLITICA.with(policy, f)
+}
@@ -0,0 +1,188 @@
+use serde_json::{Map, Value};
+
+// Suporte mínimo a JSON-LD: só as chaves são expandidas/compactadas pelo
+// `@context`; valores não são reescritos na forma expandida completa
+// (`@value`, arrays obrigatórios etc.) e contextos remotos não são buscados.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Default)]
+struct Contexto {
+    vocab: Option<String>,
+    termos: Map<String, Value>,
+}
+
+impl Contexto {
+    fn aplicar(&self, ctx: &Value) -> serde_json::Result<Contexto> {
+        let mut novo = self.clone();
+        match ctx {
+            Value::Null => novo = Contexto::default(),
+            Value::Array(itens) => {
+                for item in itens {
+                    novo = novo.aplicar(item)?;
+                }
+            }
+            Value::Object(map) => {
+                for (termo, definicao) in map {
+                    if termo == "@vocab" {
+                        novo.vocab = definicao.as_str().map(str::to_string);
+                        continue;
+                    }
+                    let iri = match definicao {
+                        Value::String(iri) => iri.clone(),
+                        Value::Object(def) => match def.get("@id").and_then(Value::as_str) {
+                            Some(iri) => iri.to_string(),
+                            None => return Err(erro(format!("termo {termo:?} sem @id"))),
+                        },
+                        _ => return Err(erro(format!("definição inválida para {termo:?}"))),
+                    };
+                    novo.termos.insert(termo.clone(), Value::String(iri));
+                }
+            }
+            Value::String(url) => {
+                return Err(erro(format!("contexto remoto não suportado: {url}")));
+            }
+            _ => return Err(erro("@context deve ser objeto, array ou null".into())),
+        }
+        Ok(novo)
+    }
+
+    fn termo(&self, termo: &str) -> Option<&str> {
+        self.termos.get(termo).and_then(Value::as_str)
+    }
+
+    fn expandir_chave(&self, chave: &str) -> String {
+        if chave.starts_with('@') {
+            return chave.to_string();
+        }
+        if let Some(iri) = self.termo(chave) {
+            return self.expandir_chave_iri(iri);
+        }
+        if let Some((prefixo, sufixo)) = chave.split_once(':') {
+            if sufixo.starts_with("//") {
+                return chave.to_string();
+            }
+            if let Some(base) = self.termo(prefixo) {
+                return format!("{base}{sufixo}");
+            }
+            return chave.to_string();
+        }
+        match &self.vocab {
+            Some(vocab) => format!("{vocab}{chave}"),
+            None => chave.to_string(),
+        }
+    }
+
+    // Definições podem usar prefixos: { "nome": "schema:name" }
+    fn expandir_chave_iri(&self, iri: &str) -> String {
+        match iri.split_once(':') {
+            Some((prefixo, sufixo)) if !sufixo.starts_with("//") => match self.termo(prefixo) {
+                Some(base) if base != iri => format!("{base}{sufixo}"),
+                _ => iri.to_string(),
+            },
+            _ => iri.to_string(),
+        }
+    }
+
+    fn compactar_chave(&self, iri: &str) -> String {
+        if iri.starts_with('@') {
+            return iri.to_string();
+        }
+        // 1) termo exato
+        for (termo, def) in &self.termos {
+            if termo.contains(':') {
+                continue;
+            }
+            if let Some(def) = def.as_str()
+                && self.expandir_chave_iri(def) == iri
+                && !(def.ends_with('/') || def.ends_with('#'))
+            {
+                return termo.clone();
+            }
+        }
+        // 2) relativo ao @vocab
+        if let Some(sufixo) = self.vocab.as_deref().and_then(|v| iri.strip_prefix(v))
+            && !sufixo.is_empty()
+            && !sufixo.contains(':')
+            && self.termo(sufixo).is_none()
+        {
+            return sufixo.to_string();
+        }
+        // 3) IRI compacto com o prefixo mais longo
+        let melhor = self
+            .termos
+            .iter()
+            .filter_map(|(termo, def)| {
+                let base = def.as_str()?;
+                let sufixo = iri.strip_prefix(base)?;
+                (!sufixo.is_empty() && !termo.contains(':')).then_some((base.len(), termo, sufixo))
+            })
+            .max_by_key(|(tamanho, _, _)| *tamanho);
+        match melhor {
+            Some((_, prefixo, sufixo)) => format!("{prefixo}:{sufixo}"),
+            None => iri.to_string(),
+        }
+    }
+}
+
+/// Expande as chaves de `doc` para IRIs completos usando o `@context`
+/// embutido (inclusive contextos aninhados). O `@context` é removido.
+pub fn expand(doc: &Value) -> serde_json::Result<Value> {
+    expandir(doc, &Contexto::default())
+}
+
+fn expandir(value: &Value, ctx: &Contexto) -> serde_json::Result<Value> {
+    match value {
+        Value::Object(map) => {
+            let ctx = match map.get("@context") {
+                Some(local) => ctx.aplicar(local)?,
+                None => ctx.clone(),
+            };
+            let mut out = Map::new();
+            for (chave, valor) in map {
+                if chave == "@context" {
+                    continue;
+                }
+                out.insert(ctx.expandir_chave(chave), expandir(valor, &ctx)?);
+            }
+            Ok(Value::Object(out))
+        }
+        Value::Array(itens) => itens
+            .iter()
+            .map(|item| expandir(item, ctx))
+            .collect::<serde_json::Result<Vec<_>>>()
+            .map(Value::Array),
+        outro => Ok(outro.clone()),
+    }
+}
+
+/// Compacta as chaves (IRIs) de `doc` para termos/prefixos de `context` e
+/// anexa o `@context` na raiz do resultado.
+pub fn compact(doc: &Value, context: &Value) -> serde_json::Result<Value> {
+    let ctx = Contexto::default().aplicar(context)?;
+    let expandido = expand(doc)?;
+    let mut out = match compactar(&expandido, &ctx) {
+        Value::Object(map) => map,
+        outro => {
+            let mut map = Map::new();
+            map.insert("@graph".into(), outro);
+            map
+        }
+    };
+    out.insert("@context".into(), context.clone());
+    Ok(Value::Object(out))
+}
+
+fn compactar(value: &Value, ctx: &Contexto) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(chave, valor)| (ctx.compactar_chave(chave), compactar(valor, ctx)))
+                .collect(),
+        ),
+        Value::Array(itens) => Value::Array(itens.iter().map(|i| compactar(i, ctx)).collect()),
+        outro => outro.clone(),
+    }
+}
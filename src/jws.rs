@@ -0,0 +1,98 @@
+use serde_json::{Map, Value};
+
+use crate::{base64, digest};
+
+// JWS em serialização compacta (RFC 7515): `header.payload.signature`, com
+// as duas primeiras partes em base64url do JSON serializado pelo serde_json.
+// O algoritmo é plugável; HS256 vem embutido.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Algoritmo de assinatura (HS256, RS256 via biblioteca externa, ...).
+pub trait Signer {
+    /// Valor do `alg` no header.
+    fn alg(&self) -> &str;
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>, String>;
+    fn verify(&self, signing_input: &[u8], signature: &[u8]) -> bool;
+}
+
+/// HMAC-SHA256 com segredo compartilhado.
+#[derive(Clone)]
+pub struct Hs256 {
+    secret: Vec<u8>,
+}
+
+impl Hs256 {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Hs256 {
+            secret: secret.into(),
+        }
+    }
+}
+
+impl Signer for Hs256 {
+    fn alg(&self) -> &str {
+        "HS256"
+    }
+
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>, String> {
+        Ok(digest::hmac_sha256(&self.secret, signing_input).to_vec())
+    }
+
+    fn verify(&self, signing_input: &[u8], signature: &[u8]) -> bool {
+        let esperado = digest::hmac_sha256(&self.secret, signing_input);
+        // Comparação em tempo constante
+        signature.len() == esperado.len()
+            && signature
+                .iter()
+                .zip(esperado)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+/// Assina `claims` (um objeto) com header `{ alg, typ: "JWT" }`.
+pub fn sign(claims: &Value, signer: &dyn Signer) -> serde_json::Result<String> {
+    if !claims.is_object() {
+        return Err(erro("claims devem ser um objeto".into()));
+    }
+    let mut header = Map::new();
+    header.insert("alg".into(), Value::from(signer.alg()));
+    header.insert("typ".into(), Value::from("JWT"));
+
+    let entrada = format!(
+        "{}.{}",
+        base64::encode_url(&serde_json::to_vec(&header)?),
+        base64::encode_url(&serde_json::to_vec(claims)?)
+    );
+    let assinatura = signer.sign(entrada.as_bytes()).map_err(erro)?;
+    Ok(format!("{entrada}.{}", base64::encode_url(&assinatura)))
+}
+
+/// Verifica a assinatura e devolve as claims. O `alg` do header precisa ser
+/// o do `signer` (`none` nunca é aceito). Não valida `exp`/`nbf`.
+pub fn verify(token: &str, signer: &dyn Signer) -> serde_json::Result<Value> {
+    let partes: Vec<&str> = token.split('.').collect();
+    let [header_b64, payload_b64, assinatura_b64] = partes[..] else {
+        return Err(erro("token JWS deve ter 3 partes".into()));
+    };
+    let decodificar = |parte: &str, nome: &str| {
+        base64::decode_url(parte).ok_or_else(|| erro(format!("{nome}: base64url inválido")))
+    };
+
+    let header: Value = serde_json::from_slice(&decodificar(header_b64, "header")?)?;
+    match header["alg"].as_str() {
+        Some(alg) if alg == signer.alg() => {}
+        Some(alg) => return Err(erro(format!("alg não suportado: {alg}"))),
+        None => return Err(erro("header sem alg".into())),
+    }
+
+    let assinatura = decodificar(assinatura_b64, "signature")?;
+    let entrada = &token[..header_b64.len() + 1 + payload_b64.len()];
+    if !signer.verify(entrada.as_bytes(), &assinatura) {
+        return Err(erro("assinatura inválida".into()));
+    }
+    serde_json::from_slice(&decodificar(payload_b64, "payload")?)
+}
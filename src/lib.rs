@@ -0,0 +1,603 @@
+// json_safe!: construção de serde_json::Value em que cada valor passa por
+// serde_json::to_value e o primeiro erro é propagado, em vez de panic, como
+// `JsonSafeError` com o caminho do campo que falhou.
+
+#[macro_export]
+macro_rules! json_safe {
+    // Interno: insere os membros do objeto em `$map`, um por vez. Cada forma
+    // de chave (ident, literal, (expr) calculada com Into<String>) aceita um
+    // valor de um token (objetos, arrays, null, literais) ou, se não casar,
+    // uma expressão comum (ex.: u.id, -1.5).
+    (@object $map:ident ()) => {};
+    // Campo com predicado: chave: skip_if!(expr, |v| ...); se o predicado
+    // for verdadeiro para &expr, a chave é omitida
+    (@object $map:ident ($key:ident : skip_if!($value:expr, $pred:expr $(,)?) $(, $($rest:tt)*)?)) => {
+        $crate::json_safe!(@skip_if $map ($crate::__private::ident(::std::stringify!($key))) ($value) ($pred) ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:literal : skip_if!($value:expr, $pred:expr $(,)?) $(, $($rest:tt)*)?)) => {
+        $crate::json_safe!(@skip_if $map ($key) ($value) ($pred) ($($($rest)*)?));
+    };
+    (@object $map:ident (($key:expr) : skip_if!($value:expr, $pred:expr $(,)?) $(, $($rest:tt)*)?)) => {
+        $crate::json_safe!(@skip_if $map ($key) ($value) ($pred) ($($($rest)*)?));
+    };
+    (@skip_if $map:ident ($key:expr) ($value:expr) ($pred:expr) ($($rest:tt)*)) => {
+        let item = &$value;
+        if !$crate::__private::skip_if(item, $pred) {
+            let chave = ::std::convert::Into::<::std::string::String>::into($key);
+            let valor = $crate::__private::key(&chave, || $crate::__private::to_value(item))?;
+            $map.insert(chave, valor);
+        }
+        $crate::json_safe!(@object $map ($($rest)*));
+    };
+    (@object $map:ident ($key:ident : $value:tt $(, $($rest:tt)*)?)) => {
+        $map.insert(
+            ::std::string::String::from($crate::__private::ident(::std::stringify!($key))),
+            $crate::__private::key($crate::__private::ident(::std::stringify!($key)), || $crate::json_safe!(@value $value))?,
+        );
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:literal : $value:tt $(, $($rest:tt)*)?)) => {
+        $map.insert(
+            ::std::string::String::from($key),
+            $crate::__private::key($key, || $crate::json_safe!(@value $value))?,
+        );
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident (($key:expr) : $value:tt $(, $($rest:tt)*)?)) => {
+        let chave = ::std::convert::Into::<::std::string::String>::into($key);
+        let valor = $crate::__private::key(&chave, || $crate::json_safe!(@value $value))?;
+        $map.insert(chave, valor);
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:ident : $value:expr $(, $($rest:tt)*)?)) => {
+        $map.insert(
+            ::std::string::String::from($crate::__private::ident(::std::stringify!($key))),
+            $crate::__private::key($crate::__private::ident(::std::stringify!($key)), || $crate::__private::to_value_lexeme(::std::stringify!($value), $value))?,
+        );
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:literal : $value:expr $(, $($rest:tt)*)?)) => {
+        $map.insert(
+            ::std::string::String::from($key),
+            $crate::__private::key($key, || $crate::__private::to_value_lexeme(::std::stringify!($value), $value))?,
+        );
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident (($key:expr) : $value:expr $(, $($rest:tt)*)?)) => {
+        let chave = ::std::convert::Into::<::std::string::String>::into($key);
+        let valor = $crate::__private::key(&chave, || $crate::__private::to_value_lexeme(::std::stringify!($value), $value))?;
+        $map.insert(chave, valor);
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    // Campo opcional: chave?: expr, com expr: Option; `None` omite a chave
+    (@object $map:ident ($key:ident ? : $value:tt $(, $($rest:tt)*)?)) => {
+        if let ::std::option::Option::Some(item) = ::std::option::Option::as_ref(&$value) {
+            $map.insert(
+                ::std::string::String::from($crate::__private::ident(::std::stringify!($key))),
+                $crate::__private::key($crate::__private::ident(::std::stringify!($key)), || $crate::__private::to_value(item))?,
+            );
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:literal ? : $value:tt $(, $($rest:tt)*)?)) => {
+        if let ::std::option::Option::Some(item) = ::std::option::Option::as_ref(&$value) {
+            $map.insert(
+                ::std::string::String::from($key),
+                $crate::__private::key($key, || $crate::__private::to_value(item))?,
+            );
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident (($key:expr) ? : $value:tt $(, $($rest:tt)*)?)) => {
+        if let ::std::option::Option::Some(item) = ::std::option::Option::as_ref(&$value) {
+            let chave = ::std::convert::Into::<::std::string::String>::into($key);
+            let valor = $crate::__private::key(&chave, || $crate::__private::to_value(item))?;
+            $map.insert(chave, valor);
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:ident ? : $value:expr $(, $($rest:tt)*)?)) => {
+        if let ::std::option::Option::Some(item) = ::std::option::Option::as_ref(&$value) {
+            $map.insert(
+                ::std::string::String::from($crate::__private::ident(::std::stringify!($key))),
+                $crate::__private::key($crate::__private::ident(::std::stringify!($key)), || $crate::__private::to_value(item))?,
+            );
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:literal ? : $value:expr $(, $($rest:tt)*)?)) => {
+        if let ::std::option::Option::Some(item) = ::std::option::Option::as_ref(&$value) {
+            $map.insert(
+                ::std::string::String::from($key),
+                $crate::__private::key($key, || $crate::__private::to_value(item))?,
+            );
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident (($key:expr) ? : $value:expr $(, $($rest:tt)*)?)) => {
+        if let ::std::option::Option::Some(item) = ::std::option::Option::as_ref(&$value) {
+            let chave = ::std::convert::Into::<::std::string::String>::into($key);
+            let valor = $crate::__private::key(&chave, || $crate::__private::to_value(item))?;
+            $map.insert(chave, valor);
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    // Atalho: { foo } equivale a { foo: foo }, serializando por referência
+    (@object $map:ident ($key:ident $(, $($rest:tt)*)?)) => {
+        $map.insert(
+            ::std::string::String::from($crate::__private::ident(::std::stringify!($key))),
+            $crate::__private::key($crate::__private::ident(::std::stringify!($key)), || $crate::__private::to_value(&$key))?,
+        );
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    // Flatten: ..flatten!(expr) serializa expr (ex.: uma struct) e copia os
+    // campos, como #[serde(flatten)]; erro se não virar objeto
+    (@object $map:ident (.. flatten!($base:expr $(,)?) $(, $($rest:tt)*)?)) => {
+        $crate::__private::flatten(
+            &mut $map,
+            $crate::__private::to_value(&$base)?,
+        )?;
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    // Spread: ..expr copia as entradas de um objeto (chaves seguintes vencem)
+    (@object $map:ident (.. $base:expr $(, $($rest:tt)*)?)) => {
+        $crate::__private::spread(
+            &mut $map,
+            $crate::__private::to_value(&$base)?,
+        )?;
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    // Campo condicional: if cond => membro (qualquer forma de membro)
+    (@object $map:ident (if $cond:expr => $($rest:tt)*)) => {
+        $crate::json_safe!(@if $map ($cond) () ($($rest)*));
+    };
+    // Interno: acumula os tokens do membro condicional até a próxima vírgula
+    // fora de parênteses/colchetes/chaves. Vírgulas soltas no valor (turbofish
+    // `f::<A, B>()`, closures `|a, b| ..`) encerram o membro: escreva o valor
+    // entre parênteses, `if c => x: (f::<A, B>())`. O json_safe_pm! não tem
+    // essa restrição.
+    (@if $map:ident ($cond:expr) ($($membro:tt)*) ($(, $($rest:tt)*)?)) => {
+        if $cond {
+            $crate::json_safe!(@object $map ($($membro)*));
+        }
+        $crate::json_safe!(@object $map ($($($rest)*)?));
+    };
+    (@if $map:ident ($cond:expr) ($($membro:tt)*) ($next:tt $($rest:tt)*)) => {
+        $crate::json_safe!(@if $map ($cond) ($($membro)* $next) ($($rest)*));
+    };
+
+    // Interno: empilha os elementos em `$vec`
+    (@array $vec:ident ()) => {};
+    // Compreensão: for pat in iter => valor, um elemento por iteração
+    (@array $vec:ident (for $pat:pat in $iter:expr => $body:tt $(, $($rest:tt)*)?)) => {
+        for $pat in $iter {
+            $vec.push($crate::__private::index($vec.len(), || $crate::json_safe!(@value $body))?);
+        }
+        $crate::json_safe!(@array $vec ($($($rest)*)?));
+    };
+    (@array $vec:ident (for $pat:pat in $iter:expr => $body:expr $(, $($rest:tt)*)?)) => {
+        for $pat in $iter {
+            $vec.push($crate::__private::index($vec.len(), || $crate::__private::to_value_lexeme(::std::stringify!($body), $body))?);
+        }
+        $crate::json_safe!(@array $vec ($($($rest)*)?));
+    };
+    // Spread: ..iter converte cada item
+    (@array $vec:ident (.. $iter:expr $(, $($rest:tt)*)?)) => {
+        for item in ::std::iter::IntoIterator::into_iter($iter) {
+            $vec.push($crate::__private::index($vec.len(), || $crate::__private::to_value(item))?);
+        }
+        $crate::json_safe!(@array $vec ($($($rest)*)?));
+    };
+    // Repetição: [valor; n], serializado uma vez e clonado
+    (@array $vec:ident ($elem:tt ; $n:expr)) => {
+        let item = $crate::__private::index(0, || $crate::json_safe!(@value $elem))?;
+        $vec.extend(::std::iter::repeat_n(item, $n));
+    };
+    (@array $vec:ident ($elem:tt $(, $($rest:tt)*)?)) => {
+        $vec.push($crate::__private::index($vec.len(), || $crate::json_safe!(@value $elem))?);
+        $crate::json_safe!(@array $vec ($($($rest)*)?));
+    };
+    // Elemento com mais de um token (ex.: -1.5, f(x)): expressão comum
+    (@array $vec:ident ($elem:expr ; $n:expr)) => {
+        let item = $crate::__private::index(0, || $crate::__private::to_value_lexeme(::std::stringify!($elem), $elem))?;
+        $vec.extend(::std::iter::repeat_n(item, $n));
+    };
+    (@array $vec:ident ($elem:expr $(, $($rest:tt)*)?)) => {
+        $vec.push($crate::__private::index($vec.len(), || $crate::__private::to_value_lexeme(::std::stringify!($elem), $elem))?);
+        $crate::json_safe!(@array $vec ($($($rest)*)?));
+    };
+
+    // Interno: @value avalia um valor; a entrada pública (no fim) é a
+    // mesma coisa, isolada de um `all` externo
+    // Objeto vazio: {}
+    (@value {}) => {
+        $crate::__private::Result::Ok(
+            $crate::__private::serde_json::Value::Object($crate::__private::serde_json::Map::new()),
+        )
+    };
+
+    // Objeto com chaves identificadores, literais ou calculadas, misturadas,
+    // atalhos, spread, opcionais e condicionais:
+    // { ..base, foo: 1, "bar-baz": 2, (k): 3, bar, baz?: opt, if c => x: 1 }
+    (@value { $($body:tt)+ }) => {{
+        (|| -> $crate::__private::Result {
+            let mut map = $crate::__private::serde_json::Map::new();
+            $crate::json_safe!(@object map ($($body)+));
+            ::std::result::Result::Ok($crate::__private::serde_json::Value::Object(map))
+        })()
+    }};
+
+    // Array vazio: []
+    (@value []) => {
+        $crate::__private::Result::Ok(
+            $crate::__private::serde_json::Value::Array(::std::vec::Vec::new()),
+        )
+    };
+
+    // Array, com spread de iteradores e compreensões, ou repetição:
+    // [ a, ..iter, for u in users => { id: u.id } ], [ linha; 10 ]
+    (@value [ $($body:tt)+ ]) => {{
+        (|| -> $crate::__private::Result {
+            let mut vec: ::std::vec::Vec<$crate::__private::serde_json::Value> =
+                ::std::vec::Vec::new();
+            $crate::json_safe!(@array vec ($($body)+));
+            ::std::result::Result::Ok($crate::__private::serde_json::Value::Array(vec))
+        })()
+    }};
+
+    // Opt-in: chaves com pontos viram objetos aninhados
+    // dotted { "a.b": 1 } -> { "a": { "b": 1 } }
+    (@value dotted { $($body:tt)* }) => {
+        $crate::json_safe!(@value { $($body)* })
+            .and_then(|valor| ::std::result::Result::Ok($crate::dotted::expand_dotted_keys(valor)?))
+    };
+
+    // Opt-in: chave repetida no objeto (literal, calculada ou via spread)
+    // vira erro em vez de a última vencer; vale para o nível de cima
+    (@value strict { $($body:tt)* }) => {{
+        (|| -> $crate::__private::Result {
+            #[allow(unused_mut)]
+            let mut map = $crate::__private::Strict::default();
+            $crate::json_safe!(@object map ($($body)*));
+            ::std::result::Result::Ok(map.finish()?)
+        })()
+    }};
+
+    // Opt-in: campos null são removidos, em qualquer profundidade
+    // compact { a: 1, b: None::<u8> } -> { "a": 1 }
+    (@value compact { $($body:tt)* }) => {
+        $crate::json_safe!(@value { $($body)* }).map($crate::compact::drop_nulls)
+    };
+    (@value compact [ $($body:tt)* ]) => {
+        $crate::json_safe!(@value [ $($body)* ]).map($crate::compact::drop_nulls)
+    };
+
+    // Opt-in: floats não finitos viram null ou string em vez de erro
+    // nan_null { t: f64::NAN } -> { "t": null }
+    // nan_string { t: f64::NAN } -> { "t": "NaN" }
+    (@value nan_null $body:tt) => {
+        $crate::non_finite::with_policy($crate::non_finite::NonFinite::CoerceToNull, || {
+            $crate::json_safe!(@value $body)
+        })
+    };
+    (@value nan_string $body:tt) => {
+        $crate::non_finite::with_policy($crate::non_finite::NonFinite::StringifyAsNaN, || {
+            $crate::json_safe!(@value $body)
+        })
+    };
+
+    // Opt-in: i128/u128 fora de i64/u64 viram string decimal em vez de erro
+    // int128_string { id: u128::MAX } -> { "id": "340282366920938463463374607431768211455" }
+    (@value int128_string $body:tt) => {
+        $crate::int128::with_policy($crate::int128::Overflow::Stringify, || {
+            $crate::json_safe!(@value $body)
+        })
+    };
+
+    // Opt-in: conversões numéricas com perda viram `NumericError`
+    // strict_numbers { total: 0.1f32 } -> Err (f32 viraria 0.10000000149011612)
+    (@value strict_numbers $body:tt) => {
+        $crate::numeric::strict(|| $crate::json_safe!(@value $body))
+    };
+
+    // null
+    (@value null) => {
+        $crate::__private::Result::Ok(
+            $crate::__private::serde_json::Value::Null,
+        )
+    };
+
+    // Qualquer outra expressão vira serde_json::Value via to_value; com a
+    // feature "arbitrary-precision", literais numéricos mantêm o texto
+    // original (1.10, 3.14159265358979323846264338327950288)
+    (@value $other:expr) => {
+        $crate::__private::to_value_lexeme(::std::stringify!($other), $other)
+    };
+
+    // Opt-in: não para no primeiro erro; devolve todos os campos que
+    // falharam em `Err(Vec<JsonSafeError>)`. Combina com as outras opções:
+    // all { ... }, all [ ... ], all strict { ... }
+    (all { $($body:tt)* }) => {
+        $crate::__private::accumulate(|| $crate::json_safe!(@value { $($body)* }))
+    };
+    (all [ $($body:tt)* ]) => {
+        $crate::__private::accumulate(|| $crate::json_safe!(@value [ $($body)* ]))
+    };
+    (all $opcao:ident $($body:tt)+) => {
+        $crate::__private::accumulate(|| $crate::json_safe!(@value $opcao $($body)+))
+    };
+
+    // Entrada pública; um json_safe! dentro de um `all` não se mistura a ele
+    ($($body:tt)+) => {
+        $crate::__private::isolate(|| $crate::json_safe!(@value $($body)+))
+    };
+}
+
+/// Só vale como valor de um campo do `json_safe!`:
+/// `{ nome: skip_if!(nome, |v| v.is_empty()) }` omite `nome` se vazio.
+#[macro_export]
+macro_rules! skip_if {
+    ($($body:tt)*) => {
+        ::std::compile_error!("skip_if! só vale como valor de um campo do json_safe!")
+    };
+}
+
+/// Só vale como spread dentro de um objeto do `json_safe!`:
+/// `{ id: 1, ..flatten!(endereco) }` copia os campos de `endereco`.
+#[macro_export]
+macro_rules! flatten {
+    ($($body:tt)*) => {
+        ::std::compile_error!(
+            "flatten! só vale como spread (..flatten!(x)) num objeto do json_safe!"
+        )
+    };
+}
+
+pub use error::JsonSafeError;
+/// Erros de sintaxe que o `json_safe_pm!` recusa na compilação:
+///
+/// ```compile_fail
+/// // chave fixa repetida
+/// json_safe::json_safe_pm!({ id: 1, id: 2 });
+/// ```
+///
+/// ```compile_fail
+/// // strict e dotted valem só para objetos
+/// json_safe::json_safe_pm!(strict [1, 2]);
+/// ```
+///
+/// ```compile_fail
+/// // o tamanho da repetição encerra o array
+/// json_safe::json_safe_pm!([null; 3, 4]);
+/// ```
+#[cfg(feature = "proc-macro")]
+pub use json_safe_macros::json_safe_pm;
+
+/// Objeto a partir de pares `(chave, valor)`. A primeira conversão que
+/// falhar vira erro com a chave no início da mensagem; chaves repetidas
+/// ficam com o último valor.
+pub fn object_from_pairs<I, K, V>(pairs: I) -> serde_json::Result<serde_json::Value>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: serde::Serialize,
+{
+    let mut map = serde_json::Map::new();
+    for (chave, valor) in pairs {
+        let chave = chave.into();
+        let valor = serde_json::to_value(valor).map_err(|e| {
+            <serde_json::Error as serde::ser::Error>::custom(format!("{chave:?}: {e}"))
+        })?;
+        map.insert(chave, valor);
+    }
+    Ok(serde_json::Value::Object(map))
+}
+
+pub mod arrays;
+pub mod audit;
+pub mod base64;
+pub mod bulk;
+pub mod canonical;
+pub mod cassette;
+pub mod clock;
+pub mod compact;
+pub mod contract;
+pub mod cursor;
+pub mod delta;
+pub mod deprecations;
+pub mod digest;
+pub mod discord;
+pub mod dotted;
+pub mod duplicate_keys;
+pub mod envelope;
+pub mod error;
+#[cfg(feature = "error-events")]
+pub mod error_event;
+pub mod event_schema;
+pub mod fidelity;
+pub mod field_crypto;
+pub mod firestore;
+pub mod flag_context;
+pub mod float_format;
+pub mod geo_privacy;
+pub mod geojson;
+pub mod health;
+pub mod idempotency;
+pub mod inspect;
+pub mod int128;
+pub mod jsonld;
+pub mod jws;
+pub mod locale;
+pub mod merkle;
+#[cfg(feature = "dev")]
+pub mod mock_server;
+pub mod money;
+pub mod non_finite;
+pub mod numeric;
+pub mod openmetrics;
+pub mod outbox;
+pub mod overrides;
+#[cfg(feature = "path-to-error")]
+pub mod path_to_error;
+pub mod policy;
+pub mod profile;
+pub mod projection;
+pub mod pseudonym;
+pub mod quantity;
+pub mod raw;
+pub mod report;
+pub mod runtime;
+pub mod sbom;
+pub mod schema_org;
+#[cfg(feature = "schema-registry")]
+pub mod schema_registry;
+mod scoped;
+pub mod serialize_with;
+mod serializer;
+pub mod shape;
+pub mod slack;
+pub mod stream;
+pub mod tenant;
+pub mod tokenize;
+pub mod trace_context;
+pub mod untrusted;
+pub mod versioned;
+
+// Caminhos usados pelas macros exportadas, para que o crate dependente não
+// precise declarar serde_json.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+
+    use crate::JsonSafeError;
+    use crate::error::PathSegment;
+    use serde_json::{Map, Value};
+
+    pub type Result = std::result::Result<Value, JsonSafeError>;
+
+    pub fn to_value<T: serde::Serialize>(value: T) -> Result {
+        match crate::serializer::to_value(&value, cfg!(feature = "path-to-error")) {
+            Ok(v) => Ok(v),
+            Err(e) => crate::error::registrar(e).map_or(Ok(Value::Null), Err),
+        }
+    }
+
+    // Texto do literal como número exato, se for um; senão, `to_value`
+    pub fn to_value_lexeme<T: serde::Serialize>(lexeme: &str, value: T) -> Result {
+        #[cfg(feature = "arbitrary-precision")]
+        {
+            // stringify! separa o sinal: `- 1.5`
+            let texto = match lexeme.strip_prefix('-') {
+                Some(resto) => format!("-{}", resto.trim_start()),
+                None => lexeme.to_string(),
+            };
+            if let Ok(numero) = texto.parse::<serde_json::Number>() {
+                return Ok(Value::Number(numero));
+            }
+        }
+        #[cfg(not(feature = "arbitrary-precision"))]
+        let _ = lexeme;
+        to_value(value)
+    }
+
+    // `skip_if!`: fixa o tipo do argumento do predicado
+    pub fn skip_if<T: ?Sized>(value: &T, pred: impl FnOnce(&T) -> bool) -> bool {
+        pred(value)
+    }
+
+    // Chave de um identificador: `r#type` vira "type"
+    pub fn ident(key: &'static str) -> &'static str {
+        key.strip_prefix("r#").unwrap_or(key)
+    }
+
+    // Avalia o valor com a chave/índice no caminho do erro
+    pub fn key(key: &str, f: impl FnOnce() -> Result) -> Result {
+        crate::error::em(PathSegment::Key(key.to_string()), f)
+    }
+
+    pub fn index(index: usize, f: impl FnOnce() -> Result) -> Result {
+        crate::error::em(PathSegment::Index(index), f)
+    }
+
+    pub fn accumulate(
+        f: impl FnOnce() -> Result,
+    ) -> std::result::Result<Value, Vec<JsonSafeError>> {
+        crate::error::accumulate(f)
+    }
+
+    pub fn isolate(f: impl FnOnce() -> Result) -> Result {
+        crate::error::isolate(f)
+    }
+
+    // `..expr` dentro de objetos
+    pub fn spread<M>(map: &mut M, value: Value) -> serde_json::Result<()>
+    where
+        M: Extend<(String, Value)>,
+    {
+        espalhar(map, value, "spread (..)")
+    }
+
+    // `..flatten!(expr)` dentro de objetos
+    pub fn flatten<M>(map: &mut M, value: Value) -> serde_json::Result<()>
+    where
+        M: Extend<(String, Value)>,
+    {
+        espalhar(map, value, "flatten!")
+    }
+
+    fn espalhar<M>(map: &mut M, value: Value, origem: &str) -> serde_json::Result<()>
+    where
+        M: Extend<(String, Value)>,
+    {
+        match value {
+            Value::Object(entradas) => {
+                map.extend(entradas);
+                Ok(())
+            }
+            outro => {
+                let erro = <serde_json::Error as serde::ser::Error>::custom(format!(
+                    "{origem}: esperado objeto, recebeu {}",
+                    crate::inspect::type_name(&outro)
+                ));
+                // No modo `all`, o erro é coletado e o spread é ignorado
+                match crate::error::registrar(erro.into()) {
+                    None => Ok(()),
+                    Some(e) => Err(e.into_inner()),
+                }
+            }
+        }
+    }
+
+    // Mapa do modo strict: guarda a primeira chave repetida
+    #[derive(Default)]
+    pub struct Strict {
+        map: Map<String, Value>,
+        duplicada: Option<String>,
+    }
+
+    impl Strict {
+        pub fn insert(&mut self, key: String, value: Value) {
+            if self.map.contains_key(&key) && self.duplicada.is_none() {
+                self.duplicada = Some(key.clone());
+            }
+            self.map.insert(key, value);
+        }
+
+        pub fn finish(self) -> serde_json::Result<Value> {
+            match self.duplicada {
+                Some(chave) => Err(<serde_json::Error as serde::ser::Error>::custom(format!(
+                    "chave duplicada: {chave:?}"
+                ))),
+                None => Ok(Value::Object(self.map)),
+            }
+        }
+    }
+
+    impl Extend<(String, Value)> for Strict {
+        fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+            for (k, v) in iter {
+                self.insert(k, v);
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use serde::{Serialize, Serializer};
+
+// Marcadores de apresentação: números e datas formatados conforme o locale,
+// serializados como string. Um locale desconhecido ou data inválida faz a
+// serialização falhar, e o erro sai pelo `json_safe!`.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+// localized_number!(x, "pt-BR") ou localized_number!(x, "pt-BR", casas)
+#[macro_export]
+macro_rules! localized_number {
+    ($value:expr, $locale:expr) => {
+        $crate::locale::LocalizedNumber::new($value as f64, $locale, None)
+    };
+    ($value:expr, $locale:expr, $decimals:expr) => {
+        $crate::locale::LocalizedNumber::new($value as f64, $locale, Some($decimals))
+    };
+}
+
+// localized_date!((2024, 5, 1), "pt-BR", "d 'de' MMMM 'de' yyyy")
+#[macro_export]
+macro_rules! localized_date {
+    ($date:expr, $locale:expr, $pattern:expr) => {
+        $crate::locale::LocalizedDate::new($date, $locale, $pattern)
+    };
+}
+
+struct Locale {
+    decimal: char,
+    group: &'static str,
+    months: [&'static str; 12],
+}
+
+const MESES_PT: [&str; 12] = [
+    "janeiro",
+    "fevereiro",
+    "março",
+    "abril",
+    "maio",
+    "junho",
+    "julho",
+    "agosto",
+    "setembro",
+    "outubro",
+    "novembro",
+    "dezembro",
+];
+const MESES_EN: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const MESES_ES: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+const MESES_DE: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+const MESES_FR: [&str; 12] = [
+    "janvier",
+    "février",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "août",
+    "septembre",
+    "octobre",
+    "novembre",
+    "décembre",
+];
+
+fn locale(tag: &str) -> serde_json::Result<Locale> {
+    let (decimal, group, months) = match tag {
+        "pt-BR" | "pt-PT" => (',', ".", MESES_PT),
+        "en-US" | "en-GB" => ('.', ",", MESES_EN),
+        "es-ES" => (',', ".", MESES_ES),
+        "de-DE" => (',', ".", MESES_DE),
+        // Espaço fino não separável, como no CLDR
+        "fr-FR" => (',', "\u{202f}", MESES_FR),
+        _ => return Err(erro(format!("locale não suportado: {tag}"))),
+    };
+    Ok(Locale {
+        decimal,
+        group,
+        months,
+    })
+}
+
+/// Número formatado (`1.234,5` em pt-BR). Sem `decimals`, usa até 3 casas e
+/// remove zeros à direita.
+#[derive(Debug, Clone)]
+pub struct LocalizedNumber {
+    value: f64,
+    locale: String,
+    decimals: Option<usize>,
+}
+
+impl LocalizedNumber {
+    pub fn new(value: f64, locale: impl Into<String>, decimals: Option<usize>) -> Self {
+        LocalizedNumber {
+            value,
+            locale: locale.into(),
+            decimals,
+        }
+    }
+
+    pub fn format(&self) -> serde_json::Result<String> {
+        let loc = locale(&self.locale)?;
+        if !self.value.is_finite() {
+            return Err(erro(format!("número não finito: {}", self.value)));
+        }
+        let mut texto = format!("{:.*}", self.decimals.unwrap_or(3), self.value.abs());
+        if self.decimals.is_none() && texto.contains('.') {
+            texto = texto
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string();
+        }
+        let (inteiro, fracao) = texto.split_once('.').unwrap_or((&texto, ""));
+
+        let mut out = String::new();
+        if self.value < 0.0 && texto.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+        for (i, c) in inteiro.chars().enumerate() {
+            if i > 0 && (inteiro.len() - i) % 3 == 0 {
+                out.push_str(loc.group);
+            }
+            out.push(c);
+        }
+        if !fracao.is_empty() {
+            out.push(loc.decimal);
+            out.push_str(fracao);
+        }
+        Ok(out)
+    }
+}
+
+impl Serialize for LocalizedNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let texto = self.format().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&texto)
+    }
+}
+
+/// Data (e hora opcional), sem fuso.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateParts {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+/// Entradas aceitas por `localized_date!`: `(ano, mês, dia)`,
+/// `(ano, mês, dia, hora, minuto, segundo)` e strings ISO 8601
+/// (`2024-05-01` ou `2024-05-01T12:30:00Z`; o fuso é ignorado).
+pub trait DateInput {
+    fn parts(&self) -> Option<DateParts>;
+}
+
+impl DateInput for (i32, u32, u32) {
+    fn parts(&self) -> Option<DateParts> {
+        (self.0, self.1, self.2, 0, 0, 0).parts()
+    }
+}
+
+impl DateInput for (i32, u32, u32, u32, u32, u32) {
+    fn parts(&self) -> Option<DateParts> {
+        let (year, month, day, hour, minute, second) = *self;
+        let bissexto = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let dias = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if bissexto => 29,
+            2 => 28,
+            _ => return None,
+        };
+        ((1..=dias).contains(&day) && hour < 24 && minute < 60 && second < 60).then_some(
+            DateParts {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+            },
+        )
+    }
+}
+
+impl DateInput for &str {
+    fn parts(&self) -> Option<DateParts> {
+        let campo = |de: usize, ate: usize| self.get(de..ate)?.parse::<u32>().ok();
+        let separadores_ok = self.get(4..5) == Some("-") && self.get(7..8) == Some("-");
+        if !separadores_ok {
+            return None;
+        }
+        let data = (campo(0, 4)? as i32, campo(5, 7)?, campo(8, 10)?);
+        match self.len() {
+            10 => data.parts(),
+            _ if matches!(self.get(10..11), Some("T" | " ")) => (
+                data.0,
+                data.1,
+                data.2,
+                campo(11, 13)?,
+                campo(14, 16)?,
+                campo(17, 19).unwrap_or(0),
+            )
+                .parts(),
+            _ => None,
+        }
+    }
+}
+
+/// Data formatada por padrão: `yyyy`, `yy`, `MMMM` (nome do mês), `MM`, `M`,
+/// `dd`, `d`, `HH`, `mm`, `ss`; texto entre aspas simples é literal.
+#[derive(Debug, Clone)]
+pub struct LocalizedDate {
+    parts: Option<DateParts>,
+    locale: String,
+    pattern: String,
+}
+
+impl LocalizedDate {
+    pub fn new(
+        date: impl DateInput,
+        locale: impl Into<String>,
+        pattern: impl Into<String>,
+    ) -> Self {
+        LocalizedDate {
+            parts: date.parts(),
+            locale: locale.into(),
+            pattern: pattern.into(),
+        }
+    }
+
+    pub fn format(&self) -> serde_json::Result<String> {
+        let loc = locale(&self.locale)?;
+        let p = self.parts.ok_or_else(|| erro("data inválida".into()))?;
+
+        let chars: Vec<char> = self.pattern.chars().collect();
+        let mut out = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '\'' {
+                let fim = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == '\'')
+                    .ok_or_else(|| erro("aspas sem fechamento no padrão".into()))?;
+                out.extend(&chars[i + 1..i + 1 + fim]);
+                i += fim + 2;
+                continue;
+            }
+            let n = chars[i..].iter().take_while(|&&x| x == c).count();
+            match (c, n) {
+                ('y', 2) => out.push_str(&format!("{:02}", p.year.rem_euclid(100))),
+                ('y', _) => out.push_str(&format!("{:04}", p.year)),
+                ('M', 1) => out.push_str(&p.month.to_string()),
+                ('M', 2) => out.push_str(&format!("{:02}", p.month)),
+                ('M', _) => out.push_str(loc.months[p.month as usize - 1]),
+                ('d', 1) => out.push_str(&p.day.to_string()),
+                ('d', _) => out.push_str(&format!("{:02}", p.day)),
+                ('H', _) => out.push_str(&format!("{:02}", p.hour)),
+                ('m', _) => out.push_str(&format!("{:02}", p.minute)),
+                ('s', _) => out.push_str(&format!("{:02}", p.second)),
+                _ => out.extend(std::iter::repeat_n(c, n)),
+            }
+            i += n;
+        }
+        Ok(out)
+    }
+}
+
+impl Serialize for LocalizedDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let texto = self.format().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&texto)
+    }
+}
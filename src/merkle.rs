@@ -0,0 +1,296 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::digest::{hex, sha256};
+
+// Árvore de Merkle de um documento: cada subárvore tem um hash, endereçado
+// por JSON Pointer, que depende só dos hashes dos filhos. Um pedaço de um
+// documento enorme pode ser conferido contra o hash da raiz com uma `Proof`
+// (os hashes dos irmãos no caminho até a raiz), sem o resto do documento.
+//
+// Folha: sha256(0x00 ‖ JSON canônico). Objeto: sha256(0x01 ‖ para cada
+// chave em ordem, [tamanho u32 LE][chave][hash do filho]). Array:
+// sha256(0x02 ‖ hashes dos itens em ordem).
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+type Hash = [u8; 32];
+
+fn folha(value: &Value) -> Hash {
+    let mut bytes = vec![0x00];
+    bytes.extend_from_slice(crate::canonical::to_canonical_string(value).as_bytes());
+    sha256(&bytes)
+}
+
+fn de_objeto<'a>(filhos: impl IntoIterator<Item = (&'a str, &'a Hash)>) -> Hash {
+    let mut bytes = vec![0x01];
+    for (chave, hash) in filhos {
+        bytes.extend_from_slice(&(chave.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(chave.as_bytes());
+        bytes.extend_from_slice(hash);
+    }
+    sha256(&bytes)
+}
+
+fn de_array<'a>(filhos: impl IntoIterator<Item = &'a Hash>) -> Hash {
+    let mut bytes = vec![0x02];
+    filhos.into_iter().for_each(|h| bytes.extend_from_slice(h));
+    sha256(&bytes)
+}
+
+#[derive(Debug, Clone)]
+enum No {
+    Folha(Hash),
+    Objeto(Hash, BTreeMap<String, No>),
+    Array(Hash, Vec<No>),
+}
+
+impl No {
+    fn de(value: &Value) -> No {
+        match value {
+            Value::Object(map) => {
+                let filhos: BTreeMap<String, No> =
+                    map.iter().map(|(k, v)| (k.clone(), No::de(v))).collect();
+                let hash = de_objeto(filhos.iter().map(|(k, n)| (k.as_str(), n.hash())));
+                No::Objeto(hash, filhos)
+            }
+            Value::Array(itens) => {
+                let filhos: Vec<No> = itens.iter().map(No::de).collect();
+                No::Array(de_array(filhos.iter().map(No::hash)), filhos)
+            }
+            outro => No::Folha(folha(outro)),
+        }
+    }
+
+    fn hash(&self) -> &Hash {
+        match self {
+            No::Folha(h) | No::Objeto(h, _) | No::Array(h, _) => h,
+        }
+    }
+}
+
+fn segmentos(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Hashes de todas as subárvores de um documento.
+#[derive(Debug, Clone)]
+pub struct Merkle {
+    raiz: No,
+}
+
+/// Árvore de Merkle de `doc`.
+pub fn merkle(doc: &Value) -> Merkle {
+    Merkle { raiz: No::de(doc) }
+}
+
+/// Hash (hex) de um valor como subárvore; igual a `merkle(value).root()`.
+pub fn subtree_hash(value: &Value) -> String {
+    hex(No::de(value).hash())
+}
+
+impl Merkle {
+    /// Hash (hex) do documento inteiro.
+    pub fn root(&self) -> String {
+        hex(self.raiz.hash())
+    }
+
+    /// Hash (hex) da subárvore em `pointer`; `None` se o caminho não existe.
+    pub fn hash(&self, pointer: &str) -> Option<String> {
+        let mut no = &self.raiz;
+        for seg in segmentos(pointer) {
+            no = filho(no, &seg)?;
+        }
+        Some(hex(no.hash()))
+    }
+
+    /// Prova de que a subárvore em `pointer` pertence ao documento: os
+    /// hashes dos irmãos em cada nível, da raiz até ela.
+    pub fn proof(&self, pointer: &str) -> Option<Proof> {
+        let mut no = &self.raiz;
+        let mut passos = Vec::new();
+        for seg in segmentos(pointer) {
+            let passo = match no {
+                No::Objeto(_, filhos) => Passo::Chave(
+                    seg.clone(),
+                    filhos
+                        .iter()
+                        .filter(|(k, _)| **k != seg)
+                        .map(|(k, n)| (k.clone(), *n.hash()))
+                        .collect(),
+                ),
+                No::Array(_, filhos) => {
+                    let i = indice(&seg, filhos.len())?;
+                    let irmaos = filhos
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != i)
+                        .map(|(_, n)| *n.hash())
+                        .collect();
+                    Passo::Indice(i, irmaos)
+                }
+                No::Folha(_) => return None,
+            };
+            no = filho(no, &seg)?;
+            passos.push(passo);
+        }
+        Some(Proof {
+            pointer: pointer.to_string(),
+            passos,
+        })
+    }
+}
+
+fn indice(seg: &str, tamanho: usize) -> Option<usize> {
+    seg.parse().ok().filter(|i| *i < tamanho)
+}
+
+fn filho<'a>(no: &'a No, seg: &str) -> Option<&'a No> {
+    match no {
+        No::Objeto(_, filhos) => filhos.get(seg),
+        No::Array(_, filhos) => filhos.get(indice(seg, filhos.len())?),
+        No::Folha(_) => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Passo {
+    Chave(String, BTreeMap<String, Hash>),
+    Indice(usize, Vec<Hash>),
+}
+
+/// Caminho de hashes de uma subárvore até a raiz.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    pointer: String,
+    passos: Vec<Passo>,
+}
+
+impl Proof {
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    /// `{ "pointer": "/a/0", "path": [{ "key": "a", "siblings": { .. } },
+    /// { "index": 0, "siblings": [..] }] }`, com os hashes em hex.
+    pub fn to_value(&self) -> Value {
+        let path: Vec<Value> = self
+            .passos
+            .iter()
+            .map(|passo| match passo {
+                Passo::Chave(chave, irmaos) => {
+                    let irmaos: Map<String, Value> = irmaos
+                        .iter()
+                        .map(|(k, h)| (k.clone(), Value::from(hex(h))))
+                        .collect();
+                    objeto([
+                        ("key", Value::from(chave.as_str())),
+                        ("siblings", Value::Object(irmaos)),
+                    ])
+                }
+                Passo::Indice(i, irmaos) => {
+                    let irmaos: Vec<Value> = irmaos.iter().map(|h| Value::from(hex(h))).collect();
+                    objeto([
+                        ("index", Value::from(*i)),
+                        ("siblings", Value::Array(irmaos)),
+                    ])
+                }
+            })
+            .collect();
+        objeto([
+            ("pointer", Value::from(self.pointer.as_str())),
+            ("path", Value::Array(path)),
+        ])
+    }
+
+    pub fn from_value(value: &Value) -> serde_json::Result<Proof> {
+        let pointer = value["pointer"]
+            .as_str()
+            .ok_or_else(|| erro("merkle: prova sem \"pointer\"".into()))?;
+        let passos = value["path"]
+            .as_array()
+            .ok_or_else(|| erro("merkle: prova sem \"path\"".into()))?
+            .iter()
+            .map(ler_passo)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        Ok(Proof {
+            pointer: pointer.to_string(),
+            passos,
+        })
+    }
+}
+
+fn objeto<const N: usize>(pares: [(&str, Value); N]) -> Value {
+    Value::Object(pares.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+}
+
+fn ler_hash(value: &Value) -> serde_json::Result<Hash> {
+    let texto = value
+        .as_str()
+        .filter(|s| s.len() == 64 && s.is_ascii())
+        .ok_or_else(|| erro(format!("merkle: hash inválido: {value}")))?;
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&texto[i * 2..i * 2 + 2], 16)
+            .map_err(|_| erro(format!("merkle: hash inválido: {value}")))?;
+    }
+    Ok(hash)
+}
+
+fn ler_passo(value: &Value) -> serde_json::Result<Passo> {
+    match (&value["key"], &value["index"], &value["siblings"]) {
+        (Value::String(chave), Value::Null, Value::Object(irmaos)) => Ok(Passo::Chave(
+            chave.clone(),
+            irmaos
+                .iter()
+                .map(|(k, h)| Ok((k.clone(), ler_hash(h)?)))
+                .collect::<serde_json::Result<_>>()?,
+        )),
+        (Value::Null, Value::Number(i), Value::Array(irmaos)) => Ok(Passo::Indice(
+            i.as_u64()
+                .ok_or_else(|| erro(format!("merkle: índice inválido: {i}")))? as usize,
+            irmaos
+                .iter()
+                .map(ler_hash)
+                .collect::<serde_json::Result<_>>()?,
+        )),
+        _ => Err(erro(format!("merkle: passo inválido: {value}"))),
+    }
+}
+
+/// Confere que `subtree` é o valor em `proof.pointer()` do documento cujo
+/// hash da raiz é `root` (hex).
+pub fn verify(subtree: &Value, proof: &Proof, root: &str) -> bool {
+    if segmentos(&proof.pointer).len() != proof.passos.len() {
+        return false;
+    }
+    let mut hash = *No::de(subtree).hash();
+    for (passo, seg) in proof.passos.iter().zip(segmentos(&proof.pointer)).rev() {
+        hash = match passo {
+            Passo::Chave(chave, irmaos) => {
+                if *chave != seg || irmaos.contains_key(chave) {
+                    return false;
+                }
+                let mut filhos: BTreeMap<&str, &Hash> =
+                    irmaos.iter().map(|(k, h)| (k.as_str(), h)).collect();
+                filhos.insert(chave, &hash);
+                de_objeto(filhos)
+            }
+            Passo::Indice(i, irmaos) => {
+                if seg.parse() != Ok(*i) || *i > irmaos.len() {
+                    return false;
+                }
+                let mut filhos: Vec<&Hash> = irmaos.iter().collect();
+                filhos.insert(*i, &hash);
+                de_array(filhos)
+            }
+        };
+    }
+    hex(&hash).eq_ignore_ascii_case(root)
+}
@@ -0,0 +1,308 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// Servidor HTTP de fixtures para testes (feature "dev"): responde com
+// `Value`s configurados por rota, com latência e falhas injetáveis, em
+// 127.0.0.1 numa porta livre. Só std: HTTP/1.1 mínimo, uma resposta por
+// conexão (`Connection: close`).
+
+/// Resposta configurada para uma rota.
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    body: Option<Value>,
+    headers: Vec<(String, String)>,
+    latency: Duration,
+    disconnect: bool,
+}
+
+impl Response {
+    /// 200 com `body` em JSON.
+    pub fn json(body: Value) -> Self {
+        Response {
+            status: 200,
+            body: Some(body),
+            headers: Vec::new(),
+            latency: Duration::ZERO,
+            disconnect: false,
+        }
+    }
+
+    /// Resposta sem corpo.
+    pub fn empty(status: u16) -> Self {
+        Response {
+            body: None,
+            ..Self::json(Value::Null).status(status)
+        }
+    }
+
+    /// Fecha a conexão sem responder (erro de rede no cliente).
+    pub fn disconnect() -> Self {
+        Response {
+            disconnect: true,
+            ..Self::empty(0)
+        }
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Espera antes de responder.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+/// Requisição recebida, na ordem de chegada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    /// Corpo como JSON; `None` se vazio ou inválido.
+    pub body: Option<Value>,
+}
+
+type Rotas = BTreeMap<(String, String), (Vec<Response>, usize)>;
+
+/// Configuração do servidor, antes de subir.
+#[derive(Debug, Default)]
+pub struct MockServer {
+    rotas: Rotas,
+}
+
+impl MockServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra uma resposta para `method path` (sem query string). Rotas
+    /// registradas mais de uma vez respondem na ordem e repetem a última,
+    /// ex.: um 503 e depois 200, para testar retry.
+    pub fn route(mut self, method: &str, path: &str, response: Response) -> Self {
+        self.rotas
+            .entry((method.to_ascii_uppercase(), path.to_string()))
+            .or_default()
+            .0
+            .push(response);
+        self
+    }
+
+    /// Sobe o servidor em uma thread; ele para quando o retorno é descartado.
+    pub fn start(self) -> io::Result<RunningServer> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let estado = Arc::new(Estado {
+            rotas: Mutex::new(self.rotas),
+            recebidas: Mutex::new(Vec::new()),
+            parar: AtomicBool::new(false),
+        });
+        let compartilhado = Arc::clone(&estado);
+        let thread = thread::spawn(move || {
+            for conexao in listener.incoming() {
+                if compartilhado.parar.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(conexao) = conexao else { continue };
+                let estado = Arc::clone(&compartilhado);
+                thread::spawn(move || {
+                    let _ = atender(conexao, &estado);
+                });
+            }
+        });
+        Ok(RunningServer {
+            addr,
+            estado,
+            thread: Some(thread),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Estado {
+    rotas: Mutex<Rotas>,
+    recebidas: Mutex<Vec<Request>>,
+    parar: AtomicBool,
+}
+
+/// Servidor em execução.
+#[derive(Debug)]
+pub struct RunningServer {
+    addr: SocketAddr,
+    estado: Arc<Estado>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RunningServer {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// `http://127.0.0.1:porta` + `path`.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+
+    /// Requisições recebidas até agora.
+    pub fn requests(&self) -> Vec<Request> {
+        self.estado
+            .recebidas
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Quantas requisições chegaram em `method path`.
+    pub fn hits(&self, method: &str, path: &str) -> usize {
+        self.requests()
+            .iter()
+            .filter(|r| r.method.eq_ignore_ascii_case(method) && r.path == path)
+            .count()
+    }
+}
+
+impl Drop for RunningServer {
+    fn drop(&mut self) {
+        self.estado.parar.store(true, Ordering::SeqCst);
+        // Desbloqueia o accept
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Fixtures são pequenas; acima disso, 413 sem ler o corpo
+const MAX_CORPO: usize = 4 << 20;
+// Cliente que não termina a requisição não prende a thread para sempre
+const TEMPO_LEITURA: Duration = Duration::from_secs(5);
+
+fn atender(conexao: TcpStream, estado: &Estado) -> io::Result<()> {
+    conexao.set_read_timeout(Some(TEMPO_LEITURA))?;
+    let mut leitor = BufReader::new(conexao.try_clone()?);
+    let mut linha = String::new();
+    leitor.read_line(&mut linha)?;
+    let mut partes = linha.split_whitespace();
+    let (Some(metodo), Some(alvo)) = (partes.next(), partes.next()) else {
+        return Ok(());
+    };
+    let metodo = metodo.to_ascii_uppercase();
+    let path = alvo.split('?').next().unwrap_or(alvo).to_string();
+
+    let mut headers = Vec::new();
+    let mut tamanho = 0;
+    loop {
+        let mut linha = String::new();
+        if leitor.read_line(&mut linha)? == 0 || linha.trim_end().is_empty() {
+            break;
+        }
+        if let Some((nome, valor)) = linha.trim_end().split_once(':') {
+            let (nome, valor) = (nome.trim().to_string(), valor.trim().to_string());
+            if nome.eq_ignore_ascii_case("content-length") {
+                tamanho = valor.parse().unwrap_or(0);
+            }
+            headers.push((nome, valor));
+        }
+    }
+    if tamanho > MAX_CORPO {
+        return responder(conexao, &Response::empty(413));
+    }
+    let mut corpo = vec![0; tamanho];
+    leitor.read_exact(&mut corpo)?;
+
+    estado
+        .recebidas
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Request {
+            method: metodo.clone(),
+            path: path.clone(),
+            headers,
+            body: serde_json::from_slice(&corpo).ok(),
+        });
+
+    let resposta = {
+        let mut rotas = estado.rotas.lock().unwrap_or_else(|e| e.into_inner());
+        match rotas.get_mut(&(metodo.clone(), path.clone())) {
+            Some((respostas, proxima)) => {
+                let r = respostas[(*proxima).min(respostas.len() - 1)].clone();
+                *proxima += 1;
+                r
+            }
+            None => {
+                let mut erro = serde_json::Map::new();
+                erro.insert("error".into(), Value::from("rota não configurada"));
+                erro.insert("method".into(), Value::from(metodo));
+                erro.insert("path".into(), Value::from(path));
+                Response::json(Value::Object(erro)).status(404)
+            }
+        }
+    };
+
+    thread::sleep(resposta.latency);
+    if resposta.disconnect {
+        return Ok(());
+    }
+    responder(conexao, &resposta)
+}
+
+fn responder(mut conexao: TcpStream, resposta: &Response) -> io::Result<()> {
+    let corpo = resposta
+        .body
+        .as_ref()
+        .map(|b| b.to_string())
+        .unwrap_or_default();
+    let mut saida = format!(
+        "HTTP/1.1 {} {}\r\n",
+        resposta.status,
+        motivo(resposta.status)
+    );
+    if resposta.body.is_some() {
+        saida.push_str("Content-Type: application/json\r\n");
+    }
+    for (nome, valor) in &resposta.headers {
+        saida.push_str(&format!("{nome}: {valor}\r\n"));
+    }
+    saida.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{corpo}",
+        corpo.len()
+    ));
+    conexao.write_all(saida.as_bytes())?;
+    conexao.flush()
+}
+
+fn motivo(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Content Too Large",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
+    }
+}
@@ -0,0 +1,112 @@
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Serialize, Serializer};
+use std::sync::RwLock;
+
+// Valores monetários a partir de unidades mínimas inteiras (centavos): o
+// marcador não aceita float, então nenhum valor monetário em ponto
+// flutuante entra no payload.
+
+// money!(1050, "BRL") ou money!(1050, "BRL", MoneyShape::DecimalString)
+#[macro_export]
+macro_rules! money {
+    ($amount_minor:expr, $currency:expr) => {
+        $crate::money::Money::new($amount_minor, $currency)
+    };
+    ($amount_minor:expr, $currency:expr, $shape:expr) => {
+        $crate::money::Money::new($amount_minor, $currency).shape($shape)
+    };
+}
+
+/// Formato de saída.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoneyShape {
+    /// `{ "amount": 1050, "currency": "BRL" }` (padrão)
+    MinorUnits,
+    /// `{ "amount": "10.50", "currency": "BRL" }`
+    DecimalString,
+    /// `"10.50 BRL"`
+    Combined,
+}
+
+static FORMATO_PADRAO: RwLock<MoneyShape> = RwLock::new(MoneyShape::MinorUnits);
+
+/// Formato usado pelos `money!` sem formato explícito.
+pub fn set_default_shape(shape: MoneyShape) {
+    *FORMATO_PADRAO.write().unwrap_or_else(|e| e.into_inner()) = shape;
+}
+
+/// Casas decimais da moeda (ISO 4217).
+pub fn minor_unit_digits(currency: &str) -> Option<u32> {
+    match currency {
+        "BRL" | "USD" | "EUR" | "GBP" | "CHF" | "CAD" | "AUD" | "NZD" | "MXN" | "ARS" | "COP"
+        | "PEN" | "UYU" | "CNY" | "HKD" | "SGD" | "INR" | "ZAR" | "SEK" | "NOK" | "DKK" | "PLN"
+        | "CZK" | "TRY" => Some(2),
+        "JPY" | "KRW" | "CLP" | "PYG" | "VND" | "ISK" | "UGX" => Some(0),
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" | "IQD" | "LYD" => Some(3),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Money {
+    amount_minor: i64,
+    currency: String,
+    shape: Option<MoneyShape>,
+}
+
+impl Money {
+    pub fn new(amount_minor: impl Into<i64>, currency: impl Into<String>) -> Self {
+        Money {
+            amount_minor: amount_minor.into(),
+            currency: currency.into(),
+            shape: None,
+        }
+    }
+
+    pub fn shape(mut self, shape: MoneyShape) -> Self {
+        self.shape = Some(shape);
+        self
+    }
+
+    /// Valor decimal exato (`"10.50"`, `"-0.05"`, `"1500"` para JPY).
+    pub fn decimal_string(&self) -> Result<String, String> {
+        let casas = minor_unit_digits(&self.currency)
+            .ok_or_else(|| format!("moeda desconhecida: {:?}", self.currency))?;
+        let sinal = if self.amount_minor < 0 { "-" } else { "" };
+        let abs = self.amount_minor.unsigned_abs();
+        if casas == 0 {
+            return Ok(format!("{sinal}{abs}"));
+        }
+        let divisor = 10u64.pow(casas);
+        Ok(format!(
+            "{sinal}{}.{:0width$}",
+            abs / divisor,
+            abs % divisor,
+            width = casas as usize
+        ))
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let decimal = self.decimal_string().map_err(S::Error::custom)?;
+        let shape = self
+            .shape
+            .unwrap_or_else(|| *FORMATO_PADRAO.read().unwrap_or_else(|e| e.into_inner()));
+        match shape {
+            MoneyShape::Combined => {
+                serializer.serialize_str(&format!("{decimal} {}", self.currency))
+            }
+            MoneyShape::MinorUnits | MoneyShape::DecimalString => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                if shape == MoneyShape::MinorUnits {
+                    map.serialize_entry("amount", &self.amount_minor)?;
+                } else {
+                    map.serialize_entry("amount", &decimal)?;
+                }
+                map.serialize_entry("currency", &self.currency)?;
+                map.end()
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+
+use crate::scoped::Politica;
+
+// Floats não finitos (NaN, inf, -inf) não existem em JSON. O padrão é
+// recusá-los com erro; payloads de telemetria com um NaN ocasional podem
+// trocá-los por null ou por string, no processo todo (`set_policy`) ou por
+// invocação: `json_safe!(nan_null { ... })`, `json_safe!(nan_string { ... })`.
+
+/// O que fazer com um float não finito em qualquer valor de `json_safe!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinite {
+    /// Erro na conversão (padrão).
+    #[default]
+    Error,
+    /// Vira `null`.
+    CoerceToNull,
+    /// Vira `"NaN"`, `"Infinity"` ou `"-Infinity"`.
+    StringifyAsNaN,
+}
+
+thread_local! {
+    static ATUAL: RefCell<Option<NonFinite>> = const { RefCell::new(None) };
+}
+
+static POLITICA: Politica<NonFinite> = Politica::new(&ATUAL);
+
+/// Política padrão do processo.
+pub fn set_policy(policy: NonFinite) {
+    POLITICA.set(policy);
+}
+
+/// Política em vigor na thread atual.
+pub fn policy() -> NonFinite {
+    POLITICA.get()
+}
+
+/// Executa `f` com `policy` na thread atual.
+pub fn with_policy<T>(policy: NonFinite, f: impl FnOnce() -> T) -> T {
+    POLITICA.with(policy, f)
+}
+
+pub(crate) fn nome(v: f64) -> &'static str {
+    if v.is_nan() {
+        "NaN"
+    } else if v > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::scoped;
+
+// Modo numérico estrito, para payloads financeiros em que conversão
+// silenciosa é inaceitável: floats não finitos (qualquer que seja a
+// política de `non_finite`), f32 que mudam de valor decimal ao virar f64 e
+// inteiros fora de i64/u64 viram `NumericError`, com o caminho do campo
+// (inclusive dentro de structs) no `JsonSafeError`.
+// json_safe!(strict_numbers { ... }) ou `numeric::strict(|| ...)`.
+
+/// Conversão numérica recusada.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericError {
+    /// NaN ou ±inf.
+    NonFinite(f64),
+    /// f32 cujo valor em f64 (o que vai no JSON) tem outra forma decimal:
+    /// 0.1f32 sairia como 0.10000000149011612.
+    LossyFloat(f32),
+    /// Inteiro fora da faixa de i64/u64.
+    OutOfRange(String),
+}
+
+impl fmt::Display for NumericError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumericError::NonFinite(v) => {
+                write!(f, "número não finito: {}", crate::non_finite::nome(*v))
+            }
+            NumericError::LossyFloat(v) => {
+                write!(f, "número com perda: f32 {v} viraria {}", f64::from(*v))
+            }
+            NumericError::OutOfRange(v) => write!(f, "inteiro fora da faixa de i64/u64: {v}"),
+        }
+    }
+}
+
+impl std::error::Error for NumericError {}
+
+// Problema de um valor f32 no modo estrito
+pub(crate) fn conferir_f32(v: f32) -> Option<NumericError> {
+    if !v.is_finite() {
+        return Some(NumericError::NonFinite(f64::from(v)));
+    }
+    (f64::from(v).to_string() != v.to_string()).then_some(NumericError::LossyFloat(v))
+}
+
+thread_local! {
+    static ESTRITO: RefCell<bool> = const { RefCell::new(false) };
+}
+
+/// Modo estrito ligado na thread atual.
+pub fn is_strict() -> bool {
+    ESTRITO.with(|estrito| *estrito.borrow())
+}
+
+/// Executa `f` com o modo estrito ligado na thread atual.
+pub fn strict<T>(f: impl FnOnce() -> T) -> T {
+    scoped::trocar(&ESTRITO, true, f).0
+}
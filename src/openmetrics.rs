@@ -0,0 +1,234 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// Exposição OpenMetrics a partir de um documento de métricas "plano":
+// `{ "fila": 3, "http_requests_total": { "get": 10, "post": 2 } }`.
+// Objetos aninhados viram uma dimensão de label (quando há regra para
+// ela) ou são achatados no nome com `_`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+    Unknown,
+}
+
+/// Regras de mapeamento do documento para o formato de exposição.
+#[derive(Debug, Clone)]
+pub struct MappingRules {
+    prefix: String,
+    labels: BTreeMap<String, String>,
+    types: BTreeMap<String, MetricType>,
+    help: BTreeMap<String, String>,
+    label_keys: BTreeMap<String, String>,
+    default_type: MetricType,
+}
+
+impl Default for MappingRules {
+    fn default() -> Self {
+        MappingRules {
+            prefix: String::new(),
+            labels: BTreeMap::new(),
+            types: BTreeMap::new(),
+            help: BTreeMap::new(),
+            label_keys: BTreeMap::new(),
+            default_type: MetricType::Gauge,
+        }
+    }
+}
+
+impl MappingRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefixo aplicado a todas as métricas (ex.: `"billing_"`).
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Label constante em todas as amostras.
+    pub fn label(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn metric_type(mut self, metric: impl Into<String>, kind: MetricType) -> Self {
+        self.types.insert(metric.into(), kind);
+        self
+    }
+
+    /// Tipo das métricas sem regra explícita (padrão: gauge).
+    pub fn default_type(mut self, kind: MetricType) -> Self {
+        self.default_type = kind;
+        self
+    }
+
+    pub fn help(mut self, metric: impl Into<String>, text: impl Into<String>) -> Self {
+        self.help.insert(metric.into(), text.into());
+        self
+    }
+
+    /// As chaves do objeto aninhado em `metric` viram valores do label `label`.
+    /// Só um nível: objetos dentro desse objeto são ignorados.
+    pub fn label_key(mut self, metric: impl Into<String>, label: impl Into<String>) -> Self {
+        self.label_keys.insert(metric.into(), label.into());
+        self
+    }
+}
+
+fn sanitizar(nome: &str) -> String {
+    let mut out: String = nome
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+// Nomes de label não aceitam `:`, que só vale em nomes de métrica
+fn sanitizar_label(nome: &str) -> String {
+    sanitizar(nome).replace(':', "_")
+}
+
+fn escapar(valor: &str) -> String {
+    valor
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn numero(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(if *b { "1" } else { "0" }.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+struct Familia {
+    tipo: MetricType,
+    help: Option<String>,
+    // (labels, valor)
+    amostras: Vec<(Vec<(String, String)>, String)>,
+}
+
+/// Converte `doc` para o formato de exposição OpenMetrics. Valores que não
+/// são números nem booleanos são ignorados.
+pub fn to_openmetrics(doc: &Value, rules: MappingRules) -> String {
+    let mut familias: BTreeMap<String, Familia> = BTreeMap::new();
+    if let Value::Object(map) = doc {
+        coletar(map, "", &[], &rules, &mut familias);
+    }
+
+    let mut out = String::new();
+    for (nome, familia) in &familias {
+        let tipo = match familia.tipo {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+            MetricType::Unknown => "unknown",
+        };
+        out.push_str(&format!("# TYPE {nome} {tipo}\n"));
+        if let Some(help) = &familia.help {
+            out.push_str(&format!("# HELP {nome} {}\n", escapar(help)));
+        }
+        let sufixo = if familia.tipo == MetricType::Counter {
+            "_total"
+        } else {
+            ""
+        };
+        for (labels, valor) in &familia.amostras {
+            out.push_str(nome);
+            out.push_str(sufixo);
+            let todos: Vec<String> = rules
+                .labels
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .chain(labels.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+                .map(|(k, v)| format!("{}=\"{}\"", sanitizar_label(k), escapar(v)))
+                .collect();
+            if !todos.is_empty() {
+                out.push('{');
+                out.push_str(&todos.join(","));
+                out.push('}');
+            }
+            out.push(' ');
+            out.push_str(valor);
+            out.push('\n');
+        }
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+fn coletar(
+    map: &Map<String, Value>,
+    base: &str,
+    labels: &[(String, String)],
+    rules: &MappingRules,
+    familias: &mut BTreeMap<String, Familia>,
+) {
+    for (chave, valor) in map {
+        let metrica = if base.is_empty() {
+            chave.clone()
+        } else {
+            format!("{base}_{chave}")
+        };
+
+        match valor {
+            Value::Object(filhos) => match rules.label_keys.get(&metrica) {
+                Some(label) => {
+                    for (valor_label, filho) in filhos {
+                        let mut l = labels.to_vec();
+                        l.push((label.clone(), valor_label.clone()));
+                        registrar(&metrica, filho, l, rules, familias);
+                    }
+                }
+                None => coletar(filhos, &metrica, labels, rules, familias),
+            },
+            outro => registrar(&metrica, outro, labels.to_vec(), rules, familias),
+        }
+    }
+}
+
+fn registrar(
+    metrica: &str,
+    valor: &Value,
+    labels: Vec<(String, String)>,
+    rules: &MappingRules,
+    familias: &mut BTreeMap<String, Familia>,
+) {
+    let Some(valor) = numero(valor) else {
+        return;
+    };
+    let tipo = rules
+        .types
+        .get(metrica)
+        .copied()
+        .unwrap_or(rules.default_type);
+    let mut nome = sanitizar(&format!("{}{metrica}", rules.prefix));
+    if tipo == MetricType::Counter
+        && let Some(sem_total) = nome.strip_suffix("_total")
+    {
+        nome = sem_total.to_string();
+    }
+
+    familias
+        .entry(nome)
+        .or_insert_with(|| Familia {
+            tipo,
+            help: rules.help.get(metrica).cloned(),
+            amostras: Vec::new(),
+        })
+        .amostras
+        .push((labels, valor));
+}
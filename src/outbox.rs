@@ -0,0 +1,163 @@
+use serde_json::{Map, Value};
+
+use crate::canonical;
+
+// Linhas da tabela de outbox transacional: o evento montado com a macro é
+// gravado na mesma transação que a mudança de estado, e um relay o publica
+// depois na fila. O payload vai na forma canônica, para que o mesmo evento
+// gere sempre os mesmos bytes (e o mesmo hash) no banco e na fila.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+/// Nomes das colunas da tabela de outbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns {
+    aggregate_id: String,
+    event_type: String,
+    payload: String,
+    headers: String,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns {
+            aggregate_id: "aggregate_id".into(),
+            event_type: "type".into(),
+            payload: "payload".into(),
+            headers: "headers".into(),
+        }
+    }
+}
+
+impl Columns {
+    /// `aggregate_id`, `type`, `payload` e `headers`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn aggregate_id(mut self, name: impl Into<String>) -> Self {
+        self.aggregate_id = name.into();
+        self
+    }
+
+    pub fn event_type(mut self, name: impl Into<String>) -> Self {
+        self.event_type = name.into();
+        self
+    }
+
+    pub fn payload(mut self, name: impl Into<String>) -> Self {
+        self.payload = name.into();
+        self
+    }
+
+    pub fn headers(mut self, name: impl Into<String>) -> Self {
+        self.headers = name.into();
+        self
+    }
+
+    /// Renomeia todas as colunas de uma vez (ex.: prefixo `outbox_`,
+    /// camelCase).
+    pub fn map(self, f: impl Fn(&str) -> String) -> Self {
+        Columns {
+            aggregate_id: f(&self.aggregate_id),
+            event_type: f(&self.event_type),
+            payload: f(&self.payload),
+            headers: f(&self.headers),
+        }
+    }
+}
+
+/// Uma linha da outbox.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxRow {
+    pub aggregate_id: String,
+    pub event_type: String,
+    /// Evento na forma canônica.
+    pub payload: String,
+    pub headers: Map<String, Value>,
+}
+
+impl OutboxRow {
+    /// Linha para `event`; `aggregate_id` e `event_type` não podem ser vazios.
+    pub fn new(
+        aggregate_id: impl Into<String>,
+        event_type: impl Into<String>,
+        event: &Value,
+    ) -> serde_json::Result<Self> {
+        let (aggregate_id, event_type) = (aggregate_id.into(), event_type.into());
+        if aggregate_id.trim().is_empty() {
+            return Err(erro("outbox: aggregate_id vazio".into()));
+        }
+        if event_type.trim().is_empty() {
+            return Err(erro("outbox: type vazio".into()));
+        }
+        Ok(OutboxRow {
+            aggregate_id,
+            event_type,
+            payload: canonical::to_canonical_string(event),
+            headers: Map::new(),
+        })
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Hash canônico do payload, útil como chave de deduplicação no relay.
+    pub fn payload_hash(&self) -> String {
+        canonical::canonical_hash(&self.payload_value())
+    }
+
+    /// Payload de volta como documento.
+    pub fn payload_value(&self) -> Value {
+        serde_json::from_str(&self.payload).unwrap_or(Value::Null)
+    }
+
+    /// A linha como objeto `{ coluna: valor }`, com os nomes de `columns`.
+    pub fn to_value(&self, columns: &Columns) -> Value {
+        let mut map = Map::new();
+        map.insert(
+            columns.aggregate_id.clone(),
+            Value::from(self.aggregate_id.as_str()),
+        );
+        map.insert(
+            columns.event_type.clone(),
+            Value::from(self.event_type.as_str()),
+        );
+        map.insert(columns.payload.clone(), Value::from(self.payload.as_str()));
+        map.insert(columns.headers.clone(), Value::Object(self.headers.clone()));
+        Value::Object(map)
+    }
+
+    /// Lê uma linha gravada com os nomes de `columns` (o lado do relay).
+    pub fn from_value(row: &Value, columns: &Columns) -> serde_json::Result<Self> {
+        let texto = |coluna: &str| {
+            row.get(coluna)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| erro(format!("outbox: coluna ausente ou não textual: {coluna}")))
+        };
+        let payload = texto(&columns.payload)?;
+        serde_json::from_str::<Value>(&payload)
+            .map_err(|e| erro(format!("outbox: payload inválido: {e}")))?;
+        let headers = match row.get(&columns.headers) {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(map)) => map.clone(),
+            Some(_) => {
+                return Err(erro(format!(
+                    "outbox: coluna {} não é objeto",
+                    columns.headers
+                )));
+            }
+        };
+        Ok(OutboxRow {
+            aggregate_id: texto(&columns.aggregate_id)?,
+            event_type: texto(&columns.event_type)?,
+            payload,
+            headers,
+        })
+    }
+}
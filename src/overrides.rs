@@ -0,0 +1,153 @@
+use serde_json::{Map, Value};
+
+// Overrides no estilo `--set a.b=1 --set tags[]=x`, pensados para receber
+// diretamente os valores coletados pelo clap (`Vec<String>`).
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tipo {
+    Inferido,
+    Int,
+    Float,
+    Bool,
+    Str,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segmento {
+    Chave(String),
+    // `tags[]`: acrescenta ao array
+    Anexar(String),
+}
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Converte uma lista de overrides em um documento JSON.
+pub fn overrides_to_value<I, S>(specs: I) -> serde_json::Result<Value>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    apply_overrides(Value::Object(Map::new()), specs)
+}
+
+/// Aplica os overrides sobre `base`, na ordem em que aparecem.
+pub fn apply_overrides<I, S>(mut base: Value, specs: I) -> serde_json::Result<Value>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for spec in specs {
+        let spec = spec.as_ref();
+        let (caminho, valor) = parse_override(spec)?;
+        aplicar(&mut base, &caminho, valor, spec)?;
+    }
+    Ok(base)
+}
+
+fn parse_override(spec: &str) -> serde_json::Result<(Vec<Segmento>, Value)> {
+    let (lado_esq, bruto) = spec
+        .split_once('=')
+        .ok_or_else(|| erro(format!("override sem '=': {spec:?}")))?;
+
+    let (caminho, tipo) = match lado_esq.rsplit_once(':') {
+        Some((caminho, sufixo)) => {
+            let tipo = match sufixo {
+                "int" => Tipo::Int,
+                "float" => Tipo::Float,
+                "bool" => Tipo::Bool,
+                "str" => Tipo::Str,
+                "json" => Tipo::Json,
+                _ => return Err(erro(format!("sufixo de tipo desconhecido em {spec:?}"))),
+            };
+            (caminho, tipo)
+        }
+        None => (lado_esq, Tipo::Inferido),
+    };
+
+    let mut segmentos = Vec::new();
+    for parte in caminho.split('.') {
+        let segmento = match parte.strip_suffix("[]") {
+            Some(chave) => Segmento::Anexar(chave.to_string()),
+            None => Segmento::Chave(parte.to_string()),
+        };
+        match &segmento {
+            Segmento::Chave(c) | Segmento::Anexar(c) if c.is_empty() => {
+                return Err(erro(format!("caminho inválido em {spec:?}")));
+            }
+            _ => {}
+        }
+        segmentos.push(segmento);
+    }
+
+    Ok((segmentos, converter(bruto, tipo, spec)?))
+}
+
+fn converter(bruto: &str, tipo: Tipo, spec: &str) -> serde_json::Result<Value> {
+    let invalido = || erro(format!("valor inválido em {spec:?}"));
+    match tipo {
+        Tipo::Str => Ok(Value::from(bruto)),
+        Tipo::Int => bruto
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| invalido()),
+        Tipo::Float => bruto
+            .parse::<f64>()
+            .ok()
+            .and_then(|f| serde_json::Number::from_f64(f).map(Value::Number))
+            .ok_or_else(invalido),
+        Tipo::Bool => bruto
+            .parse::<bool>()
+            .map(Value::from)
+            .map_err(|_| invalido()),
+        Tipo::Json => serde_json::from_str(bruto),
+        Tipo::Inferido => match serde_json::from_str::<Value>(bruto) {
+            // Só escalares são inferidos; o resto vira string
+            Ok(v @ (Value::Null | Value::Bool(_) | Value::Number(_))) => Ok(v),
+            _ => Ok(Value::from(bruto)),
+        },
+    }
+}
+
+fn aplicar(
+    alvo: &mut Value,
+    caminho: &[Segmento],
+    valor: Value,
+    spec: &str,
+) -> serde_json::Result<()> {
+    let Some((primeiro, resto)) = caminho.split_first() else {
+        *alvo = valor;
+        return Ok(());
+    };
+
+    if !alvo.is_object() {
+        *alvo = Value::Object(Map::new());
+    }
+    let Value::Object(map) = alvo else {
+        unreachable!()
+    };
+
+    match primeiro {
+        Segmento::Chave(chave) => {
+            let filho = map.entry(chave.clone()).or_insert(Value::Null);
+            aplicar(filho, resto, valor, spec)
+        }
+        Segmento::Anexar(chave) => {
+            if !resto.is_empty() {
+                return Err(erro(format!(
+                    "'[]' só é permitido no fim do caminho: {spec:?}"
+                )));
+            }
+            let filho = map
+                .entry(chave.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            match filho {
+                Value::Array(vec) => vec.push(valor),
+                outro => *outro = Value::Array(vec![valor]),
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::JsonSafeError;
+
+// Caminho serde completo nos erros (feature "path-to-error"): a falha dentro
+// de uma struct profunda diz onde aconteceu (`itens[1].meta`). Com a
+// feature, `json_safe!` rastreia o caminho em todos os valores.
+
+/// `serde_json::to_value` com o caminho serde da falha no erro.
+pub fn to_value<T: ?Sized + Serialize>(value: &T) -> Result<Value, JsonSafeError> {
+    crate::serializer::to_value(value, true)
+}
@@ -0,0 +1,114 @@
+use serde_json::{Map, Value};
+
+// Políticas de acesso por papel avaliadas sobre caminhos (JSON Pointer com
+// `*` para um segmento e `**` para qualquer profundidade). Um caminho coberto
+// por algum `allow` fica restrito aos papéis liberados; `deny` esconde um
+// caminho de um papel e vence `allow`. O que nenhuma regra cobre é público.
+
+fn segmentos(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn casa(padrao: &[String], caminho: &[String]) -> bool {
+    match (padrao.split_first(), caminho.split_first()) {
+        (None, None) => true,
+        (Some((p, resto)), _) if p == "**" => {
+            casa(resto, caminho) || (!caminho.is_empty() && casa(padrao, &caminho[1..]))
+        }
+        (Some((p, resto_p)), Some((c, resto_c))) => (p == "*" || p == c) && casa(resto_p, resto_c),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Regra {
+    role: String,
+    padrao: Vec<String>,
+}
+
+/// Conjunto de regras `allow`/`deny` por papel.
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    allow: Vec<Regra>,
+    deny: Vec<Regra>,
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restringe `pattern` aos papéis liberados, incluindo `role`.
+    pub fn allow(mut self, role: impl Into<String>, pattern: &str) -> Self {
+        self.allow.push(Regra {
+            role: role.into(),
+            padrao: segmentos(pattern),
+        });
+        self
+    }
+
+    /// Esconde `pattern` de `role`, mesmo que liberado por `allow`.
+    pub fn deny(mut self, role: impl Into<String>, pattern: &str) -> Self {
+        self.deny.push(Regra {
+            role: role.into(),
+            padrao: segmentos(pattern),
+        });
+        self
+    }
+
+    fn pode_ler(&self, roles: &[&str], caminho: &[String]) -> bool {
+        let cobre = |r: &Regra| casa(&r.padrao, caminho);
+        let do_papel = |r: &Regra| roles.contains(&r.role.as_str());
+        if self.deny.iter().any(|r| do_papel(r) && cobre(r)) {
+            return false;
+        }
+        let mut restritas = self.allow.iter().filter(|r| cobre(r)).peekable();
+        restritas.peek().is_none() || restritas.any(do_papel)
+    }
+
+    /// Se algum dos `roles` pode ler o valor em `pointer`.
+    pub fn can_read(&self, roles: &[&str], pointer: &str) -> bool {
+        self.pode_ler(roles, &segmentos(pointer))
+    }
+
+    /// Visão de `doc` para quem tem `roles`: caminhos não legíveis são
+    /// removidos (itens de arrays também, reindexando o restante).
+    pub fn view(&self, doc: &Value, roles: &[&str]) -> Value {
+        self.filtrar(doc, roles, &mut Vec::new())
+            .unwrap_or(Value::Null)
+    }
+
+    fn filtrar(&self, value: &Value, roles: &[&str], caminho: &mut Vec<String>) -> Option<Value> {
+        if !self.pode_ler(roles, caminho) {
+            return None;
+        }
+        let saida = match value {
+            Value::Object(map) => {
+                let mut out = Map::new();
+                for (k, v) in map {
+                    caminho.push(k.clone());
+                    if let Some(v) = self.filtrar(v, roles, caminho) {
+                        out.insert(k.clone(), v);
+                    }
+                    caminho.pop();
+                }
+                Value::Object(out)
+            }
+            Value::Array(itens) => {
+                let mut out = Vec::new();
+                for (i, v) in itens.iter().enumerate() {
+                    caminho.push(i.to_string());
+                    out.extend(self.filtrar(v, roles, caminho));
+                    caminho.pop();
+                }
+                Value::Array(out)
+            }
+            escalar => escalar.clone(),
+        };
+        Some(saida)
+    }
+}
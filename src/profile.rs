@@ -0,0 +1,158 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+// Perfis de saída por público (internal, partner, public): cada perfil tem
+// regras de remoção, mascaramento, liberação e renomeação por caminho (JSON
+// Pointer, com `*` casando qualquer chave ou índice). Perfis externos
+// mascaram por padrão: só sai em claro o que foi liberado com `allow`.
+
+/// Valor que substitui campos mascarados.
+pub const MASK: &str = "***";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Profile {
+    Internal,
+    Partner,
+    Public,
+}
+
+fn segmentos(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn casa(padrao: &[String], caminho: &[String]) -> bool {
+    padrao.len() == caminho.len() && padrao.iter().zip(caminho).all(|(p, c)| p == "*" || p == c)
+}
+
+/// Regras de um perfil.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    mask_by_default: bool,
+    allow: Vec<Vec<String>>,
+    redact: Vec<Vec<String>>,
+    drop: Vec<Vec<String>>,
+    rename: Vec<(Vec<String>, String)>,
+}
+
+impl Rules {
+    /// Regras que deixam tudo passar, exceto o que for removido/mascarado.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Regras que mascaram todo escalar não liberado com [`allow`](Self::allow).
+    pub fn mask_by_default() -> Self {
+        Rules {
+            mask_by_default: true,
+            ..Self::default()
+        }
+    }
+
+    /// Libera `pointer` (e tudo abaixo dele) em claro.
+    pub fn allow(mut self, pointer: &str) -> Self {
+        self.allow.push(segmentos(pointer));
+        self
+    }
+
+    /// Troca o valor em `pointer` (inteiro, mesmo objeto/array) por [`MASK`].
+    pub fn redact(mut self, pointer: &str) -> Self {
+        self.redact.push(segmentos(pointer));
+        self
+    }
+
+    /// Remove o campo em `pointer`.
+    pub fn drop(mut self, pointer: &str) -> Self {
+        self.drop.push(segmentos(pointer));
+        self
+    }
+
+    /// Renomeia a chave em `pointer` na saída; as demais regras continuam
+    /// usando o caminho original.
+    pub fn rename(mut self, pointer: &str, name: impl Into<String>) -> Self {
+        self.rename.push((segmentos(pointer), name.into()));
+        self
+    }
+
+    /// Aplica as regras: remoção vence mascaramento, que vence liberação.
+    pub fn apply(&self, value: &Value) -> Value {
+        self.aplicar(value, &mut Vec::new(), !self.mask_by_default)
+            .unwrap_or(Value::Null)
+    }
+
+    fn aplicar(&self, value: &Value, caminho: &mut Vec<String>, liberado: bool) -> Option<Value> {
+        if self.drop.iter().any(|p| casa(p, caminho)) {
+            return None;
+        }
+        if self.redact.iter().any(|p| casa(p, caminho)) {
+            return Some(Value::from(MASK));
+        }
+        let liberado = liberado || self.allow.iter().any(|p| casa(p, caminho));
+        let saida = match value {
+            Value::Object(map) => {
+                let mut out = Map::new();
+                for (k, v) in map {
+                    caminho.push(k.clone());
+                    if let Some(v) = self.aplicar(v, caminho, liberado) {
+                        let nome = self
+                            .rename
+                            .iter()
+                            .find(|(p, _)| casa(p, caminho))
+                            .map_or_else(|| k.clone(), |(_, nome)| nome.clone());
+                        out.insert(nome, v);
+                    }
+                    caminho.pop();
+                }
+                Value::Object(out)
+            }
+            Value::Array(itens) => {
+                let mut out = Vec::new();
+                for (i, v) in itens.iter().enumerate() {
+                    caminho.push(i.to_string());
+                    out.extend(self.aplicar(v, caminho, liberado));
+                    caminho.pop();
+                }
+                Value::Array(out)
+            }
+            escalar if liberado => escalar.clone(),
+            _ => Value::from(MASK),
+        };
+        Some(saida)
+    }
+}
+
+static REGRAS: RwLock<BTreeMap<Profile, Rules>> = RwLock::new(BTreeMap::new());
+
+/// Define as regras do perfil para o processo. Sem configuração, `Internal`
+/// deixa tudo passar e `Partner`/`Public` mascaram tudo.
+pub fn set_rules(profile: Profile, rules: Rules) {
+    REGRAS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(profile, rules);
+}
+
+/// Documento como deve sair para o público de `profile`.
+pub fn render(value: &Value, profile: Profile) -> Value {
+    let regras = REGRAS.read().unwrap_or_else(|e| e.into_inner());
+    match (regras.get(&profile), profile) {
+        (Some(r), _) => r.apply(value),
+        (None, Profile::Internal) => value.clone(),
+        (None, _) => Rules::mask_by_default().apply(value),
+    }
+}
+
+/// `value.render(Profile::Public)`.
+pub trait Render {
+    fn render(&self, profile: Profile) -> Value;
+}
+
+impl Render for Value {
+    fn render(&self, profile: Profile) -> Value {
+        render(self, profile)
+    }
+}
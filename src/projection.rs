@@ -0,0 +1,302 @@
+use serde_json::{Map, Value};
+
+// Projeção estilo GraphQL: `user { id name address { city } } items { sku }`
+// escolhe os campos que ficam no documento. Campos sem sub-seleção são
+// copiados inteiros; arrays aplicam a seleção a cada item; campos ausentes
+// são omitidos. Vírgulas entre campos são opcionais. `Fieldsets` traduz
+// os parâmetros REST `?fields=...&expand=...` para a mesma projeção.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+#[derive(Debug)]
+struct Campo {
+    nome: String,
+    filhos: Option<Vec<Campo>>,
+}
+
+enum Token {
+    Nome(String),
+    Abre,
+    Fecha,
+}
+
+fn tokens(selection: &str) -> Vec<(usize, Token)> {
+    let mut out = Vec::new();
+    let mut chars = selection.char_indices().peekable();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '{' => out.push((pos, Token::Abre)),
+            '}' => out.push((pos, Token::Fecha)),
+            c if c.is_whitespace() || c == ',' => {}
+            c => {
+                let mut nome = String::from(c);
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, ',' | '{' | '}') {
+                        break;
+                    }
+                    nome.push(c);
+                    chars.next();
+                }
+                out.push((pos, Token::Nome(nome)));
+            }
+        }
+    }
+    out
+}
+
+fn selecao(
+    tokens: &[(usize, Token)],
+    i: &mut usize,
+    aninhado: bool,
+) -> serde_json::Result<Vec<Campo>> {
+    let mut campos: Vec<Campo> = Vec::new();
+    loop {
+        match tokens.get(*i) {
+            None if aninhado => return Err(erro("projeção: `{` sem `}` correspondente".into())),
+            None => break,
+            Some((_, Token::Fecha)) if aninhado => {
+                *i += 1;
+                break;
+            }
+            Some((pos, Token::Fecha)) => {
+                return Err(erro(format!("projeção: `}}` inesperado na posição {pos}")));
+            }
+            Some((pos, Token::Abre)) => {
+                return Err(erro(format!(
+                    "projeção: `{{` sem campo antes, na posição {pos}"
+                )));
+            }
+            Some((_, Token::Nome(nome))) => {
+                *i += 1;
+                let filhos = match tokens.get(*i) {
+                    Some((_, Token::Abre)) => {
+                        *i += 1;
+                        Some(selecao(tokens, i, true)?)
+                    }
+                    _ => None,
+                };
+                campos.push(Campo {
+                    nome: nome.clone(),
+                    filhos,
+                });
+            }
+        }
+    }
+    if campos.is_empty() {
+        return Err(erro("projeção: seleção vazia".into()));
+    }
+    Ok(campos)
+}
+
+fn aplicar(value: &Value, campos: &[Campo]) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut out = Map::new();
+            for campo in campos {
+                if let Some(v) = map.get(&campo.nome) {
+                    let v = match &campo.filhos {
+                        Some(filhos) => aplicar(v, filhos),
+                        None => v.clone(),
+                    };
+                    out.insert(campo.nome.clone(), v);
+                }
+            }
+            Value::Object(out)
+        }
+        Value::Array(itens) => Value::Array(itens.iter().map(|v| aplicar(v, campos)).collect()),
+        // Sub-seleção sobre escalar: o valor fica como está
+        escalar => escalar.clone(),
+    }
+}
+
+/// Documento reduzido aos campos de `selection`. Erros de sintaxe (chaves
+/// desbalanceadas, seleção vazia) são reportados antes de olhar o documento.
+pub fn project(value: &Value, selection: &str) -> serde_json::Result<Value> {
+    let tokens = tokens(selection);
+    let campos = selecao(&tokens, &mut 0, false)?;
+    Ok(aplicar(value, &campos))
+}
+
+// Arvore de campos pontilhados (`author.name`) na ordem em que aparecem
+#[derive(Default)]
+struct Arvore(Vec<(String, Arvore)>);
+
+impl Arvore {
+    fn inserir(&mut self, segmentos: &[&str]) {
+        let Some((primeiro, resto)) = segmentos.split_first() else {
+            return;
+        };
+        let indice = match self.0.iter().position(|(n, _)| n == primeiro) {
+            Some(i) => i,
+            None => {
+                self.0.push((primeiro.to_string(), Arvore::default()));
+                self.0.len() - 1
+            }
+        };
+        self.0[indice].1.inserir(resto);
+    }
+
+    fn selecao(&self) -> String {
+        self.0
+            .iter()
+            .map(|(nome, filhos)| match filhos.0.is_empty() {
+                true => nome.clone(),
+                false => format!("{nome} {{ {} }}", filhos.selecao()),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn decodificar(texto: &str) -> serde_json::Result<String> {
+    let bytes = texto.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = texto
+                    .get(i + 1..i + 3)
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or_else(|| erro(format!("query: escape inválido em {texto:?}")))?;
+                out.push(hex);
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(out).map_err(|_| erro(format!("query: UTF-8 inválido em {texto:?}")))
+}
+
+fn lista(valor: &str) -> serde_json::Result<Vec<String>> {
+    let mut out = Vec::new();
+    for item in valor.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let valido = item.split('.').all(|seg| {
+            !seg.is_empty() && !seg.contains(|c: char| c.is_whitespace() || matches!(c, '{' | '}'))
+        });
+        if !valido {
+            return Err(erro(format!("query: campo inválido: {item:?}")));
+        }
+        out.push(item.to_string());
+    }
+    Ok(out)
+}
+
+/// Parâmetros `fields` e `expand` de uma requisição REST
+/// (`?fields=author.name,title&expand=comments`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fieldsets {
+    /// `None` quando `fields` não veio: todos os campos.
+    pub fields: Option<Vec<String>>,
+    pub expand: Vec<String>,
+}
+
+impl Fieldsets {
+    /// Lê a query string (com ou sem `?`); outros parâmetros são ignorados.
+    /// Parâmetros repetidos acumulam.
+    pub fn parse(query: &str) -> serde_json::Result<Self> {
+        let mut out = Fieldsets::default();
+        let query = query.trim().trim_start_matches('?');
+        for par in query.split('&').filter(|p| !p.is_empty()) {
+            let (nome, valor) = par.split_once('=').unwrap_or((par, ""));
+            match decodificar(nome)?.as_str() {
+                "fields" => out
+                    .fields
+                    .get_or_insert_with(Vec::new)
+                    .extend(lista(&decodificar(valor)?)?),
+                "expand" => out.expand.extend(lista(&decodificar(valor)?)?),
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// Aplica sobre o documento completo: campos de `expandable` que não
+    /// foram pedidos em `expand` ficam recolhidos (objetos viram o seu `id`,
+    /// ou somem se não tiverem um, também de dentro de arrays; um valor que
+    /// já é um id ou null fica como está), e depois `fields` projeta o
+    /// resultado.
+    /// Pedir a expansão de um campo fora de `expandable` é erro.
+    pub fn apply(&self, doc: &Value, expandable: &[&str]) -> serde_json::Result<Value> {
+        if let Some(campo) = self
+            .expand
+            .iter()
+            .find(|e| !expandable.contains(&e.as_str()))
+        {
+            return Err(erro(format!("expand: campo não expansível: {campo:?}")));
+        }
+        let mut saida = doc.clone();
+        for campo in expandable {
+            if !self.expand.iter().any(|e| e == campo) {
+                let segmentos: Vec<&str> = campo.split('.').collect();
+                recolher(&mut saida, &segmentos);
+            }
+        }
+        match &self.fields {
+            None => Ok(saida),
+            Some(campos) if campos.is_empty() => Ok(Value::Object(Map::new())),
+            Some(campos) => {
+                let mut arvore = Arvore::default();
+                for campo in campos {
+                    arvore.inserir(&campo.split('.').collect::<Vec<_>>());
+                }
+                project(&saida, &arvore.selecao())
+            }
+        }
+    }
+}
+
+// Troca o valor em `segmentos` (percorrendo itens de arrays) pela referência
+fn recolher(value: &mut Value, segmentos: &[&str]) {
+    match value {
+        Value::Array(itens) => {
+            for item in itens {
+                recolher(item, segmentos);
+            }
+        }
+        Value::Object(map) => {
+            let Some((primeiro, resto)) = segmentos.split_first() else {
+                return;
+            };
+            if !resto.is_empty() {
+                if let Some(filho) = map.get_mut(*primeiro) {
+                    recolher(filho, resto);
+                }
+                return;
+            }
+            match map.get(*primeiro).map(referencia) {
+                Some(Some(id)) => {
+                    map.insert(primeiro.to_string(), id);
+                }
+                Some(None) => {
+                    map.remove(*primeiro);
+                }
+                None => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+// Objeto -> o seu `id` (`None` sem um); array -> as referências dos
+// itens, sem os objetos sem `id`; escalares e null já são referências
+fn referencia(value: &Value) -> Option<Value> {
+    match value {
+        Value::Object(map) => map.get("id").cloned(),
+        Value::Array(itens) => Some(Value::Array(itens.iter().filter_map(referencia).collect())),
+        _ => Some(value.clone()),
+    }
+}
+
+/// Atalho: `Fieldsets::parse(query)?.apply(doc, expandable)`.
+pub fn sparse_fieldsets(
+    doc: &Value,
+    query: &str,
+    expandable: &[&str],
+) -> serde_json::Result<Value> {
+    Fieldsets::parse(query)?.apply(doc, expandable)
+}
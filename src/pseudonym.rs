@@ -0,0 +1,77 @@
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::digest;
+
+// Pseudônimos estáveis: HMAC-SHA256(chave, namespace || 0x00 || id) em hex,
+// truncado em 128 bits. O mesmo id no mesmo namespace gera sempre o mesmo
+// pseudônimo (joins continuam funcionando), sem expor o id real.
+
+// pseudo_id!(user.id, "analytics")
+#[macro_export]
+macro_rules! pseudo_id {
+    ($real_id:expr, $namespace:expr) => {
+        $crate::pseudonym::PseudoId::new($real_id, $namespace)
+    };
+}
+
+static CHAVE: RwLock<Option<Vec<u8>>> = RwLock::new(None);
+
+/// Define a chave secreta do processo. Sem chave, `pseudo_id!` falha ao
+/// serializar.
+pub fn set_key(key: impl Into<Vec<u8>>) {
+    *CHAVE.write().unwrap_or_else(|e| e.into_inner()) = Some(key.into());
+}
+
+/// Pseudônimo de `real_id`, com chave explícita. Ids numéricos e strings
+/// com o mesmo texto (`42` e `"42"`) geram o mesmo pseudônimo.
+pub fn pseudonymize(key: &[u8], real_id: &Value, namespace: &str) -> Result<String, String> {
+    let id = match real_id {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        outro => return Err(format!("id deve ser string ou número, recebeu {outro}")),
+    };
+    let mut mensagem = namespace.as_bytes().to_vec();
+    mensagem.push(0);
+    mensagem.extend_from_slice(id.as_bytes());
+    Ok(digest::hex(&digest::hmac_sha256(key, &mensagem)[..16]))
+}
+
+#[derive(Clone)]
+pub struct PseudoId {
+    real_id: Result<Value, String>,
+    namespace: String,
+}
+
+// Sem o id real: um `{:?}` em log não pode vazar o que o pseudônimo esconde
+impl fmt::Debug for PseudoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PseudoId")
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PseudoId {
+    pub fn new<T: Serialize>(real_id: T, namespace: impl Into<String>) -> Self {
+        PseudoId {
+            real_id: serde_json::to_value(real_id).map_err(|e| e.to_string()),
+            namespace: namespace.into(),
+        }
+    }
+}
+
+impl Serialize for PseudoId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let id = self.real_id.as_ref().map_err(S::Error::custom)?;
+        let chave = CHAVE.read().unwrap_or_else(|e| e.into_inner());
+        let chave = chave
+            .as_deref()
+            .ok_or_else(|| S::Error::custom("pseudo_id! sem chave: chame pseudonym::set_key"))?;
+        let pseudo = pseudonymize(chave, id, &self.namespace).map_err(S::Error::custom)?;
+        serializer.serialize_str(&pseudo)
+    }
+}
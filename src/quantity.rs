@@ -0,0 +1,92 @@
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::sync::RwLock;
+
+// Medidas com unidade (`ms`, `bytes`, `%`...) num formato único para a
+// telemetria: `{ value, unit }` ou string com sufixo (`"250 ms"`).
+
+// quantity!(250, "ms") ou quantity!(250, "ms", QuantityShape::Suffixed)
+#[macro_export]
+macro_rules! quantity {
+    ($value:expr, $unit:expr) => {
+        $crate::quantity::Quantity::new($value, $unit)
+    };
+    ($value:expr, $unit:expr, $shape:expr) => {
+        $crate::quantity::Quantity::new($value, $unit).shape($shape)
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantityShape {
+    /// `{ "value": 250, "unit": "ms" }` (padrão)
+    Object,
+    /// `"250 ms"`; `%` vai colado (`"12.5%"`)
+    Suffixed,
+}
+
+static FORMATO_PADRAO: RwLock<QuantityShape> = RwLock::new(QuantityShape::Object);
+
+/// Formato usado pelos `quantity!` sem formato explícito.
+pub fn set_default_shape(shape: QuantityShape) {
+    *FORMATO_PADRAO.write().unwrap_or_else(|e| e.into_inner()) = shape;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    // Erro guardado como texto até a serialização
+    value: Result<Value, String>,
+    unit: String,
+    shape: Option<QuantityShape>,
+}
+
+impl Quantity {
+    /// `value` deve serializar como número finito.
+    pub fn new<T: Serialize>(value: T, unit: impl Into<String>) -> Self {
+        Quantity {
+            value: serde_json::to_value(value).map_err(|e| e.to_string()),
+            unit: unit.into(),
+            shape: None,
+        }
+    }
+
+    pub fn shape(mut self, shape: QuantityShape) -> Self {
+        self.shape = Some(shape);
+        self
+    }
+}
+
+impl Serialize for Quantity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let valor = match &self.value {
+            Ok(Value::Number(n)) => n,
+            Ok(outro) => {
+                return Err(S::Error::custom(format!(
+                    "quantidade não numérica: {outro}"
+                )));
+            }
+            Err(e) => return Err(S::Error::custom(e)),
+        };
+        if self.unit.is_empty() || self.unit.chars().any(char::is_whitespace) {
+            return Err(S::Error::custom(format!(
+                "unidade inválida: {:?}",
+                self.unit
+            )));
+        }
+        let shape = self
+            .shape
+            .unwrap_or_else(|| *FORMATO_PADRAO.read().unwrap_or_else(|e| e.into_inner()));
+        match shape {
+            QuantityShape::Suffixed if self.unit == "%" => {
+                serializer.serialize_str(&format!("{valor}%"))
+            }
+            QuantityShape::Suffixed => serializer.serialize_str(&format!("{valor} {}", self.unit)),
+            QuantityShape::Object => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("value", valor)?;
+                map.serialize_entry("unit", &self.unit)?;
+                map.end()
+            }
+        }
+    }
+}
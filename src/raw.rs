@@ -0,0 +1,71 @@
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+// Fragmentos já serializados (cache, outro serviço) dentro do `json_safe!`:
+// `raw!(texto)` valida o JSON e o encaixa na árvore como `Value`. Texto
+// inválido vira erro do campo, com o caminho. Com a feature "raw-value",
+// aceita também `Box<RawValue>`/`&RawValue` do serde_json.
+
+// raw!(r#"{"id":1}"#) -> { "id": 1 }
+#[macro_export]
+macro_rules! raw {
+    ($json:expr) => {
+        $crate::raw::Raw::new($json)
+    };
+}
+
+/// Texto JSON pronto: `str`, `String` e, com "raw-value", `RawValue`.
+pub trait RawJson {
+    fn json(&self) -> &str;
+}
+
+impl RawJson for str {
+    fn json(&self) -> &str {
+        self
+    }
+}
+
+impl RawJson for String {
+    fn json(&self) -> &str {
+        self
+    }
+}
+
+#[cfg(feature = "raw-value")]
+impl RawJson for serde_json::value::RawValue {
+    fn json(&self) -> &str {
+        self.get()
+    }
+}
+
+impl<T: RawJson + ?Sized> RawJson for &T {
+    fn json(&self) -> &str {
+        (**self).json()
+    }
+}
+
+impl<T: RawJson + ?Sized> RawJson for Box<T> {
+    fn json(&self) -> &str {
+        (**self).json()
+    }
+}
+
+/// Fragmento JSON validado na serialização.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raw<T>(T);
+
+impl<T: RawJson> Raw<T> {
+    pub fn new(json: T) -> Self {
+        Raw(json)
+    }
+}
+
+impl<T: RawJson> Serialize for Raw<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match serde_json::from_str::<Value>(self.0.json()) {
+            Ok(value) => value.serialize(serializer),
+            Err(e) => Err(S::Error::custom(format!("raw!: JSON inválido: {e}"))),
+        }
+    }
+}
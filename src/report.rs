@@ -0,0 +1,165 @@
+use serde_json::{Map, Value};
+
+// Renderização de documentos em texto para notificações (e-mail, chat).
+// Arrays de objetos viram tabelas; objetos viram listas chave/valor.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    PlainText,
+    Markdown,
+}
+
+/// Renderiza `value` no layout escolhido.
+pub fn render(value: &Value, layout: Layout) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) => render_objeto(map, layout, &mut out),
+        Value::Array(itens) if e_tabela(itens) => render_tabela(itens, layout, &mut out),
+        outro => {
+            out.push_str(&celula(outro));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn e_tabela(itens: &[Value]) -> bool {
+    !itens.is_empty() && itens.iter().all(Value::is_object)
+}
+
+fn celula(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        outro => outro.to_string(),
+    }
+}
+
+// Texto de uma célula de tabela numa linha só; no Markdown, `|` e quebras
+// de linha fechariam a célula
+fn texto_celula(texto: &str, layout: Layout) -> String {
+    let quebra = match layout {
+        Layout::PlainText => " ",
+        Layout::Markdown => "<br>",
+    };
+    let texto = texto.replace("\r\n", "\n").replace(['\n', '\r'], quebra);
+    match layout {
+        Layout::PlainText => texto,
+        Layout::Markdown => texto.replace('|', "\\|"),
+    }
+}
+
+fn largura(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn preencher(s: &str, largura_alvo: usize) -> String {
+    let mut out = s.to_string();
+    out.extend(std::iter::repeat_n(
+        ' ',
+        largura_alvo.saturating_sub(largura(s)),
+    ));
+    out
+}
+
+fn render_objeto(map: &Map<String, Value>, layout: Layout, out: &mut String) {
+    let (tabelas, campos): (Vec<_>, Vec<_>) = map
+        .iter()
+        .partition(|(_, v)| matches!(v, Value::Array(itens) if e_tabela(itens)));
+
+    let largura_chave = campos.iter().map(|(k, _)| largura(k)).max().unwrap_or(0);
+    for (chave, valor) in &campos {
+        match layout {
+            Layout::PlainText => {
+                out.push_str(&preencher(&format!("{chave}:"), largura_chave + 1));
+                out.push(' ');
+                out.push_str(&celula(valor));
+            }
+            Layout::Markdown => {
+                out.push_str(&format!("- **{chave}**: {}", celula(valor)));
+            }
+        }
+        out.push('\n');
+    }
+
+    for (chave, valor) in tabelas {
+        let Value::Array(itens) = valor else {
+            continue;
+        };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        match layout {
+            Layout::PlainText => {
+                out.push_str(chave);
+                out.push('\n');
+            }
+            Layout::Markdown => {
+                out.push_str(&format!("### {chave}\n\n"));
+            }
+        }
+        render_tabela(itens, layout, out);
+    }
+}
+
+fn render_tabela(itens: &[Value], layout: Layout, out: &mut String) {
+    let mut colunas: Vec<&str> = Vec::new();
+    for item in itens {
+        if let Value::Object(map) = item {
+            for chave in map.keys() {
+                if !colunas.contains(&chave.as_str()) {
+                    colunas.push(chave);
+                }
+            }
+        }
+    }
+
+    let linhas: Vec<Vec<String>> = itens
+        .iter()
+        .map(|item| {
+            colunas
+                .iter()
+                .map(|c| texto_celula(&item.get(c).map(celula).unwrap_or_default(), layout))
+                .collect()
+        })
+        .collect();
+
+    let cabecalho: Vec<String> = colunas.iter().map(|c| texto_celula(c, layout)).collect();
+    let larguras: Vec<usize> = cabecalho
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            linhas
+                .iter()
+                .map(|l| largura(&l[i]))
+                .chain([largura(c), if layout == Layout::Markdown { 3 } else { 0 }])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let separador: Vec<String> = larguras.iter().map(|l| "-".repeat(*l)).collect();
+
+    let linha = |celulas: &[String], out: &mut String| {
+        let alinhadas: Vec<String> = celulas
+            .iter()
+            .zip(&larguras)
+            .map(|(c, l)| preencher(c, *l))
+            .collect();
+        match layout {
+            Layout::PlainText => out.push_str(alinhadas.join("  ").trim_end()),
+            Layout::Markdown => {
+                out.push_str("| ");
+                out.push_str(&alinhadas.join(" | "));
+                out.push_str(" |");
+            }
+        }
+        out.push('\n');
+    };
+
+    linha(&cabecalho, out);
+    linha(&separador, out);
+    for l in &linhas {
+        linha(l, out);
+    }
+}
@@ -0,0 +1,104 @@
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+// Metadados de proveniência (host, pid, versão e commit) para eventos. A
+// versão e o SHA do commit são os do crate que chama `runtime!()`, lidos em
+// tempo de compilação (`CARGO_PKG_VERSION` e `GIT_SHA`, se definida no
+// build).
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+// runtime!() -> Runtime com a versão e o GIT_SHA do crate chamador
+#[macro_export]
+macro_rules! runtime {
+    () => {
+        $crate::runtime::Runtime::detect()
+            .version(::std::env!("CARGO_PKG_VERSION"))
+            .git_sha(::std::option_env!("GIT_SHA"))
+    };
+}
+
+/// Metadados do processo atual.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Runtime {
+    key: String,
+    hostname: Option<String>,
+    pid: u32,
+    version: Option<String>,
+    git_sha: Option<String>,
+}
+
+fn hostname() -> Option<String> {
+    static HOST: OnceLock<Option<String>> = OnceLock::new();
+    HOST.get_or_init(|| {
+        std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+    })
+    .clone()
+}
+
+impl Runtime {
+    /// Host (`HOSTNAME`, `/etc/hostname` ou `COMPUTERNAME`) e pid; versão e
+    /// commit ficam vazios (use `runtime!()` para preenchê-los).
+    pub fn detect() -> Self {
+        Runtime {
+            key: "runtime".into(),
+            hostname: hostname(),
+            pid: std::process::id(),
+            version: None,
+            git_sha: None,
+        }
+    }
+
+    /// Chave onde os metadados são inseridos (padrão `runtime`).
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+
+    pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn git_sha(mut self, sha: Option<&str>) -> Self {
+        self.git_sha = sha.map(str::to_string);
+        self
+    }
+
+    /// `{ hostname, pid, version, git_sha }`, sem os campos desconhecidos.
+    pub fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        if let Some(host) = &self.hostname {
+            map.insert("hostname".into(), Value::from(host.clone()));
+        }
+        map.insert("pid".into(), Value::from(self.pid));
+        if let Some(versao) = &self.version {
+            map.insert("version".into(), Value::from(versao.clone()));
+        }
+        if let Some(sha) = &self.git_sha {
+            map.insert("git_sha".into(), Value::from(sha.clone()));
+        }
+        Value::Object(map)
+    }
+}
+
+/// Insere os metadados de `runtime` no objeto `doc`, na chave configurada.
+pub fn enrich_with_runtime(doc: &mut Value, runtime: &Runtime) -> serde_json::Result<()> {
+    let map = doc
+        .as_object_mut()
+        .ok_or_else(|| erro("enrich_with_runtime: documento não é objeto".into()))?;
+    map.insert(runtime.key.clone(), runtime.to_value());
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// SBOM no formato CycloneDX (JSON, specVersion 1.5) e relatório de licenças,
+// montados a partir da saída de `cargo metadata --format-version 1`.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentType {
+    Application,
+    Library,
+}
+
+/// Componente CycloneDX. `bom-ref` é a purl.
+#[derive(Debug, Clone)]
+pub struct Component {
+    kind: ComponentType,
+    name: String,
+    version: String,
+    license: Option<String>,
+    description: Option<String>,
+    depends_on: Vec<String>,
+}
+
+impl Component {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Component {
+            kind: ComponentType::Library,
+            name: name.into(),
+            version: version.into(),
+            license: None,
+            description: None,
+            depends_on: Vec::new(),
+        }
+    }
+
+    pub fn kind(mut self, kind: ComponentType) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Expressão SPDX (`MIT OR Apache-2.0`).
+    pub fn license(mut self, expression: impl Into<String>) -> Self {
+        self.license = Some(expression.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Dependência direta, pela purl.
+    pub fn depends_on(mut self, purl: impl Into<String>) -> Self {
+        self.depends_on.push(purl.into());
+        self
+    }
+
+    pub fn purl(&self) -> String {
+        format!("pkg:cargo/{}@{}", self.name, self.version)
+    }
+
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        if self.name.trim().is_empty() || self.version.trim().is_empty() {
+            return Err(erro("componente sem name/version".into()));
+        }
+        let mut map = Map::new();
+        let tipo = match self.kind {
+            ComponentType::Application => "application",
+            ComponentType::Library => "library",
+        };
+        map.insert("type".into(), Value::from(tipo));
+        map.insert("bom-ref".into(), Value::from(self.purl()));
+        map.insert("name".into(), Value::from(self.name.as_str()));
+        map.insert("version".into(), Value::from(self.version.as_str()));
+        map.insert("purl".into(), Value::from(self.purl()));
+        if let Some(descricao) = &self.description {
+            map.insert("description".into(), Value::from(descricao.as_str()));
+        }
+        if let Some(licenca) = &self.license {
+            let mut expr = Map::new();
+            expr.insert("expression".into(), Value::from(licenca.as_str()));
+            map.insert("licenses".into(), Value::Array(vec![Value::Object(expr)]));
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// Documento CycloneDX.
+#[derive(Debug, Clone, Default)]
+pub struct Bom {
+    root: Option<Component>,
+    components: Vec<Component>,
+}
+
+impl Bom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Componente descrito pelo SBOM (`metadata.component`).
+    pub fn root(mut self, component: Component) -> Self {
+        self.root = Some(component);
+        self
+    }
+
+    pub fn component(mut self, component: Component) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    /// Monta o SBOM a partir da saída de `cargo metadata`. O pacote
+    /// `resolve.root` (se houver) vira `metadata.component`; os demais
+    /// pacotes viram componentes, com as dependências de `resolve.nodes`.
+    pub fn from_cargo_metadata(metadata: &Value) -> serde_json::Result<Self> {
+        let pacotes = metadata["packages"]
+            .as_array()
+            .ok_or_else(|| erro("cargo metadata sem packages".into()))?;
+
+        // (id do pacote, componente), na ordem de `packages`
+        let mut por_id: Vec<(&str, Component)> = Vec::new();
+        for (i, pacote) in pacotes.iter().enumerate() {
+            let campo = |nome: &str| {
+                pacote[nome]
+                    .as_str()
+                    .ok_or_else(|| erro(format!("packages[{i}].{nome} ausente")))
+            };
+            let mut componente = Component::new(campo("name")?, campo("version")?);
+            if let Some(licenca) = pacote["license"].as_str() {
+                componente = componente.license(licenca);
+            }
+            if let Some(descricao) = pacote["description"].as_str() {
+                componente = componente.description(descricao.trim());
+            }
+            let binario = pacote["targets"].as_array().is_some_and(|alvos| {
+                alvos.iter().any(|alvo| {
+                    alvo["kind"]
+                        .as_array()
+                        .is_some_and(|k| k.contains(&Value::from("bin")))
+                })
+            });
+            if binario {
+                componente = componente.kind(ComponentType::Application);
+            }
+            por_id.push((campo("id")?, componente));
+        }
+
+        let purls: BTreeMap<&str, String> = por_id.iter().map(|(id, c)| (*id, c.purl())).collect();
+        for no in metadata["resolve"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let Some((_, componente)) = por_id.iter_mut().find(|(id, _)| no["id"] == *id) else {
+                continue;
+            };
+            for dep in no["deps"].as_array().into_iter().flatten() {
+                if let Some(purl) = dep["pkg"].as_str().and_then(|id| purls.get(id)) {
+                    componente.depends_on.push(purl.clone());
+                }
+            }
+        }
+
+        let raiz = metadata["resolve"]["root"].as_str();
+        let mut bom = Bom::new();
+        for (id, componente) in por_id {
+            if Some(id) == raiz {
+                bom.root = Some(componente);
+            } else {
+                bom.components.push(componente);
+            }
+        }
+        Ok(bom)
+    }
+
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        let mut map = Map::new();
+        map.insert("bomFormat".into(), Value::from("CycloneDX"));
+        map.insert("specVersion".into(), Value::from("1.5"));
+        map.insert("version".into(), Value::from(1));
+        if let Some(raiz) = &self.root {
+            let mut metadata = Map::new();
+            metadata.insert("component".into(), raiz.to_value()?);
+            map.insert("metadata".into(), Value::Object(metadata));
+        }
+        let componentes = self
+            .components
+            .iter()
+            .map(Component::to_value)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        map.insert("components".into(), Value::Array(componentes));
+
+        let dependencias: Vec<Value> = self
+            .root
+            .iter()
+            .chain(&self.components)
+            .filter(|c| !c.depends_on.is_empty())
+            .map(|c| {
+                let mut dep = Map::new();
+                dep.insert("ref".into(), Value::from(c.purl()));
+                dep.insert("dependsOn".into(), Value::from(c.depends_on.clone()));
+                Value::Object(dep)
+            })
+            .collect();
+        if !dependencias.is_empty() {
+            map.insert("dependencies".into(), Value::Array(dependencias));
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// Relatório `{ licença: ["nome@versão", ...] }` dos componentes (sem a
+    /// raiz); os sem licença declarada ficam em `"UNKNOWN"`.
+    pub fn license_report(&self) -> Value {
+        let mut por_licenca: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for c in &self.components {
+            por_licenca
+                .entry(c.license.as_deref().unwrap_or("UNKNOWN"))
+                .or_default()
+                .push(format!("{}@{}", c.name, c.version));
+        }
+        Value::Object(
+            por_licenca
+                .into_iter()
+                .map(|(licenca, nomes)| (licenca.to_string(), Value::from(nomes)))
+                .collect(),
+        )
+    }
+}
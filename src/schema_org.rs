@@ -0,0 +1,214 @@
+use serde_json::{Map, Value};
+
+// Builders de snippets schema.org (JSON-LD) para SEO em páginas SSR.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+const CONTEXTO: &str = "https://schema.org";
+
+fn raiz(tipo: &str) -> Map<String, Value> {
+    let mut map = Map::new();
+    map.insert("@context".into(), Value::from(CONTEXTO));
+    map.insert("@type".into(), Value::from(tipo));
+    map
+}
+
+fn inserir_opcional(map: &mut Map<String, Value>, chave: &str, valor: &Option<String>) {
+    if let Some(v) = valor {
+        map.insert(chave.into(), Value::from(v.as_str()));
+    }
+}
+
+fn exigir(campo: &str, valor: &str, tipo: &str) -> serde_json::Result<()> {
+    if valor.trim().is_empty() {
+        return Err(erro(format!("{tipo}: campo obrigatório vazio: {campo}")));
+    }
+    Ok(())
+}
+
+/// Tipos schema.org que podem ser emitidos como JSON-LD.
+pub trait StructuredData {
+    /// Monta o documento JSON-LD, validando os campos obrigatórios.
+    fn to_value(&self) -> serde_json::Result<Value>;
+
+    /// Conteúdo pronto para `<script type="application/ld+json">`, com `<`
+    /// escapado para que o payload não feche a tag.
+    fn to_script(&self) -> serde_json::Result<String> {
+        let json = serde_json::to_string(&self.to_value()?)?;
+        Ok(json.replace('<', "\\u003c"))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Organization {
+    name: String,
+    url: Option<String>,
+    logo: Option<String>,
+    same_as: Vec<String>,
+}
+
+impl Organization {
+    pub fn new(name: impl Into<String>) -> Self {
+        Organization {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn logo(mut self, logo: impl Into<String>) -> Self {
+        self.logo = Some(logo.into());
+        self
+    }
+
+    pub fn same_as(mut self, perfil: impl Into<String>) -> Self {
+        self.same_as.push(perfil.into());
+        self
+    }
+}
+
+impl StructuredData for Organization {
+    fn to_value(&self) -> serde_json::Result<Value> {
+        exigir("name", &self.name, "Organization")?;
+        let mut map = raiz("Organization");
+        map.insert("name".into(), Value::from(self.name.as_str()));
+        inserir_opcional(&mut map, "url", &self.url);
+        inserir_opcional(&mut map, "logo", &self.logo);
+        if !self.same_as.is_empty() {
+            map.insert("sameAs".into(), Value::from(self.same_as.clone()));
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Product {
+    name: String,
+    sku: Option<String>,
+    description: Option<String>,
+    image: Option<String>,
+    brand: Option<String>,
+    // (preço decimal em texto, moeda ISO 4217)
+    offer: Option<(String, String)>,
+}
+
+impl Product {
+    pub fn new(name: impl Into<String>) -> Self {
+        Product {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn sku(mut self, sku: impl Into<String>) -> Self {
+        self.sku = Some(sku.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn image(mut self, image: impl Into<String>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    pub fn brand(mut self, brand: impl Into<String>) -> Self {
+        self.brand = Some(brand.into());
+        self
+    }
+
+    /// Oferta com preço em texto decimal (ex.: `"19.90"`), evitando floats.
+    pub fn offer(mut self, price: impl Into<String>, currency: impl Into<String>) -> Self {
+        self.offer = Some((price.into(), currency.into()));
+        self
+    }
+}
+
+impl StructuredData for Product {
+    fn to_value(&self) -> serde_json::Result<Value> {
+        exigir("name", &self.name, "Product")?;
+        let mut map = raiz("Product");
+        map.insert("name".into(), Value::from(self.name.as_str()));
+        inserir_opcional(&mut map, "sku", &self.sku);
+        inserir_opcional(&mut map, "description", &self.description);
+        inserir_opcional(&mut map, "image", &self.image);
+        if let Some(brand) = &self.brand {
+            let mut marca = Map::new();
+            marca.insert("@type".into(), Value::from("Brand"));
+            marca.insert("name".into(), Value::from(brand.as_str()));
+            map.insert("brand".into(), Value::Object(marca));
+        }
+        if let Some((preco, moeda)) = &self.offer {
+            let preco_valido = preco.split_once('.').map_or_else(
+                || !preco.is_empty() && preco.bytes().all(|b| b.is_ascii_digit()),
+                |(int, frac)| {
+                    !int.is_empty()
+                        && !frac.is_empty()
+                        && int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+                },
+            );
+            if !preco_valido {
+                return Err(erro(format!("Product: preço inválido: {preco:?}")));
+            }
+            if moeda.len() != 3 || !moeda.bytes().all(|b| b.is_ascii_uppercase()) {
+                return Err(erro(format!("Product: moeda inválida: {moeda:?}")));
+            }
+            let mut oferta = Map::new();
+            oferta.insert("@type".into(), Value::from("Offer"));
+            oferta.insert("price".into(), Value::from(preco.as_str()));
+            oferta.insert("priceCurrency".into(), Value::from(moeda.as_str()));
+            map.insert("offers".into(), Value::Object(oferta));
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BreadcrumbList {
+    items: Vec<(String, String)>,
+}
+
+impl BreadcrumbList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acrescenta um nível; a `position` é atribuída pela ordem (a partir de 1).
+    pub fn item(mut self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.items.push((name.into(), url.into()));
+        self
+    }
+}
+
+impl StructuredData for BreadcrumbList {
+    fn to_value(&self) -> serde_json::Result<Value> {
+        if self.items.is_empty() {
+            return Err(erro(
+                "BreadcrumbList: ao menos um item é obrigatório".into(),
+            ));
+        }
+        let mut itens = Vec::with_capacity(self.items.len());
+        for (i, (nome, url)) in self.items.iter().enumerate() {
+            exigir("name", nome, "BreadcrumbList")?;
+            exigir("item", url, "BreadcrumbList")?;
+            let mut item = Map::new();
+            item.insert("@type".into(), Value::from("ListItem"));
+            item.insert("position".into(), Value::from(i + 1));
+            item.insert("name".into(), Value::from(nome.as_str()));
+            item.insert("item".into(), Value::from(url.as_str()));
+            itens.push(Value::Object(item));
+        }
+        let mut map = raiz("BreadcrumbList");
+        map.insert("itemListElement".into(), Value::Array(itens));
+        Ok(Value::Object(map))
+    }
+}
@@ -0,0 +1,290 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::event_schema::{EventSchema, FieldType};
+
+// Cliente de schema registry no estilo Confluent (feature "schema-registry"):
+// busca o JSON Schema registrado de um subject (`/subjects/{s}/versions/..`),
+// compila num `EventSchema` e valida documentos contra a versão mais recente
+// ou uma fixada. Só std: HTTP/1.1 em texto puro, sem TLS. O subconjunto
+// aceito de JSON Schema é o que o `EventSchema` representa: objeto com
+// `properties` tipadas, `required` e `additionalProperties`.
+
+// Respostas maiores que isso são recusadas
+const MAX_RESPOSTA: u64 = 4 << 20;
+const ACEITA: &str = "application/vnd.schemaregistry.v1+json, application/json";
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+/// Versão de um subject no registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Version {
+    Latest,
+    Pinned(u32),
+}
+
+/// Cliente com cache dos schemas compilados.
+#[derive(Debug)]
+pub struct RegistryClient {
+    host: String,
+    porta: u16,
+    prefixo: String,
+    timeout: Duration,
+    cache: Mutex<BTreeMap<(String, Version), Arc<EventSchema>>>,
+}
+
+impl RegistryClient {
+    /// Cliente para `url` (`http://host[:porta][/prefixo]`).
+    pub fn new(url: &str) -> serde_json::Result<Self> {
+        let resto = url
+            .strip_prefix("http://")
+            .ok_or_else(|| erro(format!("schema_registry: só http:// é suportado: {url}")))?;
+        let (autoridade, prefixo) = match resto.find('/') {
+            Some(i) => (&resto[..i], resto[i..].trim_end_matches('/')),
+            None => (resto, ""),
+        };
+        let (host, porta) = match autoridade.rsplit_once(':') {
+            Some((host, porta)) => {
+                let porta = porta
+                    .parse()
+                    .map_err(|_| erro(format!("schema_registry: porta inválida em {url}")))?;
+                (host, porta)
+            }
+            None => (autoridade, 80),
+        };
+        if host.is_empty() {
+            return Err(erro(format!("schema_registry: host ausente em {url}")));
+        }
+        Ok(RegistryClient {
+            host: host.to_string(),
+            porta,
+            prefixo: prefixo.to_string(),
+            timeout: Duration::from_secs(10),
+            cache: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Prazo de conexão e de leitura de cada requisição (padrão: 10s).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Schema compilado de `subject` em `version`, do cache ou do registry.
+    /// `Latest` fica em cache até [`RegistryClient::refresh`].
+    pub fn schema(&self, subject: &str, version: Version) -> serde_json::Result<Arc<EventSchema>> {
+        let chave = (subject.to_string(), version);
+        if let Some(schema) = self.travar().get(&chave) {
+            return Ok(Arc::clone(schema));
+        }
+        // A busca corre fora do lock; duas threads podem buscar o mesmo
+        // subject, e a segunda só sobrescreve com o mesmo schema
+        let schema = Arc::new(self.buscar(subject, version)?);
+        self.travar().insert(chave, Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    /// Esquece a versão mais recente de `subject` em cache.
+    pub fn refresh(&self, subject: &str) {
+        self.travar()
+            .remove(&(subject.to_string(), Version::Latest));
+    }
+
+    /// Valida `value` contra o schema e retorna os bytes JSON.
+    pub fn emit_checked(
+        &self,
+        subject: &str,
+        version: Version,
+        value: &Value,
+    ) -> serde_json::Result<Vec<u8>> {
+        let violacoes = self.schema(subject, version)?.violations(value);
+        if !violacoes.is_empty() {
+            return Err(erro(format!("{subject}: {}", violacoes.join("; "))));
+        }
+        serde_json::to_vec(value)
+    }
+
+    fn travar(&self) -> std::sync::MutexGuard<'_, BTreeMap<(String, Version), Arc<EventSchema>>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn buscar(&self, subject: &str, version: Version) -> serde_json::Result<EventSchema> {
+        let versao = match version {
+            Version::Latest => "latest".to_string(),
+            Version::Pinned(n) => n.to_string(),
+        };
+        let path = format!(
+            "{}/subjects/{}/versions/{versao}",
+            self.prefixo,
+            codificar(subject)
+        );
+        let registro = self.get(&path)?;
+        if let Some(tipo) = registro.get("schemaType").and_then(Value::as_str)
+            && tipo != "JSON"
+        {
+            return Err(erro(format!(
+                "schema_registry: {subject} é {tipo}, não JSON Schema"
+            )));
+        }
+        let texto = registro
+            .get("schema")
+            .and_then(Value::as_str)
+            .ok_or_else(|| erro(format!("schema_registry: {subject} sem campo schema")))?;
+        let schema: Value = serde_json::from_str(texto).map_err(|e| {
+            erro(format!(
+                "schema_registry: schema de {subject} inválido: {e}"
+            ))
+        })?;
+        compilar(&schema).map_err(|e| erro(format!("schema_registry: {subject}: {e}")))
+    }
+
+    fn get(&self, path: &str) -> serde_json::Result<Value> {
+        let rede = |e: std::io::Error| erro(format!("schema_registry: GET {path}: {e}"));
+        let endereco = (self.host.as_str(), self.porta)
+            .to_socket_addrs()
+            .map_err(rede)?
+            .next()
+            .ok_or_else(|| erro(format!("schema_registry: host {} não resolvido", self.host)))?;
+        let mut conexao = TcpStream::connect_timeout(&endereco, self.timeout).map_err(rede)?;
+        conexao.set_read_timeout(Some(self.timeout)).map_err(rede)?;
+        conexao
+            .set_write_timeout(Some(self.timeout))
+            .map_err(rede)?;
+        write!(
+            conexao,
+            "GET {path} HTTP/1.1\r\nHost: {}\r\nAccept: {ACEITA}\r\nConnection: close\r\n\r\n",
+            self.host
+        )
+        .map_err(rede)?;
+
+        let mut resposta = Vec::new();
+        conexao
+            .take(MAX_RESPOSTA + 1)
+            .read_to_end(&mut resposta)
+            .map_err(rede)?;
+        if resposta.len() as u64 > MAX_RESPOSTA {
+            return Err(erro(format!(
+                "schema_registry: GET {path}: resposta acima de {MAX_RESPOSTA} bytes"
+            )));
+        }
+        let fim = resposta
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(|| erro(format!("schema_registry: GET {path}: resposta incompleta")))?;
+        let cabecalho = String::from_utf8_lossy(&resposta[..fim]);
+        let status: u16 = cabecalho
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        if cabecalho
+            .lines()
+            .any(|l| l.to_ascii_lowercase().starts_with("transfer-encoding:"))
+        {
+            return Err(erro(format!(
+                "schema_registry: GET {path}: transfer-encoding não suportado"
+            )));
+        }
+        let corpo = &resposta[fim + 4..];
+        if status != 200 {
+            let detalhe = serde_json::from_slice::<Value>(corpo)
+                .ok()
+                .and_then(|v| v.get("message").and_then(Value::as_str).map(str::to_string))
+                .unwrap_or_default();
+            return Err(erro(format!(
+                "schema_registry: GET {path}: status {status} {detalhe}"
+            )));
+        }
+        serde_json::from_slice(corpo)
+            .map_err(|e| erro(format!("schema_registry: GET {path}: corpo inválido: {e}")))
+    }
+}
+
+// Escapa o subject para o segmento do path
+fn codificar(segmento: &str) -> String {
+    let mut out = String::new();
+    for b in segmento.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+// JSON Schema -> EventSchema, recusando o que ele não representa
+fn compilar(schema: &Value) -> Result<EventSchema, String> {
+    let Value::Object(schema) = schema else {
+        return Err("schema deve ser um objeto".into());
+    };
+    if let Some(tipo) = schema.get("type")
+        && tipo != "object"
+    {
+        return Err(format!("tipo de topo {tipo} não suportado, só object"));
+    }
+    let vazio = Map::new();
+    let propriedades = match schema.get("properties") {
+        Some(Value::Object(p)) => p,
+        Some(_) => return Err("properties deve ser um objeto".into()),
+        None => &vazio,
+    };
+    let obrigatorios: Vec<&str> = match schema.get("required") {
+        Some(Value::Array(r)) => r.iter().filter_map(Value::as_str).collect(),
+        Some(_) => return Err("required deve ser uma lista".into()),
+        None => Vec::new(),
+    };
+    if let Some(nome) = obrigatorios
+        .iter()
+        .find(|n| !propriedades.contains_key(**n))
+    {
+        return Err(format!("campo obrigatório {nome} sem tipo em properties"));
+    }
+
+    let mut out = EventSchema::new().allow_additional(!matches!(
+        schema.get("additionalProperties"),
+        Some(Value::Bool(false))
+    ));
+    for (nome, propriedade) in propriedades {
+        let tipo = tipo_do_campo(propriedade).ok_or_else(|| {
+            format!("campo {nome}: tipo não suportado (use um único type do JSON Schema)")
+        })?;
+        out = if obrigatorios.contains(&nome.as_str()) {
+            out.required(nome.as_str(), tipo)
+        } else {
+            out.optional(nome.as_str(), tipo)
+        };
+    }
+    Ok(out)
+}
+
+// `"type": "string"` ou `["string", "null"]`; o `EventSchema` já trata
+// null como ausente
+fn tipo_do_campo(propriedade: &Value) -> Option<FieldType> {
+    let tipo = match propriedade.get("type")? {
+        Value::Array(tipos) => {
+            let mut nao_nulos = tipos.iter().filter(|t| *t != "null");
+            let tipo = nao_nulos.next()?;
+            if nao_nulos.next().is_some() {
+                return None;
+            }
+            tipo.as_str()?
+        }
+        tipo => tipo.as_str()?,
+    };
+    Some(match tipo {
+        "string" => FieldType::String,
+        "integer" => FieldType::Integer,
+        "number" => FieldType::Number,
+        "boolean" => FieldType::Boolean,
+        "object" => FieldType::Object,
+        "array" => FieldType::Array,
+        _ => return None,
+    })
+}
@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::sync::RwLock;
+use std::thread::LocalKey;
+
+// Estado por thread trocado durante uma closure e restaurado no fim, mesmo
+// em panic; e políticas com um padrão do processo que uma invocação pode
+// sobrepor na thread atual (non_finite, int128).
+
+// Volta o valor anterior da thread se `trocar` não chegar ao fim
+struct Restaurar<T: 'static> {
+    chave: &'static LocalKey<RefCell<T>>,
+    anterior: Option<T>,
+}
+
+impl<T> Drop for Restaurar<T> {
+    fn drop(&mut self) {
+        if let Some(anterior) = self.anterior.take() {
+            self.chave.with(|c| c.replace(anterior));
+        }
+    }
+}
+
+// Executa `f` com `valor` em `chave`; devolve o resultado e o valor que
+// estava em `chave` no fim
+pub(crate) fn trocar<T, R>(
+    chave: &'static LocalKey<RefCell<T>>,
+    valor: T,
+    f: impl FnOnce() -> R,
+) -> (R, T) {
+    let mut guarda = Restaurar {
+        chave,
+        anterior: Some(chave.with(|c| c.replace(valor))),
+    };
+    let resultado = f();
+    let anterior = guarda.anterior.take().expect("restaurado só aqui");
+    (resultado, chave.with(|c| c.replace(anterior)))
+}
+
+// Padrão do processo, sobreposto na thread atual por `with`
+pub(crate) struct Politica<T: Copy + 'static> {
+    padrao: RwLock<Option<T>>,
+    atual: &'static LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: Copy + Default + 'static> Politica<T> {
+    pub(crate) const fn new(atual: &'static LocalKey<RefCell<Option<T>>>) -> Self {
+        Politica {
+            padrao: RwLock::new(None),
+            atual,
+        }
+    }
+
+    pub(crate) fn set(&self, valor: T) {
+        *self.padrao.write().unwrap_or_else(|e| e.into_inner()) = Some(valor);
+    }
+
+    // A política pedida explicitamente, na thread ou no processo
+    pub(crate) fn explicita(&self) -> Option<T> {
+        self.atual
+            .with(|c| *c.borrow())
+            .or_else(|| *self.padrao.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub(crate) fn get(&self) -> T {
+        self.explicita().unwrap_or_default()
+    }
+
+    pub(crate) fn with<R>(&self, valor: T, f: impl FnOnce() -> R) -> R {
+        trocar(self.atual, Some(valor), f).0
+    }
+}
@@ -0,0 +1,38 @@
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+// `with!(expr, caminho::da::funcao)`: formata um valor com uma função no
+// formato do `#[serde(serialize_with = "...")]`, sem newtype. A função
+// recebe o serializador de `serde_json::Value`; o erro dela vira o erro do
+// campo, com o caminho.
+
+// with!(pedido.criado_em, formatos::data_br) -> "01/05/2024"
+#[macro_export]
+macro_rules! with {
+    ($value:expr, $serialize:path) => {
+        $crate::serialize_with::With::new($serialize(
+            &$value,
+            $crate::__private::serde_json::value::Serializer,
+        ))
+    };
+}
+
+/// Resultado de uma função `serialize_with`, pronto para o `json_safe!`.
+#[derive(Debug)]
+pub struct With(Result<Value, serde_json::Error>);
+
+impl With {
+    pub fn new(result: Result<Value, serde_json::Error>) -> Self {
+        With(result)
+    }
+}
+
+impl Serialize for With {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            Ok(value) => value.serialize(serializer),
+            Err(e) => Err(S::Error::custom(format!("with!: {e}"))),
+        }
+    }
+}
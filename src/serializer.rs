@@ -0,0 +1,491 @@
+use serde::ser::{self, Serialize, Serializer};
+use serde_json::Value;
+use std::cell::RefCell;
+
+use crate::JsonSafeError;
+use crate::error::PathSegment;
+use crate::int128::{self, Overflow};
+use crate::non_finite::{self, NonFinite};
+use crate::numeric::{self, NumericError};
+
+// Serializer que envolve o do serde_json em todos os valores de
+// `json_safe!`: aplica as políticas de floats não finitos (NaN, ±inf) e de
+// i128/u128 e o modo numérico estrito em qualquer profundidade e, se pedido
+// (feature "path-to-error" ou modo estrito), acompanha campos, índices,
+// chaves de mapas e variantes, para que uma falha dentro de uma struct
+// profunda diga onde (`itens[1].meta`).
+
+// Caminho atual e, depois de uma falha, o caminho onde ela aconteceu
+struct Trilha {
+    rastrear: bool,
+    nao_finito: NonFinite,
+    // `None`: nenhuma política explícita (com arbitrary-precision, número exato)
+    int128: Option<Overflow>,
+    estrito: bool,
+    // Conversão numérica recusada, para o erro tipado
+    numerico: RefCell<Option<NumericError>>,
+    caminho: RefCell<Vec<PathSegment>>,
+    falha: RefCell<Option<Vec<PathSegment>>>,
+}
+
+impl Trilha {
+    // Guarda o caminho do erro mais interno (o primeiro a ser visto)
+    fn falhou<T, E>(&self, resultado: Result<T, E>) -> Result<T, E> {
+        if resultado.is_err() {
+            let mut falha = self.falha.borrow_mut();
+            if falha.is_none() {
+                *falha = Some(self.caminho.borrow().clone());
+            }
+        }
+        resultado
+    }
+
+    fn dentro<T, E>(
+        &self,
+        segmentos: impl FnOnce() -> Vec<PathSegment>,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        if !self.rastrear {
+            return self.falhou(f());
+        }
+        let antes = self.caminho.borrow().len();
+        self.caminho.borrow_mut().extend(segmentos());
+        let resultado = self.falhou(f());
+        self.caminho.borrow_mut().truncate(antes);
+        resultado
+    }
+}
+
+// `serde_json::to_value` com as políticas e o modo estrito da thread; com
+// `rastrear` (ou no modo estrito), o erro leva o caminho serde da falha
+pub(crate) fn to_value<T: ?Sized + Serialize>(
+    value: &T,
+    rastrear: bool,
+) -> Result<Value, JsonSafeError> {
+    let estrito = numeric::is_strict();
+    let trilha = Trilha {
+        rastrear: rastrear || estrito,
+        nao_finito: non_finite::policy(),
+        int128: int128::explicit_policy(),
+        estrito,
+        numerico: RefCell::default(),
+        caminho: RefCell::default(),
+        falha: RefCell::default(),
+    };
+    serde_json::to_value(Envolto {
+        valor: value,
+        trilha: &trilha,
+    })
+    .map_err(|e| {
+        let caminho = trilha.falha.into_inner().unwrap_or_default();
+        crate::error::com_caminho(caminho, e, trilha.numerico.into_inner())
+    })
+}
+
+struct Envolto<'a, T: ?Sized> {
+    valor: &'a T,
+    trilha: &'a Trilha,
+}
+
+impl<T: ?Sized + Serialize> Serialize for Envolto<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.valor.serialize(Rastreador {
+            inner: serializer,
+            trilha: self.trilha,
+        })
+    }
+}
+
+fn nome_da_chave<T: ?Sized + Serialize>(chave: &T) -> String {
+    match serde_json::to_value(chave) {
+        Ok(Value::String(s)) => s,
+        Ok(outro) => outro.to_string(),
+        Err(_) => "?".into(),
+    }
+}
+
+struct Rastreador<'a, S> {
+    inner: S,
+    trilha: &'a Trilha,
+}
+
+impl<S: Serializer> Rastreador<'_, S> {
+    fn recusar(self, problema: NumericError) -> Result<S::Ok, S::Error> {
+        let erro = <S::Error as ser::Error>::custom(&problema);
+        self.trilha.numerico.replace(Some(problema));
+        self.trilha.falhou(Err(erro))
+    }
+
+    fn fora_da_faixa(self, texto: String) -> Result<S::Ok, S::Error> {
+        if self.trilha.estrito || self.trilha.int128.unwrap_or_default() == Overflow::Error {
+            return self.recusar(NumericError::OutOfRange(texto));
+        }
+        self.trilha.falhou(self.inner.serialize_str(&texto))
+    }
+
+    fn nao_finito(self, v: f64) -> Result<S::Ok, S::Error> {
+        if self.trilha.estrito {
+            return self.recusar(NumericError::NonFinite(v));
+        }
+        let resultado = match self.trilha.nao_finito {
+            NonFinite::Error => Err(<S::Error as ser::Error>::custom(format!(
+                "número não finito: {}",
+                non_finite::nome(v)
+            ))),
+            NonFinite::CoerceToNull => self.inner.serialize_unit(),
+            NonFinite::StringifyAsNaN => self.inner.serialize_str(non_finite::nome(v)),
+        };
+        self.trilha.falhou(resultado)
+    }
+}
+
+macro_rules! primitivos {
+    ($($metodo:ident($tipo:ty)),* $(,)?) => {
+        $(
+            fn $metodo(self, v: $tipo) -> Result<S::Ok, S::Error> {
+                self.trilha.falhou(self.inner.$metodo(v))
+            }
+        )*
+    };
+}
+
+impl<'a, S: Serializer> Serializer for Rastreador<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Composto<'a, S::SerializeSeq>;
+    type SerializeTuple = Composto<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = Composto<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Composto<'a, S::SerializeTupleVariant>;
+    type SerializeMap = Composto<'a, S::SerializeMap>;
+    type SerializeStruct = Composto<'a, S::SerializeStruct>;
+    type SerializeStructVariant = Composto<'a, S::SerializeStructVariant>;
+
+    primitivos!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_i128(self, v: i128) -> Result<S::Ok, S::Error> {
+        if let Ok(v) = i64::try_from(v) {
+            return self.trilha.falhou(self.inner.serialize_i64(v));
+        }
+        if let Ok(v) = u64::try_from(v) {
+            return self.trilha.falhou(self.inner.serialize_u64(v));
+        }
+        #[cfg(feature = "arbitrary-precision")]
+        if !self.trilha.estrito && self.trilha.int128.is_none() {
+            return self.trilha.falhou(self.inner.serialize_i128(v));
+        }
+        self.fora_da_faixa(v.to_string())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<S::Ok, S::Error> {
+        if let Ok(v) = u64::try_from(v) {
+            return self.trilha.falhou(self.inner.serialize_u64(v));
+        }
+        #[cfg(feature = "arbitrary-precision")]
+        if !self.trilha.estrito && self.trilha.int128.is_none() {
+            return self.trilha.falhou(self.inner.serialize_u128(v));
+        }
+        self.fora_da_faixa(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        if self.trilha.estrito
+            && let Some(problema) = numeric::conferir_f32(v)
+        {
+            return self.recusar(problema);
+        }
+        if v.is_finite() {
+            return self.trilha.falhou(self.inner.serialize_f32(v));
+        }
+        self.nao_finito(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        if v.is_finite() {
+            return self.trilha.falhou(self.inner.serialize_f64(v));
+        }
+        self.nao_finito(v)
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.trilha.falhou(self.inner.serialize_none())
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.trilha.falhou(self.inner.serialize_unit())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.trilha
+            .falhou(self.inner.serialize_unit_variant(name, index, variant))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+        let trilha = self.trilha;
+        trilha.falhou(self.inner.serialize_some(&Envolto {
+            valor: value,
+            trilha,
+        }))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let trilha = self.trilha;
+        trilha.falhou(self.inner.serialize_newtype_struct(
+            name,
+            &Envolto {
+                valor: value,
+                trilha,
+            },
+        ))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let (inner, trilha) = (self.inner, self.trilha);
+        trilha.dentro(
+            || vec![PathSegment::Key(variant.into())],
+            || {
+                inner.serialize_newtype_variant(
+                    name,
+                    index,
+                    variant,
+                    &Envolto {
+                        valor: value,
+                        trilha,
+                    },
+                )
+            },
+        )
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let c = self.trilha.falhou(self.inner.serialize_seq(len))?;
+        Ok(Composto::novo(c, self.trilha, None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let c = self.trilha.falhou(self.inner.serialize_tuple(len))?;
+        Ok(Composto::novo(c, self.trilha, None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let c = self
+            .trilha
+            .falhou(self.inner.serialize_tuple_struct(name, len))?;
+        Ok(Composto::novo(c, self.trilha, None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let c = self.trilha.falhou(
+            self.inner
+                .serialize_tuple_variant(name, index, variant, len),
+        )?;
+        Ok(Composto::novo(c, self.trilha, Some(variant)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let c = self.trilha.falhou(self.inner.serialize_map(len))?;
+        Ok(Composto::novo(c, self.trilha, None))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let c = self.trilha.falhou(self.inner.serialize_struct(name, len))?;
+        Ok(Composto::novo(c, self.trilha, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let c = self.trilha.falhou(
+            self.inner
+                .serialize_struct_variant(name, index, variant, len),
+        )?;
+        Ok(Composto::novo(c, self.trilha, Some(variant)))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct Composto<'a, C> {
+    inner: C,
+    trilha: &'a Trilha,
+    // Variante de enum que prefixa o caminho dos membros
+    variante: Option<&'static str>,
+    indice: usize,
+    // Última chave de mapa, para o valor que vem a seguir
+    chave: Option<String>,
+}
+
+impl<'a, C> Composto<'a, C> {
+    fn novo(inner: C, trilha: &'a Trilha, variante: Option<&'static str>) -> Self {
+        Composto {
+            inner,
+            trilha,
+            variante,
+            indice: 0,
+            chave: None,
+        }
+    }
+}
+
+fn segmentos(variante: Option<&'static str>, membro: PathSegment) -> Vec<PathSegment> {
+    let mut out: Vec<PathSegment> = variante
+        .map(|v| PathSegment::Key(v.into()))
+        .into_iter()
+        .collect();
+    out.push(membro);
+    out
+}
+
+// Compostos com elementos posicionais
+macro_rules! posicional {
+    ($tr:ident, $metodo:ident) => {
+        impl<C: ser::$tr> ser::$tr for Composto<'_, C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn $metodo<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+                let (variante, indice) = (self.variante, self.indice);
+                self.indice += 1;
+                let (inner, trilha) = (&mut self.inner, self.trilha);
+                trilha.dentro(
+                    || segmentos(variante, PathSegment::Index(indice)),
+                    || {
+                        inner.$metodo(&Envolto {
+                            valor: value,
+                            trilha,
+                        })
+                    },
+                )
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.trilha.falhou(self.inner.end())
+            }
+        }
+    };
+}
+
+posicional!(SerializeSeq, serialize_element);
+posicional!(SerializeTuple, serialize_element);
+posicional!(SerializeTupleStruct, serialize_field);
+posicional!(SerializeTupleVariant, serialize_field);
+
+// Compostos com campos nomeados
+macro_rules! nomeado {
+    ($tr:ident) => {
+        impl<C: ser::$tr> ser::$tr for Composto<'_, C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn serialize_field<T: ?Sized + Serialize>(
+                &mut self,
+                key: &'static str,
+                value: &T,
+            ) -> Result<(), C::Error> {
+                let variante = self.variante;
+                let (inner, trilha) = (&mut self.inner, self.trilha);
+                trilha.dentro(
+                    || segmentos(variante, PathSegment::Key(key.into())),
+                    || {
+                        inner.serialize_field(
+                            key,
+                            &Envolto {
+                                valor: value,
+                                trilha,
+                            },
+                        )
+                    },
+                )
+            }
+
+            fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+                self.trilha.falhou(self.inner.skip_field(key))
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.trilha.falhou(self.inner.end())
+            }
+        }
+    };
+}
+
+nomeado!(SerializeStruct);
+nomeado!(SerializeStructVariant);
+
+impl<C: ser::SerializeMap> ser::SerializeMap for Composto<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), C::Error> {
+        if self.trilha.rastrear {
+            self.chave = Some(nome_da_chave(key));
+        }
+        self.trilha.falhou(self.inner.serialize_key(key))
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        let chave = self.chave.take().unwrap_or_default();
+        let (inner, trilha) = (&mut self.inner, self.trilha);
+        trilha.dentro(
+            || vec![PathSegment::Key(chave)],
+            || {
+                inner.serialize_value(&Envolto {
+                    valor: value,
+                    trilha,
+                })
+            },
+        )
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.trilha.falhou(self.inner.end())
+    }
+}
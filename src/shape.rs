@@ -0,0 +1,239 @@
+use serde_json::{Map, Value};
+
+// Formas de saída declarativas: um `Transform` lista os campos do documento
+// de saída (copiados, renomeados ou extraídos por JSONPath) e é aplicado a
+// qualquer documento interno. A macro `shape!` declara a forma como dados:
+//
+//     shape! { id, nome <- name, cidade: "$.address.city", skus: ["$.items[*].sku"] }
+//
+// JSONPath suportado: `$`, `.chave`, `['chave']`, `[n]` (negativo conta do
+// fim), `[*]`, `.*` e `..chave`/`..*` (descendentes).
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+// shape! { campo, novo <- antigo, campo: "$.caminho", campo: ["$.todos[*]"] }
+// -> Result<Transform>
+#[macro_export]
+macro_rules! shape {
+    (@t $t:ident ()) => {};
+    (@t $t:ident ($to:ident <- $from:ident $(, $($rest:tt)*)?)) => {
+        $t = $t.rename(::std::stringify!($from), ::std::stringify!($to));
+        $crate::shape!(@t $t ($($($rest)*)?));
+    };
+    (@t $t:ident ($to:ident : [ $path:expr ] $(, $($rest:tt)*)?)) => {
+        $t = $t.path_all(::std::stringify!($to), $path)?;
+        $crate::shape!(@t $t ($($($rest)*)?));
+    };
+    (@t $t:ident ($to:ident : $path:expr $(, $($rest:tt)*)?)) => {
+        $t = $t.path(::std::stringify!($to), $path)?;
+        $crate::shape!(@t $t ($($($rest)*)?));
+    };
+    (@t $t:ident ($name:ident $(, $($rest:tt)*)?)) => {
+        $t = $t.pick(::std::stringify!($name));
+        $crate::shape!(@t $t ($($($rest)*)?));
+    };
+    (@t $t:ident ($($bad:tt)*)) => {
+        ::std::compile_error!(::std::concat!("shape!: campo inválido: ", ::std::stringify!($($bad)*)));
+    };
+    ($($body:tt)*) => {
+        (|| -> $crate::__private::serde_json::Result<$crate::shape::Transform> {
+            #[allow(unused_mut)]
+            let mut t = $crate::shape::Transform::new();
+            $crate::shape!(@t t ($($body)*));
+            ::std::result::Result::Ok(t)
+        })()
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Passo {
+    Chave(String),
+    Indice(i64),
+    Todos,
+    // `..chave` ou `..*`
+    Descendentes(Option<String>),
+}
+
+/// Expressão JSONPath já validada.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    passos: Vec<Passo>,
+}
+
+fn nome(texto: &str) -> (&str, &str) {
+    let fim = texto.find(['.', '[']).unwrap_or(texto.len());
+    texto.split_at(fim)
+}
+
+impl JsonPath {
+    pub fn parse(expr: &str) -> serde_json::Result<Self> {
+        let invalido = |motivo: &str| erro(format!("JSONPath {expr:?}: {motivo}"));
+        let mut resto = expr
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| invalido("deve começar com `$`"))?;
+        let mut passos = Vec::new();
+        while !resto.is_empty() {
+            if let Some(depois) = resto.strip_prefix("..") {
+                let (n, r) = nome(depois);
+                if n.is_empty() {
+                    return Err(invalido("esperado nome ou `*` depois de `..`"));
+                }
+                passos.push(Passo::Descendentes((n != "*").then(|| n.to_string())));
+                resto = r;
+            } else if let Some(depois) = resto.strip_prefix('.') {
+                let (n, r) = nome(depois);
+                passos.push(match n {
+                    "" => return Err(invalido("esperado nome depois de `.`")),
+                    "*" => Passo::Todos,
+                    _ => Passo::Chave(n.to_string()),
+                });
+                resto = r;
+            } else if let Some(depois) = resto.strip_prefix('[') {
+                let fim = depois.find(']').ok_or_else(|| invalido("`[` sem `]`"))?;
+                let dentro = depois[..fim].trim();
+                let citado = |q: char| dentro.strip_prefix(q).and_then(|d| d.strip_suffix(q));
+                passos.push(if dentro == "*" {
+                    Passo::Todos
+                } else if let Some(chave) = citado('\'').or_else(|| citado('"')) {
+                    Passo::Chave(chave.to_string())
+                } else {
+                    Passo::Indice(
+                        dentro
+                            .parse()
+                            .map_err(|_| invalido(&format!("índice inválido: {dentro:?}")))?,
+                    )
+                });
+                resto = &depois[fim + 1..];
+            } else {
+                return Err(invalido(&format!("token inesperado em {resto:?}")));
+            }
+        }
+        Ok(JsonPath { passos })
+    }
+
+    /// Todos os valores que casam, na ordem do documento.
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let mut atuais = vec![value];
+        for passo in &self.passos {
+            let mut proximos = Vec::new();
+            for v in atuais {
+                match passo {
+                    Passo::Chave(k) => proximos.extend(v.get(k)),
+                    Passo::Indice(i) => {
+                        if let Value::Array(itens) = v {
+                            let i = if *i < 0 { itens.len() as i64 + i } else { *i };
+                            proximos.extend(usize::try_from(i).ok().and_then(|i| itens.get(i)));
+                        }
+                    }
+                    Passo::Todos => filhos(v, &mut proximos),
+                    Passo::Descendentes(chave) => {
+                        let mut nos = Vec::new();
+                        descendentes(v, &mut nos);
+                        for no in nos {
+                            match chave {
+                                Some(k) => proximos.extend(no.get(k)),
+                                None => filhos(no, &mut proximos),
+                            }
+                        }
+                    }
+                }
+            }
+            atuais = proximos;
+        }
+        atuais
+    }
+}
+
+fn filhos<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(map) => out.extend(map.values()),
+        Value::Array(itens) => out.extend(itens),
+        _ => {}
+    }
+}
+
+// O próprio nó e todos abaixo dele, em pré-ordem
+fn descendentes<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    let mut diretos = Vec::new();
+    filhos(value, &mut diretos);
+    for filho in diretos {
+        descendentes(filho, out);
+    }
+}
+
+/// Valores de `value` que casam com `expr`.
+pub fn select<'a>(value: &'a Value, expr: &str) -> serde_json::Result<Vec<&'a Value>> {
+    Ok(JsonPath::parse(expr)?.select(value))
+}
+
+#[derive(Debug, Clone)]
+enum Fonte {
+    // Primeiro valor que casar
+    Um(JsonPath),
+    // Todos os valores, em um array
+    Todos(JsonPath),
+}
+
+/// Forma de saída reutilizável.
+#[derive(Debug, Clone, Default)]
+pub struct Transform {
+    campos: Vec<(String, Fonte)>,
+}
+
+impl Transform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copia o campo `name` da raiz.
+    pub fn pick(self, name: &str) -> Self {
+        self.rename(name, name)
+    }
+
+    /// Copia o campo `from` da raiz como `to`.
+    pub fn rename(mut self, from: &str, to: impl Into<String>) -> Self {
+        let caminho = JsonPath {
+            passos: vec![Passo::Chave(from.to_string())],
+        };
+        self.campos.push((to.into(), Fonte::Um(caminho)));
+        self
+    }
+
+    /// `to` recebe o primeiro valor que casar com `expr`.
+    pub fn path(mut self, to: impl Into<String>, expr: &str) -> serde_json::Result<Self> {
+        self.campos
+            .push((to.into(), Fonte::Um(JsonPath::parse(expr)?)));
+        Ok(self)
+    }
+
+    /// `to` recebe um array com todos os valores que casarem com `expr`.
+    pub fn path_all(mut self, to: impl Into<String>, expr: &str) -> serde_json::Result<Self> {
+        self.campos
+            .push((to.into(), Fonte::Todos(JsonPath::parse(expr)?)));
+        Ok(self)
+    }
+
+    /// Documento na forma declarada. Campos sem correspondência são
+    /// omitidos; `path_all` sem correspondência vira `[]`.
+    pub fn apply(&self, value: &Value) -> Value {
+        let mut out = Map::new();
+        for (nome, fonte) in &self.campos {
+            match fonte {
+                Fonte::Um(caminho) => {
+                    if let Some(v) = caminho.select(value).first() {
+                        out.insert(nome.clone(), (*v).clone());
+                    }
+                }
+                Fonte::Todos(caminho) => {
+                    let todos = caminho.select(value).into_iter().cloned().collect();
+                    out.insert(nome.clone(), Value::Array(todos));
+                }
+            }
+        }
+        Value::Object(out)
+    }
+}
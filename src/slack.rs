@@ -0,0 +1,226 @@
+use serde_json::{Map, Value};
+
+// Builders de mensagens Slack Block Kit com validação dos limites de
+// tamanho documentados pela API.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+fn limitar(onde: &str, texto: &str, max: usize) -> serde_json::Result<()> {
+    let n = texto.chars().count();
+    if n == 0 {
+        return Err(erro(format!("{onde}: texto vazio")));
+    }
+    if n > max {
+        return Err(erro(format!("{onde}: {n} caracteres, máximo é {max}")));
+    }
+    Ok(())
+}
+
+fn texto(tipo: &str, conteudo: &str) -> Value {
+    let mut map = Map::new();
+    map.insert("type".into(), Value::from(tipo));
+    map.insert("text".into(), Value::from(conteudo));
+    Value::Object(map)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonStyle {
+    Primary,
+    Danger,
+}
+
+#[derive(Debug, Clone)]
+pub struct Button {
+    text: String,
+    action_id: String,
+    url: Option<String>,
+    value: Option<String>,
+    style: Option<ButtonStyle>,
+}
+
+impl Button {
+    pub fn new(text: impl Into<String>, action_id: impl Into<String>) -> Self {
+        Button {
+            text: text.into(),
+            action_id: action_id.into(),
+            url: None,
+            value: None,
+            style: None,
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn style(mut self, style: ButtonStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    fn to_value(&self, onde: &str) -> serde_json::Result<Value> {
+        limitar(&format!("{onde}.text"), &self.text, 75)?;
+        limitar(&format!("{onde}.action_id"), &self.action_id, 255)?;
+
+        let mut map = Map::new();
+        map.insert("type".into(), Value::from("button"));
+        map.insert("text".into(), texto("plain_text", &self.text));
+        map.insert("action_id".into(), Value::from(self.action_id.as_str()));
+        if let Some(url) = &self.url {
+            limitar(&format!("{onde}.url"), url, 3000)?;
+            map.insert("url".into(), Value::from(url.as_str()));
+        }
+        if let Some(value) = &self.value {
+            limitar(&format!("{onde}.value"), value, 2000)?;
+            map.insert("value".into(), Value::from(value.as_str()));
+        }
+        if let Some(style) = self.style {
+            let nome = match style {
+                ButtonStyle::Primary => "primary",
+                ButtonStyle::Danger => "danger",
+            };
+            map.insert("style".into(), Value::from(nome));
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    text: String,
+    fields: Vec<String>,
+    accessory: Option<Button>,
+}
+
+impl Section {
+    /// Seção com texto em `mrkdwn`.
+    pub fn new(text: impl Into<String>) -> Self {
+        Section {
+            text: text.into(),
+            fields: Vec::new(),
+            accessory: None,
+        }
+    }
+
+    pub fn field(mut self, text: impl Into<String>) -> Self {
+        self.fields.push(text.into());
+        self
+    }
+
+    pub fn accessory(mut self, button: Button) -> Self {
+        self.accessory = Some(button);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Block {
+    Header(String),
+    Section(Section),
+    Divider,
+    Actions(Vec<Button>),
+}
+
+impl Block {
+    fn to_value(&self, onde: &str) -> serde_json::Result<Value> {
+        let mut map = Map::new();
+        match self {
+            Block::Header(titulo) => {
+                limitar(&format!("{onde}.text"), titulo, 150)?;
+                map.insert("type".into(), Value::from("header"));
+                map.insert("text".into(), texto("plain_text", titulo));
+            }
+            Block::Section(secao) => {
+                limitar(&format!("{onde}.text"), &secao.text, 3000)?;
+                map.insert("type".into(), Value::from("section"));
+                map.insert("text".into(), texto("mrkdwn", &secao.text));
+                if secao.fields.len() > 10 {
+                    return Err(erro(format!("{onde}.fields: máximo de 10 campos")));
+                }
+                if !secao.fields.is_empty() {
+                    let mut campos = Vec::new();
+                    for (i, campo) in secao.fields.iter().enumerate() {
+                        limitar(&format!("{onde}.fields[{i}]"), campo, 2000)?;
+                        campos.push(texto("mrkdwn", campo));
+                    }
+                    map.insert("fields".into(), Value::Array(campos));
+                }
+                if let Some(botao) = &secao.accessory {
+                    map.insert(
+                        "accessory".into(),
+                        botao.to_value(&format!("{onde}.accessory"))?,
+                    );
+                }
+            }
+            Block::Divider => {
+                map.insert("type".into(), Value::from("divider"));
+            }
+            Block::Actions(botoes) => {
+                if botoes.is_empty() || botoes.len() > 25 {
+                    return Err(erro(format!("{onde}.elements: entre 1 e 25 botões")));
+                }
+                let elementos = botoes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| b.to_value(&format!("{onde}.elements[{i}]")))
+                    .collect::<serde_json::Result<Vec<_>>>()?;
+                map.insert("type".into(), Value::from("actions"));
+                map.insert("elements".into(), Value::Array(elementos));
+            }
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// Mensagem (`chat.postMessage` ou webhook) com texto de fallback e blocos.
+#[derive(Debug, Clone)]
+pub struct Message {
+    text: String,
+    blocks: Vec<Block>,
+}
+
+impl Message {
+    /// `text` é o fallback exibido em notificações.
+    pub fn new(text: impl Into<String>) -> Self {
+        Message {
+            text: text.into(),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn block(mut self, block: Block) -> Self {
+        self.blocks.push(block);
+        self
+    }
+
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        limitar("text", &self.text, 40_000)?;
+        if self.blocks.len() > 50 {
+            return Err(erro(format!(
+                "blocks: {} blocos, máximo é 50",
+                self.blocks.len()
+            )));
+        }
+        let blocos = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(i, b)| b.to_value(&format!("blocks[{i}]")))
+            .collect::<serde_json::Result<Vec<_>>>()?;
+
+        let mut map = Map::new();
+        map.insert("text".into(), Value::from(self.text.as_str()));
+        if !blocos.is_empty() {
+            map.insert("blocks".into(), Value::Array(blocos));
+        }
+        Ok(Value::Object(map))
+    }
+}
@@ -0,0 +1,525 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::io::Read;
+
+use crate::event_schema::{EventSchema, FieldType};
+
+// Validação em streaming para gateways na frente de JSON não confiável: o
+// tokenizador lê o `Read` aos poucos e confere limites (tamanho,
+// profundidade, strings, números, membros) e, opcionalmente, um
+// `EventSchema`, rejeitando a entrada assim que o problema aparece, sem
+// nunca montar o documento. Os erros dizem linha, coluna e byte.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+/// Limites da entrada. Padrões: 10 MiB, profundidade 64, strings de 1 MiB,
+/// números de 100 caracteres e 100 000 membros por objeto/array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub(crate) max_bytes: usize,
+    pub(crate) max_depth: usize,
+    pub(crate) max_string_len: usize,
+    pub(crate) max_number_len: usize,
+    pub(crate) max_entries: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_bytes: 10 << 20,
+            max_depth: 64,
+            max_string_len: 1 << 20,
+            max_number_len: 100,
+            max_entries: 100_000,
+        }
+    }
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tamanho máximo da entrada, em bytes.
+    pub fn max_bytes(mut self, n: usize) -> Self {
+        self.max_bytes = n;
+        self
+    }
+
+    /// Aninhamento máximo de objetos/arrays.
+    pub fn max_depth(mut self, n: usize) -> Self {
+        self.max_depth = n;
+        self
+    }
+
+    /// Tamanho máximo de strings e chaves (em bytes, depois dos escapes).
+    pub fn max_string_len(mut self, n: usize) -> Self {
+        self.max_string_len = n;
+        self
+    }
+
+    /// Quantidade máxima de caracteres de um número.
+    pub fn max_number_len(mut self, n: usize) -> Self {
+        self.max_number_len = n;
+        self
+    }
+
+    /// Membros máximos de um objeto ou itens de um array.
+    pub fn max_entries(mut self, n: usize) -> Self {
+        self.max_entries = n;
+        self
+    }
+}
+
+/// Linha e coluna (a partir de 1) e byte (a partir de 0) na entrada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Posicao {
+    pub(crate) linha: usize,
+    pub(crate) coluna: usize,
+    pub(crate) byte: usize,
+}
+
+impl Posicao {
+    pub(crate) fn erro(&self, msg: impl std::fmt::Display) -> serde_json::Error {
+        erro(format!(
+            "{msg} na linha {}, coluna {} (byte {})",
+            self.linha, self.coluna, self.byte
+        ))
+    }
+}
+
+// Leitura byte a byte com buffer próprio e contagem de posição
+struct Fonte<R> {
+    inner: R,
+    buf: Vec<u8>,
+    ini: usize,
+    fim: usize,
+    pos: Posicao,
+    max_bytes: usize,
+}
+
+impl<R: Read> Fonte<R> {
+    fn espiar(&mut self) -> serde_json::Result<Option<u8>> {
+        if self.ini == self.fim {
+            self.fim = loop {
+                match self.inner.read(&mut self.buf) {
+                    Ok(n) => break n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(serde_json::Error::io(e)),
+                }
+            };
+            self.ini = 0;
+            if self.fim == 0 {
+                return Ok(None);
+            }
+        }
+        if self.pos.byte >= self.max_bytes {
+            let msg = format!("entrada excede {} bytes", self.max_bytes);
+            return Err(self.pos.erro(msg));
+        }
+        Ok(Some(self.buf[self.ini]))
+    }
+
+    fn avancar(&mut self) {
+        let b = self.buf[self.ini];
+        self.ini += 1;
+        self.pos.byte += 1;
+        if b == b'\n' {
+            self.pos.linha += 1;
+            self.pos.coluna = 1;
+        } else {
+            self.pos.coluna += 1;
+        }
+    }
+
+    fn proximo(&mut self) -> serde_json::Result<Option<u8>> {
+        let b = self.espiar()?;
+        if b.is_some() {
+            self.avancar();
+        }
+        Ok(b)
+    }
+
+    fn pular_espacos(&mut self) -> serde_json::Result<Option<u8>> {
+        loop {
+            match self.espiar()? {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.avancar(),
+                outro => return Ok(outro),
+            }
+        }
+    }
+}
+
+/// Um passo do tokenizador.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Evento {
+    InicioObjeto,
+    FimObjeto,
+    InicioArray,
+    FimArray,
+    Chave(String),
+    Escalar(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Espera {
+    Valor,
+    // Logo depois de `[`
+    ValorOuFim,
+    Chave,
+    // Logo depois de `{`
+    ChaveOuFim,
+    DoisPontos,
+    Separador,
+    Fim,
+}
+
+struct Quadro {
+    objeto: bool,
+    membros: usize,
+}
+
+/// Tokenizador incremental com os limites aplicados a cada token.
+pub(crate) struct Parser<R> {
+    fonte: Fonte<R>,
+    limits: Limits,
+    pilha: Vec<Quadro>,
+    espera: Espera,
+    // Onde começou o último evento
+    inicio: Posicao,
+    // Texto original do último número
+    lexema: String,
+}
+
+impl<R: Read> Parser<R> {
+    pub(crate) fn new(reader: R, limits: &Limits) -> Self {
+        Parser {
+            fonte: Fonte {
+                inner: reader,
+                buf: vec![0; 8192],
+                ini: 0,
+                fim: 0,
+                pos: Posicao {
+                    linha: 1,
+                    coluna: 1,
+                    byte: 0,
+                },
+                max_bytes: limits.max_bytes,
+            },
+            limits: *limits,
+            pilha: Vec::new(),
+            espera: Espera::Valor,
+            inicio: Posicao {
+                linha: 1,
+                coluna: 1,
+                byte: 0,
+            },
+            lexema: String::new(),
+        }
+    }
+
+    /// Texto original do último número lido (`1.10`, `1e2`).
+    pub(crate) fn lexema(&self) -> &str {
+        &self.lexema
+    }
+
+    /// Posição em que começou o último evento devolvido.
+    pub(crate) fn inicio(&self) -> Posicao {
+        self.inicio
+    }
+
+    /// Profundidade atual (objetos/arrays abertos).
+    pub(crate) fn profundidade(&self) -> usize {
+        self.pilha.len()
+    }
+
+    fn apos_valor(&self) -> Espera {
+        if self.pilha.is_empty() {
+            Espera::Fim
+        } else {
+            Espera::Separador
+        }
+    }
+
+    fn membro(&mut self) -> serde_json::Result<()> {
+        let max = self.limits.max_entries;
+        if let Some(quadro) = self.pilha.last_mut() {
+            quadro.membros += 1;
+            if quadro.membros > max {
+                let msg = format!("mais de {max} membros");
+                return Err(self.inicio.erro(msg));
+            }
+        }
+        Ok(())
+    }
+
+    fn abrir(&mut self, objeto: bool) -> serde_json::Result<Evento> {
+        self.fonte.avancar();
+        self.pilha.push(Quadro { objeto, membros: 0 });
+        if self.pilha.len() > self.limits.max_depth {
+            let msg = format!("profundidade máxima ({}) excedida", self.limits.max_depth);
+            return Err(self.inicio.erro(msg));
+        }
+        if objeto {
+            self.espera = Espera::ChaveOuFim;
+            Ok(Evento::InicioObjeto)
+        } else {
+            self.espera = Espera::ValorOuFim;
+            Ok(Evento::InicioArray)
+        }
+    }
+
+    fn fechar(&mut self) -> Evento {
+        self.fonte.avancar();
+        let quadro = self.pilha.pop().expect("há um container aberto");
+        self.espera = self.apos_valor();
+        if quadro.objeto {
+            Evento::FimObjeto
+        } else {
+            Evento::FimArray
+        }
+    }
+
+    fn inesperado(&self, b: Option<u8>, esperado: &str) -> serde_json::Error {
+        let msg = match b {
+            Some(b) => format!("esperado {esperado}, encontrado {:?}", b as char),
+            None => format!("fim inesperado da entrada (esperado {esperado})"),
+        };
+        self.fonte.pos.erro(msg)
+    }
+
+    /// Próximo evento; `None` no fim de um documento completo.
+    pub(crate) fn proximo(&mut self) -> serde_json::Result<Option<Evento>> {
+        loop {
+            let b = self.fonte.pular_espacos()?;
+            self.inicio = self.fonte.pos;
+            match self.espera {
+                Espera::Fim => {
+                    return match b {
+                        None => Ok(None),
+                        Some(_) => Err(self.fonte.pos.erro("dados após o fim do documento")),
+                    };
+                }
+                Espera::ValorOuFim if b == Some(b']') => return Ok(Some(self.fechar())),
+                Espera::Valor | Espera::ValorOuFim => {
+                    if self.pilha.last().is_some_and(|q| !q.objeto) {
+                        self.membro()?;
+                    }
+                    return self.valor(b).map(Some);
+                }
+                Espera::ChaveOuFim if b == Some(b'}') => return Ok(Some(self.fechar())),
+                Espera::Chave | Espera::ChaveOuFim => {
+                    if b != Some(b'"') {
+                        return Err(self.inesperado(b, "chave entre aspas"));
+                    }
+                    self.membro()?;
+                    let chave = self.string()?;
+                    self.espera = Espera::DoisPontos;
+                    return Ok(Some(Evento::Chave(chave)));
+                }
+                Espera::DoisPontos => {
+                    if b != Some(b':') {
+                        return Err(self.inesperado(b, "':'"));
+                    }
+                    self.fonte.avancar();
+                    self.espera = Espera::Valor;
+                }
+                Espera::Separador => {
+                    let objeto = self.pilha.last().is_some_and(|q| q.objeto);
+                    match (b, objeto) {
+                        (Some(b','), true) => {
+                            self.fonte.avancar();
+                            self.espera = Espera::Chave;
+                        }
+                        (Some(b','), false) => {
+                            self.fonte.avancar();
+                            self.espera = Espera::Valor;
+                        }
+                        (Some(b'}'), true) | (Some(b']'), false) => {
+                            return Ok(Some(self.fechar()));
+                        }
+                        (b, true) => return Err(self.inesperado(b, "',' ou '}'")),
+                        (b, false) => return Err(self.inesperado(b, "',' ou ']'")),
+                    }
+                }
+            }
+        }
+    }
+
+    fn valor(&mut self, b: Option<u8>) -> serde_json::Result<Evento> {
+        let valor = match b {
+            Some(b'{') => return self.abrir(true),
+            Some(b'[') => return self.abrir(false),
+            Some(b'"') => Value::String(self.string()?),
+            Some(b'-' | b'0'..=b'9') => self.numero()?,
+            Some(b't') => self.literal("true", Value::Bool(true))?,
+            Some(b'f') => self.literal("false", Value::Bool(false))?,
+            Some(b'n') => self.literal("null", Value::Null)?,
+            b => return Err(self.inesperado(b, "um valor")),
+        };
+        self.espera = self.apos_valor();
+        Ok(Evento::Escalar(valor))
+    }
+
+    fn literal(&mut self, texto: &str, valor: Value) -> serde_json::Result<Value> {
+        for esperado in texto.bytes() {
+            if self.fonte.proximo()? != Some(esperado) {
+                return Err(self
+                    .inicio
+                    .erro(format!("literal inválido (esperado {texto})")));
+            }
+        }
+        Ok(valor)
+    }
+
+    fn numero(&mut self) -> serde_json::Result<Value> {
+        let mut texto = String::new();
+        while let Some(b @ (b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) =
+            self.fonte.espiar()?
+        {
+            if texto.len() == self.limits.max_number_len {
+                let msg = format!(
+                    "número com mais de {} caracteres",
+                    self.limits.max_number_len
+                );
+                return Err(self.inicio.erro(msg));
+            }
+            texto.push(b as char);
+            self.fonte.avancar();
+        }
+        match serde_json::from_str::<Value>(&texto) {
+            Ok(n @ Value::Number(_)) => {
+                self.lexema = texto;
+                Ok(n)
+            }
+            _ => Err(self.inicio.erro(format!("número inválido {texto:?}"))),
+        }
+    }
+
+    fn string(&mut self) -> serde_json::Result<String> {
+        self.fonte.avancar();
+        let mut bytes = Vec::new();
+        loop {
+            if bytes.len() > self.limits.max_string_len {
+                let msg = format!("string com mais de {} bytes", self.limits.max_string_len);
+                return Err(self.inicio.erro(msg));
+            }
+            let pos = self.fonte.pos;
+            match self.fonte.proximo()? {
+                None => return Err(self.inesperado(None, "'\"'")),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let c = match self.fonte.proximo()? {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode(pos)?,
+                        _ => return Err(pos.erro("escape inválido")),
+                    };
+                    let mut utf8 = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                }
+                Some(b) if b < 0x20 => return Err(pos.erro("caractere de controle em string")),
+                Some(b) => bytes.push(b),
+            }
+        }
+        if bytes.len() > self.limits.max_string_len {
+            let msg = format!("string com mais de {} bytes", self.limits.max_string_len);
+            return Err(self.inicio.erro(msg));
+        }
+        String::from_utf8(bytes).map_err(|_| self.inicio.erro("UTF-8 inválido em string"))
+    }
+
+    fn hex4(&mut self, pos: Posicao) -> serde_json::Result<u32> {
+        let mut n = 0;
+        for _ in 0..4 {
+            let digito = self
+                .fonte
+                .proximo()?
+                .and_then(|b| (b as char).to_digit(16))
+                .ok_or_else(|| pos.erro("escape \\u inválido"))?;
+            n = n * 16 + digito;
+        }
+        Ok(n)
+    }
+
+    fn unicode(&mut self, pos: Posicao) -> serde_json::Result<char> {
+        let alto = self.hex4(pos)?;
+        let codigo = if (0xD800..0xDC00).contains(&alto) {
+            if self.fonte.proximo()? != Some(b'\\') || self.fonte.proximo()? != Some(b'u') {
+                return Err(pos.erro("surrogate sem par"));
+            }
+            let baixo = self.hex4(pos)?;
+            if !(0xDC00..0xE000).contains(&baixo) {
+                return Err(pos.erro("surrogate sem par"));
+            }
+            0x10000 + ((alto - 0xD800) << 10) + (baixo - 0xDC00)
+        } else {
+            alto
+        };
+        char::from_u32(codigo).ok_or_else(|| pos.erro("surrogate sem par"))
+    }
+}
+
+fn aceita(tipo: FieldType, evento: &Evento) -> bool {
+    match evento {
+        Evento::InicioObjeto => tipo == FieldType::Object,
+        Evento::InicioArray => tipo == FieldType::Array,
+        Evento::Escalar(v) => tipo.aceita(v),
+        _ => false,
+    }
+}
+
+/// Confere `reader` contra `limits` e, se houver, `schema` (campos de topo),
+/// sem montar o documento. O primeiro problema encerra a leitura.
+pub fn validate_stream<R: Read>(
+    reader: R,
+    limits: &Limits,
+    schema: Option<&EventSchema>,
+) -> serde_json::Result<()> {
+    let mut parser = Parser::new(reader, limits);
+    let mut presentes = BTreeSet::new();
+    let mut primeiro = true;
+    // Chave de topo cujo valor é o próximo evento
+    let mut chave: Option<String> = None;
+    while let Some(evento) = parser.proximo()? {
+        let Some(schema) = schema else { continue };
+        let inicio = parser.inicio();
+        if std::mem::take(&mut primeiro) && evento != Evento::InicioObjeto {
+            return Err(inicio.erro("evento deve ser um objeto"));
+        }
+        if let Some(nome) = chave.take() {
+            match schema.fields.get(&nome) {
+                None if !schema.allow_additional => {
+                    return Err(inicio.erro(format!("campo não declarado: {nome}")));
+                }
+                None => {}
+                Some(_) if evento == Evento::Escalar(Value::Null) => {}
+                Some((tipo, _)) if !aceita(*tipo, &evento) => {
+                    let msg = format!("campo {nome} deve ser {}", tipo.nome());
+                    return Err(inicio.erro(msg));
+                }
+                Some(_) => {
+                    presentes.insert(nome);
+                }
+            }
+        } else if let (Evento::Chave(k), 1) = (&evento, parser.profundidade()) {
+            chave = Some(k.clone());
+        }
+    }
+    if let Some(schema) = schema {
+        for (nome, (_, obrigatorio)) in &schema.fields {
+            if *obrigatorio && !presentes.contains(nome) {
+                return Err(erro(format!("campo obrigatório ausente: {nome}")));
+            }
+        }
+    }
+    Ok(())
+}
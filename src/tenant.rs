@@ -0,0 +1,102 @@
+use serde_json::Value;
+use std::cell::RefCell;
+use std::sync::RwLock;
+
+use crate::trace_context;
+
+// Isolamento multi-tenant dos payloads: o tenant do contexto atual (por
+// thread, definido com `enter`) é injetado em cada documento montado com
+// `json_safe_tenant!`, e `validate` barra na saída documentos sem tenant ou
+// de outro tenant, evitando vazamento de dados entre clientes.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+// json_safe_tenant!({ ... }) -> json_safe! com o tenant atual injetado
+#[macro_export]
+macro_rules! json_safe_tenant {
+    ($($body:tt)+) => {
+        $crate::json_safe!($($body)+)
+            .and_then(|doc| ::std::result::Result::Ok($crate::tenant::inject(doc)?))
+    };
+}
+
+static CAMINHO: RwLock<Option<String>> = RwLock::new(None);
+
+thread_local! {
+    static ATUAL: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Caminho (JSON Pointer) do tenant nos documentos; padrão `/tenant_id`.
+pub fn set_tenant_path(pointer: impl Into<String>) {
+    *CAMINHO.write().unwrap_or_else(|e| e.into_inner()) = Some(pointer.into());
+}
+
+fn caminho() -> String {
+    CAMINHO
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| "/tenant_id".into())
+}
+
+/// Tenant ativo enquanto o guard existir; ao sair, volta o anterior.
+#[derive(Debug)]
+#[must_use = "o tenant só vale enquanto o guard existir"]
+pub struct TenantGuard {
+    anterior: Option<String>,
+}
+
+impl Drop for TenantGuard {
+    fn drop(&mut self) {
+        ATUAL.with(|atual| *atual.borrow_mut() = self.anterior.take());
+    }
+}
+
+/// Define o tenant da thread atual até o guard ser descartado.
+pub fn enter(tenant: impl Into<String>) -> TenantGuard {
+    let anterior = ATUAL.with(|atual| atual.borrow_mut().replace(tenant.into()));
+    TenantGuard { anterior }
+}
+
+/// Tenant da thread atual.
+pub fn current() -> Option<String> {
+    ATUAL.with(|atual| atual.borrow().clone())
+}
+
+fn exigir_atual() -> serde_json::Result<String> {
+    current().ok_or_else(|| erro("tenant: nenhum tenant ativo".into()))
+}
+
+/// Grava o tenant atual no caminho configurado. Erro se não houver tenant
+/// ativo ou se o documento já tiver outro tenant.
+pub fn inject(mut doc: Value) -> serde_json::Result<Value> {
+    let tenant = exigir_atual()?;
+    let caminho = caminho();
+    match doc.pointer(&caminho) {
+        None | Some(Value::Null) => {
+            trace_context::gravar(&mut doc, &caminho, Value::from(tenant))?;
+        }
+        Some(Value::String(t)) if *t == tenant => {}
+        Some(outro) => {
+            return Err(erro(format!(
+                "tenant: {caminho} = {outro} difere do tenant atual {tenant:?}"
+            )));
+        }
+    }
+    Ok(doc)
+}
+
+/// Confere que `doc` pertence ao tenant atual.
+pub fn validate(doc: &Value) -> serde_json::Result<()> {
+    let tenant = exigir_atual()?;
+    let caminho = caminho();
+    match doc.pointer(&caminho) {
+        Some(Value::String(t)) if *t == tenant => Ok(()),
+        None | Some(Value::Null) => Err(erro(format!("tenant: {caminho} ausente"))),
+        Some(outro) => Err(erro(format!(
+            "tenant: {caminho} = {outro} difere do tenant atual {tenant:?}"
+        ))),
+    }
+}
@@ -0,0 +1,206 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::{canonical, digest};
+
+// Tokenização de PII preservando formato: o valor em cada JSON Pointer é
+// trocado por um token determinístico com a mesma "cara" (dígitos viram
+// dígitos, letras — acentuadas inclusive — viram letras ASCII da mesma
+// caixa, pontuação é mantida), de modo que joins e contagens em analytics
+// continuam funcionando sobre o documento redigido.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::ser::Error>::custom(msg)
+}
+
+/// Gera o token de um valor. Deve ser determinístico.
+pub trait Tokenizer {
+    fn tokenize(&self, value: &Value) -> Result<Value, String>;
+}
+
+/// Guarda tokens emitidos para consulta reversa.
+pub trait TokenVault {
+    /// Erro se `token` já pertence a outro original: tokens que preservam
+    /// formato têm poucas combinações (10^n dígitos, 26^n letras) e colidem.
+    fn store(&mut self, token: &Value, original: &Value) -> Result<(), String>;
+    fn lookup(&self, token: &Value) -> Option<Value>;
+}
+
+/// Tokenizador padrão, baseado em HMAC-SHA256 com chave secreta. Aceita
+/// strings e inteiros; inteiros continuam inteiros com o mesmo número de
+/// dígitos e sinal.
+#[derive(Clone)]
+pub struct HmacTokenizer {
+    key: Vec<u8>,
+}
+
+impl HmacTokenizer {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        HmacTokenizer { key: key.into() }
+    }
+
+    // Fluxo pseudoaleatório derivado de HMAC(key, contador || valor canônico)
+    fn fluxo(&self, value: &Value, n: usize) -> Vec<u8> {
+        let mensagem = canonical::to_canonical_string(value);
+        let mut out = Vec::with_capacity(n);
+        let mut contador: u32 = 0;
+        while out.len() < n {
+            let mut entrada = contador.to_be_bytes().to_vec();
+            entrada.extend_from_slice(mensagem.as_bytes());
+            out.extend_from_slice(&digest::hmac_sha256(&self.key, &entrada));
+            contador += 1;
+        }
+        out.truncate(n);
+        out
+    }
+
+    fn substituir(&self, value: &Value, texto: &str) -> String {
+        let fluxo = self.fluxo(value, texto.chars().count());
+        texto
+            .chars()
+            .zip(fluxo)
+            // Fora do ASCII também: "Ñúñez" não pode sair com "ú" e "ñ"
+            .map(|(c, b)| match c {
+                c if c.is_numeric() => (b'0' + b % 10) as char,
+                c if c.is_uppercase() => (b'A' + b % 26) as char,
+                c if c.is_alphabetic() => (b'a' + b % 26) as char,
+                outro => outro,
+            })
+            .collect()
+    }
+}
+
+impl Tokenizer for HmacTokenizer {
+    fn tokenize(&self, value: &Value) -> Result<Value, String> {
+        match value {
+            Value::String(s) => Ok(Value::String(self.substituir(value, s))),
+            Value::Number(n) if n.is_i64() || n.is_u64() => {
+                let texto = n.to_string();
+                let (sinal, digitos) = match texto.strip_prefix('-') {
+                    Some(resto) => ("-", resto),
+                    None => ("", texto.as_str()),
+                };
+                let mut token: Vec<u8> = self.substituir(value, digitos).into_bytes();
+                // Sem zero à esquerda, para manter a quantidade de dígitos
+                if token.len() > 1 && token[0] == b'0' {
+                    token[0] = b'1' + self.fluxo(value, 1)[0] % 9;
+                }
+                let token = String::from_utf8(token).unwrap_or_default();
+                let inteiro = |texto: String| {
+                    serde_json::from_str::<Value>(&texto)
+                        .ok()
+                        .filter(|v| v.is_i64() || v.is_u64())
+                };
+                inteiro(format!("{sinal}{token}"))
+                    // Estourou o tipo inteiro: força o primeiro dígito para 1
+                    .or_else(|| inteiro(format!("{sinal}1{}", &token[1..])))
+                    // Ainda acima de u64::MAX (20 dígitos começando em 19):
+                    // reduz para a faixa de 20 dígitos que cabe
+                    .or_else(|| {
+                        let piso = 10u128.pow(token.len() as u32 - 1);
+                        let teto = if sinal.is_empty() {
+                            u128::from(u64::MAX)
+                        } else {
+                            u128::from(i64::MIN.unsigned_abs())
+                        };
+                        let n: u128 = token.parse().ok()?;
+                        inteiro(format!("{sinal}{}", piso + (n - piso) % (teto - piso + 1)))
+                    })
+                    .ok_or_else(|| "token numérico inválido".to_string())
+            }
+            _ => Err("somente strings e inteiros são tokenizáveis".into()),
+        }
+    }
+}
+
+/// Cofre em memória, indexado pela forma canônica do token.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryVault {
+    tokens: BTreeMap<String, Value>,
+}
+
+impl MemoryVault {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+impl TokenVault for MemoryVault {
+    fn store(&mut self, token: &Value, original: &Value) -> Result<(), String> {
+        let chave = canonical::to_canonical_string(token);
+        match self.tokens.get(&chave) {
+            Some(anterior) if anterior != original => Err(format!(
+                "colisão: o token {chave} já pertence a outro valor"
+            )),
+            Some(_) => Ok(()),
+            None => {
+                self.tokens.insert(chave, original.clone());
+                Ok(())
+            }
+        }
+    }
+
+    fn lookup(&self, token: &Value) -> Option<Value> {
+        self.tokens
+            .get(&canonical::to_canonical_string(token))
+            .cloned()
+    }
+}
+
+/// Troca os valores em `pointers` por tokens. Caminhos ausentes e `null`
+/// são ignorados. Com `vault`, cada par token/original é registrado; uma
+/// colisão de tokens é erro.
+pub fn tokenize_paths(
+    doc: &mut Value,
+    pointers: &[&str],
+    tokenizer: &dyn Tokenizer,
+    mut vault: Option<&mut dyn TokenVault>,
+) -> serde_json::Result<()> {
+    for &ponteiro in pointers {
+        let Some(alvo) = doc.pointer_mut(ponteiro) else {
+            continue;
+        };
+        if alvo.is_null() {
+            continue;
+        }
+        let token = tokenizer
+            .tokenize(alvo)
+            .map_err(|e| erro(format!("{ponteiro}: {e}")))?;
+        if let Some(vault) = vault.as_deref_mut() {
+            vault
+                .store(&token, alvo)
+                .map_err(|e| erro(format!("{ponteiro}: {e}")))?;
+        }
+        *alvo = token;
+    }
+    Ok(())
+}
+
+/// Inverso de [`tokenize_paths`] via consulta ao cofre. Um token
+/// desconhecido é erro.
+pub fn detokenize_paths(
+    doc: &mut Value,
+    pointers: &[&str],
+    vault: &dyn TokenVault,
+) -> serde_json::Result<()> {
+    for &ponteiro in pointers {
+        let Some(alvo) = doc.pointer_mut(ponteiro) else {
+            continue;
+        };
+        if alvo.is_null() {
+            continue;
+        }
+        *alvo = vault
+            .lookup(alvo)
+            .ok_or_else(|| erro(format!("{ponteiro}: token desconhecido")))?;
+    }
+    Ok(())
+}
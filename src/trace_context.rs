@@ -0,0 +1,195 @@
+use serde_json::{Map, Value};
+use std::fmt;
+
+use crate::clock;
+use crate::digest::sha256;
+
+// Propagação de contexto de trace W3C (`traceparent`/`tracestate`) dentro de
+// documentos, para que o trace distribuído sobreviva a filas: o produtor
+// injeta o contexto em caminhos configurados e o consumidor o extrai.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+fn hex_valido(texto: &str, tamanho: usize) -> bool {
+    texto.len() == tamanho
+        && texto
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        && texto.bytes().any(|b| b != b'0')
+}
+
+// Novo id hexadecimal a partir do gerador de ids configurado em `clock`.
+// O id passa pelo SHA-256: com `SequentialIds` só os bytes finais mudam, e
+// um prefixo direto do UUID daria o mesmo span id a cada chamada
+fn novo_hex(tamanho: usize) -> String {
+    let mut hex = String::new();
+    let mut bloco = 0u32;
+    while hex.len() < tamanho {
+        let id = format!("{}/{bloco}", clock::new_id());
+        hex.extend(sha256(id.as_bytes()).iter().map(|b| format!("{b:02x}")));
+        bloco += 1;
+    }
+    hex.truncate(tamanho);
+    hex
+}
+
+/// Contexto de trace: `traceparent` decomposto e `tracestate` opaco.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: String,
+    pub parent_id: String,
+    pub flags: u8,
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Início de um trace novo, amostrado.
+    pub fn new_root() -> Self {
+        TraceContext {
+            trace_id: novo_hex(32),
+            parent_id: novo_hex(16),
+            flags: 1,
+            tracestate: None,
+        }
+    }
+
+    /// Lê os cabeçalhos W3C. Versões futuras são aceitas se o início seguir
+    /// o formato da versão 00; `ff` e ids zerados são inválidos.
+    pub fn from_headers(traceparent: &str, tracestate: Option<&str>) -> serde_json::Result<Self> {
+        let invalido = || erro(format!("traceparent inválido: {traceparent:?}"));
+        let partes: Vec<&str> = traceparent.trim().split('-').collect();
+        let [versao, trace_id, parent_id, flags, resto @ ..] = partes.as_slice() else {
+            return Err(invalido());
+        };
+        let versao_ok =
+            versao.len() == 2 && versao.bytes().all(|b| b.is_ascii_hexdigit()) && *versao != "ff";
+        if !versao_ok
+            || (*versao == "00" && !resto.is_empty())
+            || !hex_valido(trace_id, 32)
+            || !hex_valido(parent_id, 16)
+            || flags.len() != 2
+        {
+            return Err(invalido());
+        }
+        let flags = u8::from_str_radix(flags, 16).map_err(|_| invalido())?;
+        Ok(TraceContext {
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            flags,
+            tracestate: tracestate
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    /// Cabeçalho `traceparent` (versão 00).
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{:02x}", self.trace_id, self.parent_id, self.flags)
+    }
+
+    pub fn sampled(&self) -> bool {
+        self.flags & 1 == 1
+    }
+
+    /// Mesmo trace, com um novo span como pai (o próximo salto).
+    pub fn child(&self) -> Self {
+        TraceContext {
+            parent_id: novo_hex(16),
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.traceparent())
+    }
+}
+
+/// Onde o contexto fica nos documentos (JSON Pointer).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracePaths {
+    traceparent: String,
+    tracestate: String,
+}
+
+impl Default for TracePaths {
+    fn default() -> Self {
+        TracePaths {
+            traceparent: "/trace/traceparent".into(),
+            tracestate: "/trace/tracestate".into(),
+        }
+    }
+}
+
+impl TracePaths {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn traceparent(mut self, pointer: impl Into<String>) -> Self {
+        self.traceparent = pointer.into();
+        self
+    }
+
+    pub fn tracestate(mut self, pointer: impl Into<String>) -> Self {
+        self.tracestate = pointer.into();
+        self
+    }
+}
+
+// Grava em `pointer`, criando objetos intermediários
+pub(crate) fn gravar(doc: &mut Value, pointer: &str, valor: Value) -> serde_json::Result<()> {
+    let mut atual = doc;
+    let segmentos: Vec<String> = pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let Some((ultimo, caminho)) = segmentos.split_last() else {
+        return Err(erro("caminho não pode ser a raiz".into()));
+    };
+    for seg in caminho {
+        if atual.is_null() {
+            *atual = Value::Object(Map::new());
+        }
+        atual = atual
+            .as_object_mut()
+            .ok_or_else(|| erro(format!("{pointer}: `{seg}` não está em um objeto")))?
+            .entry(seg.clone())
+            .or_insert(Value::Null);
+    }
+    if atual.is_null() {
+        *atual = Value::Object(Map::new());
+    }
+    atual
+        .as_object_mut()
+        .ok_or_else(|| erro(format!("{pointer}: destino não está em um objeto")))?
+        .insert(ultimo.clone(), valor);
+    Ok(())
+}
+
+/// Injeta o contexto em `doc`; `tracestate` só é gravado se existir.
+pub fn inject(doc: &mut Value, ctx: &TraceContext, paths: &TracePaths) -> serde_json::Result<()> {
+    gravar(doc, &paths.traceparent, Value::from(ctx.traceparent()))?;
+    if let Some(estado) = &ctx.tracestate {
+        gravar(doc, &paths.tracestate, Value::from(estado.clone()))?;
+    }
+    Ok(())
+}
+
+/// Extrai o contexto de `doc`: `None` se não houver `traceparent`, erro se
+/// ele existir e for inválido.
+pub fn extract(doc: &Value, paths: &TracePaths) -> serde_json::Result<Option<TraceContext>> {
+    let Some(pai) = doc.pointer(&paths.traceparent) else {
+        return Ok(None);
+    };
+    let pai = pai
+        .as_str()
+        .ok_or_else(|| erro(format!("{}: traceparent não é string", paths.traceparent)))?;
+    let estado = doc.pointer(&paths.tracestate).and_then(Value::as_str);
+    TraceContext::from_headers(pai, estado).map(Some)
+}
@@ -0,0 +1,164 @@
+use serde_json::{Map, Value};
+use std::io::Read;
+
+use crate::duplicate_keys::DuplicateKey;
+use crate::stream::{Evento, Limits, Parser};
+
+// Parse endurecido para entradas vindas da internet: o tokenizador de
+// `stream` com limites apertados (tamanho, profundidade, strings, números,
+// membros) e chaves duplicadas recusadas, com linha, coluna e byte no erro.
+// `UntrustedProfile::default()` é o padrão seguro; cada limite pode ser
+// ajustado.
+
+/// Limites e regras de `parse_untrusted`. Padrões: 1 MiB, profundidade 32,
+/// strings de 64 KiB, números de 40 caracteres, 10 000 membros por
+/// objeto/array e chaves duplicadas recusadas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UntrustedProfile {
+    limits: Limits,
+    reject_duplicate_keys: bool,
+}
+
+impl Default for UntrustedProfile {
+    fn default() -> Self {
+        UntrustedProfile {
+            limits: Limits::new()
+                .max_bytes(1 << 20)
+                .max_depth(32)
+                .max_string_len(64 << 10)
+                .max_number_len(40)
+                .max_entries(10_000),
+            reject_duplicate_keys: true,
+        }
+    }
+}
+
+impl UntrustedProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Com `false`, a última ocorrência vence, como no serde_json.
+    pub fn reject_duplicate_keys(mut self, reject: bool) -> Self {
+        self.reject_duplicate_keys = reject;
+        self
+    }
+}
+
+/// Parse de `bytes` não confiáveis sob `profile`.
+pub fn parse_untrusted(bytes: &[u8], profile: &UntrustedProfile) -> serde_json::Result<Value> {
+    let parser = Parser::new(bytes, &profile.limits);
+    montar(parser, |duplicada| {
+        if profile.reject_duplicate_keys {
+            return Err(duplicada.erro());
+        }
+        Ok(())
+    })
+}
+
+enum Aberto {
+    Objeto(Map<String, Value>, Option<String>),
+    Array(Vec<Value>),
+}
+
+fn escapar(segmento: &str) -> String {
+    segmento.replace('~', "~0").replace('/', "~1")
+}
+
+// JSON Pointer da posição em que o próximo valor de cada aberto vai entrar
+fn ponteiro(pilha: &[Aberto]) -> String {
+    let mut out = String::new();
+    for aberto in pilha {
+        match aberto {
+            Aberto::Objeto(_, Some(k)) => out.push_str(&format!("/{}", escapar(k))),
+            Aberto::Array(itens) => out.push_str(&format!("/{}", itens.len())),
+            Aberto::Objeto(_, None) => {}
+        }
+    }
+    out
+}
+
+// Monta o documento a partir dos eventos; `duplicada` decide o que fazer
+// com uma chave repetida (a última ocorrência vence se ela aceitar)
+pub(crate) fn montar<R: Read>(
+    parser: Parser<R>,
+    duplicada: impl FnMut(&DuplicateKey) -> serde_json::Result<()>,
+) -> serde_json::Result<Value> {
+    montar_com(parser, duplicada, None)
+}
+
+// Recebe o ponteiro e o texto original de cada número
+pub(crate) type Numero<'a> = &'a mut dyn FnMut(String, &str);
+
+// `montar`, avisando `numero` do ponteiro e do texto original de cada número
+pub(crate) fn montar_com<R: Read>(
+    mut parser: Parser<R>,
+    mut duplicada: impl FnMut(&DuplicateKey) -> serde_json::Result<()>,
+    mut numero: Option<Numero<'_>>,
+) -> serde_json::Result<Value> {
+    let mut pilha: Vec<Aberto> = Vec::new();
+    let mut raiz = None;
+    while let Some(evento) = parser.proximo()? {
+        let valor = match evento {
+            Evento::InicioObjeto => {
+                pilha.push(Aberto::Objeto(Map::new(), None));
+                continue;
+            }
+            Evento::InicioArray => {
+                pilha.push(Aberto::Array(Vec::new()));
+                continue;
+            }
+            Evento::Chave(chave) => {
+                if let Some(Aberto::Objeto(map, _)) = pilha.last()
+                    && map.contains_key(&chave)
+                {
+                    let pos = parser.inicio();
+                    duplicada(&DuplicateKey {
+                        key: chave.clone(),
+                        pointer: format!(
+                            "{}/{}",
+                            ponteiro(&pilha[..pilha.len() - 1]),
+                            escapar(&chave)
+                        ),
+                        line: pos.linha,
+                        column: pos.coluna,
+                        offset: pos.byte,
+                    })?;
+                }
+                if let Some(Aberto::Objeto(_, pendente)) = pilha.last_mut() {
+                    *pendente = Some(chave);
+                }
+                continue;
+            }
+            Evento::Escalar(v) => {
+                if let Some(numero) = numero.as_mut()
+                    && v.is_number()
+                {
+                    numero(ponteiro(&pilha), parser.lexema());
+                }
+                v
+            }
+            Evento::FimObjeto | Evento::FimArray => match pilha.pop() {
+                Some(Aberto::Objeto(map, _)) => Value::Object(map),
+                Some(Aberto::Array(itens)) => Value::Array(itens),
+                None => unreachable!("o parser fecha só o que abriu"),
+            },
+        };
+        match pilha.last_mut() {
+            Some(Aberto::Objeto(map, pendente)) => {
+                let chave = pendente
+                    .take()
+                    .expect("o parser exige a chave antes do valor");
+                map.insert(chave, valor);
+            }
+            Some(Aberto::Array(itens)) => itens.push(valor),
+            None => raiz = Some(valor),
+        }
+    }
+    Ok(raiz.expect("o parser só termina depois de um documento completo"))
+}
@@ -0,0 +1,94 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// Envelopes versionados `{ schema_version, payload }` e um despachante que
+// migra payloads antigos, versão a versão, até a versão atual do leitor.
+
+fn erro(msg: String) -> serde_json::Error {
+    <serde_json::Error as serde::de::Error>::custom(msg)
+}
+
+// versioned!(2, { ... }) ou versioned!(2, expr)
+#[macro_export]
+macro_rules! versioned {
+    ($version:expr, $payload:tt) => {
+        $crate::json_safe!($payload).map(|payload| $crate::versioned::wrap($version, payload))
+    };
+}
+
+/// Monta o envelope.
+pub fn wrap(version: u32, payload: Value) -> Value {
+    let mut map = Map::new();
+    map.insert("schema_version".into(), Value::from(version));
+    map.insert("payload".into(), payload);
+    Value::Object(map)
+}
+
+/// Separa versão e payload de um envelope.
+pub fn unwrap(envelope: &Value) -> serde_json::Result<(u32, &Value)> {
+    let versao = envelope["schema_version"]
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| erro("envelope sem schema_version inteiro".into()))?;
+    let payload = envelope
+        .get("payload")
+        .ok_or_else(|| erro("envelope sem payload".into()))?;
+    Ok((versao, payload))
+}
+
+type Migracao = Box<dyn Fn(Value) -> serde_json::Result<Value> + Send + Sync>;
+
+/// Leitor de envelopes de um tipo de evento, na versão `current`.
+pub struct Dispatcher {
+    current: u32,
+    // versão de origem -> migração para a versão seguinte
+    migrations: BTreeMap<u32, Migracao>,
+}
+
+impl Dispatcher {
+    pub fn new(current: u32) -> Self {
+        Dispatcher {
+            current,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Registra a migração de `from` para `from + 1`.
+    pub fn migration<F>(mut self, from: u32, migrate: F) -> Self
+    where
+        F: Fn(Value) -> serde_json::Result<Value> + Send + Sync + 'static,
+    {
+        self.migrations.insert(from, Box::new(migrate));
+        self
+    }
+
+    /// Payload do envelope migrado até a versão atual. Versões mais novas
+    /// que a do leitor são erro.
+    pub fn parse(&self, envelope: &Value) -> serde_json::Result<Value> {
+        let (mut versao, payload) = unwrap(envelope)?;
+        if versao > self.current {
+            return Err(erro(format!(
+                "schema_version {versao} é mais nova que a suportada ({})",
+                self.current
+            )));
+        }
+        let mut payload = payload.clone();
+        while versao < self.current {
+            let migrar = self.migrations.get(&versao).ok_or_else(|| {
+                erro(format!(
+                    "sem migração de schema_version {versao} para {}",
+                    versao + 1
+                ))
+            })?;
+            payload = migrar(payload)?;
+            versao += 1;
+        }
+        Ok(payload)
+    }
+
+    /// [`parse`](Self::parse) seguido de desserialização.
+    pub fn parse_as<T: DeserializeOwned>(&self, envelope: &Value) -> serde_json::Result<T> {
+        serde_json::from_value(self.parse(envelope)?)
+    }
+}
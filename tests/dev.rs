@@ -0,0 +1,240 @@
+#![cfg(feature = "dev")]
+
+use json_safe::cassette::Cassette;
+use json_safe::json_safe;
+use json_safe::mock_server::{MockServer, Response};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+// Cliente HTTP mínimo: (status, corpo JSON) ou erro de conexão
+fn requisitar(
+    addr: SocketAddr,
+    metodo: &str,
+    path: &str,
+    corpo: &str,
+) -> std::io::Result<(u16, Value)> {
+    let mut conexao = TcpStream::connect(addr)?;
+    write!(
+        conexao,
+        "{metodo} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{corpo}",
+        corpo.len()
+    )?;
+    let mut resposta = String::new();
+    conexao.read_to_string(&mut resposta)?;
+    let (cabecalho, corpo) = resposta
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| std::io::Error::other("resposta vazia"))?;
+    let status = cabecalho
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+    Ok((status, serde_json::from_str(corpo).unwrap_or(Value::Null)))
+}
+
+// Servidor de fixtures HTTP (feature "dev")
+#[test]
+fn mock_server() {
+    let usuario = json_safe!({ id: 1, name: "Ana" }).unwrap();
+    let servidor = MockServer::new()
+        .route("GET", "/users/1", Response::json(usuario.clone()))
+        .route(
+            "POST",
+            "/users",
+            Response::json(json_safe!({ error: "indisponível" }).unwrap()).status(503),
+        )
+        .route(
+            "POST",
+            "/users",
+            Response::json(json_safe!({ id: 2 }).unwrap()).status(201),
+        )
+        .route(
+            "GET",
+            "/slow",
+            Response::empty(204).latency(Duration::from_millis(50)),
+        )
+        .route("GET", "/broken", Response::disconnect())
+        .start()
+        .unwrap();
+    let addr = servidor.addr();
+    assert!(servidor.url("/users/1").starts_with("http://127.0.0.1:"));
+
+    assert_eq!(
+        requisitar(addr, "GET", "/users/1?x=1", "").unwrap(),
+        (200, usuario)
+    );
+
+    // Primeira falha, depois sucesso (a última resposta se repete)
+    let corpo = r#"{"name":"Bia"}"#;
+    assert_eq!(requisitar(addr, "POST", "/users", corpo).unwrap().0, 503);
+    assert_eq!(
+        requisitar(addr, "POST", "/users", corpo).unwrap(),
+        (201, json_safe!({ id: 2 }).unwrap())
+    );
+    assert_eq!(requisitar(addr, "POST", "/users", corpo).unwrap().0, 201);
+
+    let inicio = Instant::now();
+    assert_eq!(requisitar(addr, "GET", "/slow", "").unwrap().0, 204);
+    assert!(inicio.elapsed() >= Duration::from_millis(50));
+
+    assert!(requisitar(addr, "GET", "/broken", "").is_err());
+    let (status, corpo) = requisitar(addr, "DELETE", "/nada", "").unwrap();
+    assert_eq!(status, 404);
+    assert_eq!(corpo["path"], Value::from("/nada"));
+
+    assert_eq!(servidor.hits("POST", "/users"), 3);
+    let recebidas = servidor.requests();
+    assert_eq!(recebidas.len(), 7);
+    assert_eq!(
+        recebidas[1].body,
+        Some(json_safe!({ name: "Bia" }).unwrap())
+    );
+    assert_eq!(recebidas[0].path, "/users/1");
+
+    // Content-Length acima do limite: 413, sem alocar nem registrar
+    let mut conexao = TcpStream::connect(addr).unwrap();
+    write!(
+        conexao,
+        "POST /users HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n"
+    )
+    .unwrap();
+    let mut resposta = String::new();
+    conexao.read_to_string(&mut resposta).unwrap();
+    assert!(resposta.starts_with("HTTP/1.1 413 "), "{resposta}");
+    assert_eq!(servidor.requests().len(), 7);
+}
+
+// Cassette gravado servido pelo servidor de fixtures
+#[test]
+fn cassette_mock_server() {
+    let mut gravacao = Cassette::new();
+    gravacao.record(
+        "GET",
+        "/jobs/1",
+        None,
+        202,
+        &json_safe!({ state: "queued" }).unwrap(),
+    );
+    gravacao.record(
+        "GET",
+        "/jobs/1",
+        None,
+        200,
+        &json_safe!({ state: "done" }).unwrap(),
+    );
+    let servidor = gravacao.to_mock_server().start().unwrap();
+
+    let primeira = requisitar(servidor.addr(), "GET", "/jobs/1", "").unwrap();
+    assert_eq!(primeira, (202, json_safe!({ state: "queued" }).unwrap()));
+    assert_eq!(
+        requisitar(servidor.addr(), "GET", "/jobs/1", "").unwrap().0,
+        200
+    );
+}
+
+// Cliente de schema registry contra o servidor de fixtures
+#[cfg(feature = "schema-registry")]
+#[test]
+fn schema_registry_client() {
+    use json_safe::schema_registry::{RegistryClient, Version};
+
+    let registro = |versao: u32, schema: Value| {
+        Response::json(
+            json_safe!({
+                subject: "pedidos-value",
+                version: versao,
+                id: (versao + 100),
+                schemaType: "JSON",
+                schema: (schema.to_string()),
+            })
+            .unwrap(),
+        )
+    };
+    let v1 = json_safe!({
+        type: "object",
+        properties: { id: { type: "integer" }, cupom: { type: ["string", "null"] } },
+        required: ["id"],
+        additionalProperties: false,
+    })
+    .unwrap();
+    let v2 = json_safe!({
+        type: "object",
+        properties: { id: { type: "integer" }, total: { type: "number" } },
+        required: ["id", "total"],
+    })
+    .unwrap();
+    let servidor = MockServer::new()
+        .route(
+            "GET",
+            "/api/subjects/pedidos-value/versions/latest",
+            registro(2, v2),
+        )
+        .route(
+            "GET",
+            "/api/subjects/pedidos-value/versions/1",
+            registro(1, v1),
+        )
+        .route(
+            "GET",
+            "/api/subjects/outro/versions/latest",
+            Response::json(
+                json_safe!({ error_code: 40401, message: "Subject not found" }).unwrap(),
+            )
+            .status(404),
+        )
+        .start()
+        .unwrap();
+    let cliente = RegistryClient::new(&servidor.url("/api/")).unwrap();
+
+    // Mais recente: total obrigatório, campos extras aceitos
+    let pedido = json_safe!({ id: 7, total: 9.5, canal: "web" }).unwrap();
+    assert_eq!(
+        cliente
+            .emit_checked("pedidos-value", Version::Latest, &pedido)
+            .unwrap(),
+        serde_json::to_vec(&pedido).unwrap()
+    );
+    let erro = cliente
+        .emit_checked(
+            "pedidos-value",
+            Version::Latest,
+            &json_safe!({ id: "7" }).unwrap(),
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(erro.contains("campo id deve ser integer"), "{erro}");
+    assert!(erro.contains("campo obrigatório ausente: total"), "{erro}");
+
+    // Versão fixada: sem campos extras, cupom pode ser null
+    let fixada = Version::Pinned(1);
+    assert!(
+        cliente
+            .emit_checked(
+                "pedidos-value",
+                fixada,
+                &json_safe!({ id: 7, cupom: null }).unwrap()
+            )
+            .is_ok()
+    );
+    assert!(
+        cliente
+            .emit_checked("pedidos-value", fixada, &pedido)
+            .is_err()
+    );
+
+    // Schemas compilados ficam em cache; refresh busca a mais recente de novo
+    let path = "/api/subjects/pedidos-value/versions/latest";
+    assert_eq!(servidor.hits("GET", path), 1);
+    cliente.refresh("pedidos-value");
+    cliente.schema("pedidos-value", Version::Latest).unwrap();
+    assert_eq!(servidor.hits("GET", path), 2);
+
+    let erro = cliente
+        .schema("outro", Version::Latest)
+        .unwrap_err()
+        .to_string();
+    assert!(erro.contains("404 Subject not found"), "{erro}");
+    assert!(RegistryClient::new("https://registry").is_err());
+}
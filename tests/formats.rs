@@ -0,0 +1,1262 @@
+#[cfg(feature = "error-events")]
+use json_safe::error_event;
+use json_safe::{
+    bulk, cassette, discord, envelope, event_schema, feature, feature_collection, firestore,
+    flag_context, geojson, health, json_safe, jsonld, openmetrics, outbox, point, profile, runtime,
+    sbom, schema_org, slack, trace_context,
+};
+use serde_json::Value;
+
+// GeoJSON
+#[test]
+fn geojson() {
+    let colecao = feature_collection![
+        feature!(point!(-46.63, -23.55).unwrap(), { nome: "São Paulo" }).unwrap(),
+        feature!(point!(-43.2, -22.9, 10.0).unwrap()).unwrap(),
+    ]
+    .expect("feature_collection válida");
+
+    assert_eq!(colecao["type"], Value::from("FeatureCollection"));
+    assert_eq!(
+        colecao["features"][0]["geometry"]["type"],
+        Value::from("Point")
+    );
+    assert_eq!(
+        colecao["features"][0]["properties"]["nome"],
+        Value::from("São Paulo")
+    );
+    assert_eq!(
+        colecao["features"][1]["geometry"]["coordinates"],
+        json_safe!([-43.2, -22.9, 10.0]).unwrap()
+    );
+    assert!(colecao["features"][1]["properties"].is_null());
+    assert!(geojson::validate(&colecao).is_empty());
+
+    assert!(point!(f64::NAN, 0.0).is_err());
+    assert!(geojson::point(&[1.0]).is_err());
+    assert!(feature!(json_safe!({ type: "Nada" }).unwrap()).is_err());
+    assert!(feature!(Value::Null, [1]).is_err());
+    assert!(feature_collection![json_safe!({ type: "Point" }).unwrap()].is_err());
+
+    let poligonos = json_safe!({
+    type: "FeatureCollection",
+    bbox: [0, 10, 10, 0],
+    features: [
+        {
+            type: "Feature",
+            properties: null,
+            geometry: {
+                type: "Polygon",
+                coordinates: [
+                    [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                    [[2, 2], [4, 2], [4, 4], [2, 4], [2, 2]],
+                ],
+            },
+        },
+        {
+            type: "Feature",
+            properties: null,
+            geometry: {
+                type: "MultiPolygon",
+                coordinates: [[[[0, 0], [0, 10], [10, 10], [0, 0]]], [[[0, 0], [1, 1], [0, 0]]]],
+            },
+        },
+        {
+            type: "Feature",
+            properties: {},
+            geometry: { type: "LineString", coordinates: [[200, 0]] },
+        },
+    ],
+})
+.expect("coleção de polígonos válida");
+
+    let issues = geojson::validate(&poligonos);
+    let resumo: Vec<(&str, &str)> = issues
+        .iter()
+        .map(|i| (i.path.as_str(), i.message.as_str()))
+        .collect();
+    assert_eq!(
+        resumo,
+        vec![
+            ("/bbox", "bbox com sul maior que norte"),
+            (
+                "/features/0/geometry/coordinates/1",
+                "buraco deve estar em sentido horário"
+            ),
+            (
+                "/features/1/geometry/coordinates/0/0",
+                "anel exterior deve estar em sentido anti-horário"
+            ),
+            (
+                "/features/1/geometry/coordinates/1/0",
+                "anel linear precisa de ao menos 4 posições"
+            ),
+            (
+                "/features/2/geometry/coordinates/0",
+                "longitude fora de [-180, 180]: 200"
+            ),
+            (
+                "/features/2/geometry/coordinates",
+                "LineString precisa de ao menos 2 posições"
+            ),
+        ]
+    );
+
+    let aberto =
+        json_safe!({ type: "Polygon", coordinates: [[[0, 0], [1, 0], [1, 1], [0, 1]]] }).unwrap();
+    assert_eq!(
+        geojson::validate(&aberto)[0].message,
+        "anel linear não está fechado"
+    );
+    assert_eq!(
+        geojson::validate(&json_safe!({}).unwrap())[0].message,
+        "objeto GeoJSON sem 'type'"
+    );
+}
+
+// JSON-LD: expandir/compactar chaves pelo @context
+#[test]
+fn jsonld() {
+    let contexto = json_safe!({
+        "@vocab": "http://schema.org/",
+        "foaf": "http://xmlns.com/foaf/0.1/",
+        "nome": "schema:name",
+        "schema": "http://schema.org/",
+        "amigo": { "@id": "foaf:knows" },
+    })
+    .unwrap();
+
+    let pessoa = json_safe!({
+        "@context": (contexto.clone()),
+        "@type": "Person",
+        "nome": "Alice",
+        "email": "alice@example.com",
+        "foaf:age": 30,
+        "amigo": [{ "nome": "Bob" }],
+        "http://example.org/extra": true,
+    })
+    .unwrap();
+
+    let expandido = jsonld::expand(&pessoa).expect("expand válido");
+    assert_eq!(
+        expandido,
+        json_safe!({
+            "@type": "Person",
+            "http://schema.org/name": "Alice",
+            "http://schema.org/email": "alice@example.com",
+            "http://xmlns.com/foaf/0.1/age": 30,
+            "http://xmlns.com/foaf/0.1/knows": [{ "http://schema.org/name": "Bob" }],
+            "http://example.org/extra": true,
+        })
+        .unwrap()
+    );
+
+    let compactado = jsonld::compact(&expandido, &contexto).expect("compact válido");
+    assert_eq!(
+        compactado,
+        json_safe!({
+            "@context": (contexto.clone()),
+            "@type": "Person",
+            "nome": "Alice",
+            "email": "alice@example.com",
+            "foaf:age": 30,
+            "amigo": [{ "nome": "Bob" }],
+            "http://example.org/extra": true,
+        })
+        .unwrap()
+    );
+
+    // Contexto aninhado sobrepõe o externo
+    let aninhado = json_safe!({
+        "@context": { "x": "http://a/x" },
+        "x": { "@context": { "x": "http://b/x" }, "x": 1 },
+    })
+    .unwrap();
+    assert_eq!(
+        jsonld::expand(&aninhado).unwrap(),
+        json_safe!({ "http://a/x": { "http://b/x": 1 } }).unwrap()
+    );
+
+    assert!(jsonld::expand(&json_safe!({ "@context": "http://remoto" }).unwrap()).is_err());
+    assert!(jsonld::expand(&json_safe!({ "@context": { "a": 1 } }).unwrap()).is_err());
+}
+
+// Snippets schema.org
+#[test]
+fn schema_org() {
+    use schema_org::StructuredData;
+
+    let org = schema_org::Organization::new("Acme")
+        .url("https://acme.example")
+        .logo("https://acme.example/logo.png")
+        .same_as("https://x.com/acme");
+    assert_eq!(
+        org.to_value().unwrap(),
+        json_safe!({
+            "@context": "https://schema.org",
+            "@type": "Organization",
+            "name": "Acme",
+            "url": "https://acme.example",
+            "logo": "https://acme.example/logo.png",
+            "sameAs": ["https://x.com/acme"],
+        })
+        .unwrap()
+    );
+
+    let produto = schema_org::Product::new("Caneca </script>")
+        .sku("CAN-1")
+        .description("Caneca de cerâmica")
+        .image("https://acme.example/caneca.png")
+        .brand("Acme")
+        .offer("19.90", "BRL");
+    let produto_json = produto.to_value().unwrap();
+    assert_eq!(produto_json["brand"]["name"], Value::from("Acme"));
+    assert_eq!(produto_json["offers"]["price"], Value::from("19.90"));
+    assert_eq!(produto_json["offers"]["priceCurrency"], Value::from("BRL"));
+    assert_eq!(produto_json["sku"], Value::from("CAN-1"));
+    let script = produto.to_script().unwrap();
+    assert!(!script.contains("</script>"));
+    assert_eq!(
+        serde_json::from_str::<Value>(&script).unwrap(),
+        produto_json
+    );
+
+    assert!(
+        schema_org::Product::new("x")
+            .offer("19,90", "BRL")
+            .to_value()
+            .is_err()
+    );
+    assert!(
+        schema_org::Product::new("x")
+            .offer("19.90", "real")
+            .to_value()
+            .is_err()
+    );
+    assert!(schema_org::Organization::new(" ").to_value().is_err());
+
+    let trilha = schema_org::BreadcrumbList::new()
+        .item("Início", "https://acme.example/")
+        .item("Canecas", "https://acme.example/canecas");
+    let trilha_json = trilha.to_value().unwrap();
+    assert_eq!(
+        trilha_json["itemListElement"][1]["position"],
+        Value::from(2)
+    );
+    assert_eq!(
+        trilha_json["itemListElement"][1]["name"],
+        Value::from("Canecas")
+    );
+    assert!(schema_org::BreadcrumbList::new().to_value().is_err());
+}
+
+// Corpo da API _bulk
+#[test]
+fn bulk() {
+    let ops = vec![
+        bulk::BulkOp::index("logs", json_safe!({ msg: "linha\nquebrada" }).unwrap()).id("1"),
+        bulk::BulkOp::create("logs", json_safe!({ msg: "b" }).unwrap()),
+        bulk::BulkOp::update("logs", "1", json_safe!({ lido: true }).unwrap()),
+        bulk::BulkOp::delete("logs", "2"),
+    ];
+
+    let corpo = bulk::to_chunks(&ops, 1024).expect("bulk válido");
+    assert_eq!(
+        corpo,
+        vec![
+            "{\"index\":{\"_id\":\"1\",\"_index\":\"logs\"}}\n\
+         {\"msg\":\"linha\\nquebrada\"}\n\
+         {\"create\":{\"_index\":\"logs\"}}\n\
+         {\"msg\":\"b\"}\n\
+         {\"update\":{\"_id\":\"1\",\"_index\":\"logs\"}}\n\
+         {\"doc\":{\"lido\":true}}\n\
+         {\"delete\":{\"_id\":\"2\",\"_index\":\"logs\"}}\n"
+                .to_string()
+        ]
+    );
+
+    // Limite pequeno: cada operação em seu bloco, ação e documento juntos
+    let blocos = bulk::to_chunks(&ops, 80).unwrap();
+    assert_eq!(blocos.len(), 4);
+    assert!(blocos.iter().all(|b| b.len() <= 80 && b.ends_with('\n')));
+    assert_eq!(blocos[3].lines().count(), 1);
+
+    assert!(bulk::to_chunks(&ops, 10).is_err());
+    assert!(
+        bulk::BulkOp::index("", json_safe!({}).unwrap())
+            .to_lines()
+            .is_err()
+    );
+    assert!(
+        bulk::BulkOp::index("logs", json_safe!([1]).unwrap())
+            .to_lines()
+            .is_err()
+    );
+    assert!(bulk::to_chunks(&[], 10).unwrap().is_empty());
+}
+
+// Valores tipados do Firestore/Datastore REST
+#[test]
+fn firestore() {
+    let doc = json_safe!({
+        nome: "alice",
+        idade: 30,
+        saldo: 10.5,
+        ativo: true,
+        apelido: null,
+        tags: ["a", 1],
+        vazio: [],
+        endereco: { cidade: "Recife" },
+        extra: {},
+    })
+    .unwrap();
+
+    let fields = firestore::to_fields(&doc).expect("to_fields válido");
+    assert_eq!(
+        fields,
+        json_safe!({
+            fields: {
+                nome: { stringValue: "alice" },
+                idade: { integerValue: "30" },
+                saldo: { doubleValue: 10.5 },
+                ativo: { booleanValue: true },
+                apelido: { nullValue: null },
+                tags: { arrayValue: { values: [{ stringValue: "a" }, { integerValue: "1" }] } },
+                vazio: { arrayValue: {} },
+                endereco: { mapValue: { fields: { cidade: { stringValue: "Recife" } } } },
+                extra: { mapValue: {} },
+            },
+        })
+        .unwrap()
+    );
+    assert_eq!(firestore::from_fields(&fields).unwrap(), doc);
+
+    let especiais = json_safe!({
+        fields: {
+            quando: { timestampValue: "2024-01-01T00:00:00Z" },
+            ref_: { referenceValue: "projects/p/databases/(default)/documents/c/d" },
+            bin: { bytesValue: "AQI=" },
+            lugar: { geoPointValue: { latitude: (-8.05) } },
+            n: { integerValue: 7 },
+        },
+    })
+    .unwrap();
+    assert_eq!(
+        firestore::from_fields(&especiais).unwrap(),
+        json_safe!({
+            quando: "2024-01-01T00:00:00Z",
+            ref_: "projects/p/databases/(default)/documents/c/d",
+            bin: "AQI=",
+            lugar: { latitude: (-8.05), longitude: 0.0 },
+            n: 7,
+        })
+        .unwrap()
+    );
+
+    assert!(firestore::to_typed(&Value::from(u64::MAX)).is_err());
+    assert!(firestore::to_fields(&Value::from(1)).is_err());
+    assert!(firestore::from_typed(&json_safe!({ doubleValue: "NaN" }).unwrap()).is_err());
+    assert!(firestore::from_typed(&json_safe!({ integerValue: "x" }).unwrap()).is_err());
+    assert!(firestore::from_typed(&json_safe!({ a: 1, b: 2 }).unwrap()).is_err());
+    assert!(firestore::from_fields(&json_safe!([]).unwrap()).is_err());
+    assert_eq!(
+        firestore::from_fields(&json_safe!({}).unwrap()).unwrap(),
+        json_safe!({}).unwrap()
+    );
+}
+
+// Envelopes EventBridge / SNS / SQS / Event Grid
+#[test]
+fn envelopes() {
+    let detalhe = json_safe!({ pedido: 42, status: "pago" }).unwrap();
+
+    let entrada = envelope::EventBridge::new("loja.pedidos", "PedidoPago")
+        .event_bus("vendas")
+        .resource("arn:aws:loja:pedido/42")
+        .wrap(detalhe.clone())
+        .expect("entrada EventBridge válida");
+    assert_eq!(entrada["Source"], Value::from("loja.pedidos"));
+    assert_eq!(entrada["EventBusName"], Value::from("vendas"));
+    assert_eq!(
+        entrada["Resources"][0],
+        Value::from("arn:aws:loja:pedido/42")
+    );
+    assert_eq!(
+        serde_json::from_str::<Value>(entrada["Detail"].as_str().unwrap()).unwrap(),
+        detalhe
+    );
+    assert!(
+        envelope::EventBridge::new("", "X")
+            .wrap(detalhe.clone())
+            .is_err()
+    );
+    assert!(
+        envelope::EventBridge::new("a", "X")
+            .wrap(json_safe!([1]).unwrap())
+            .is_err()
+    );
+    assert!(
+        envelope::EventBridge::new("a", "X")
+            .wrap(json_safe!({ grande: ("x".repeat(300 * 1024)) }).unwrap())
+            .is_err()
+    );
+
+    let atributos = envelope::MessageAttributes::new()
+        .string("tenant", "acme")
+        .number("tentativa", 2);
+    let publish = envelope::sns_publish("arn:aws:sns:topico", &detalhe, &atributos).unwrap();
+    assert_eq!(
+        publish["MessageAttributes"],
+        json_safe!({
+            tenant: { DataType: "String", StringValue: "acme" },
+            tentativa: { DataType: "Number", StringValue: "2" },
+        })
+        .unwrap()
+    );
+    let send = envelope::sqs_send("https://sqs/fila", &detalhe, &atributos).unwrap();
+    assert_eq!(
+        send["MessageBody"],
+        Value::from("{\"pedido\":42,\"status\":\"pago\"}")
+    );
+    assert!(envelope::sqs_send("", &detalhe, &atributos).is_err());
+
+    let invalidos = [
+        envelope::MessageAttributes::new().string("AWS.trace", "x"),
+        envelope::MessageAttributes::new().string("com espaço", "x"),
+        envelope::MessageAttributes::new()
+            .string("a", "x")
+            .string("a", "y"),
+        envelope::MessageAttributes::new().number("n", "abc"),
+        (0..11).fold(envelope::MessageAttributes::new(), |a, i| {
+            a.number(format!("n{i}"), i)
+        }),
+    ];
+    for attrs in &invalidos {
+        assert!(attrs.to_value().is_err());
+    }
+
+    let grid = envelope::EventGrid::new(
+        "e-1",
+        "Loja.PedidoPago",
+        "/pedidos/42",
+        "2024-05-01T12:00:00.5-03:00",
+    )
+    .data_version("2")
+    .wrap(detalhe.clone())
+    .expect("evento Event Grid válido");
+    assert_eq!(grid["dataVersion"], Value::from("2"));
+    assert_eq!(grid["data"], detalhe);
+    for quando in [
+        "2024-05-01",
+        "2024-05-01 12:00:00Z",
+        "2024-05-01T12:00:00.Z",
+        "2024-05-01T12:00:00+3",
+    ] {
+        assert!(
+            envelope::EventGrid::new("e", "T", "/s", quando)
+                .wrap(Value::Null)
+                .is_err()
+        );
+    }
+    assert!(
+        envelope::EventGrid::new("", "T", "/s", "2024-05-01T12:00:00Z")
+            .wrap(Value::Null)
+            .is_err()
+    );
+}
+
+// Linhas da outbox transacional
+#[test]
+fn outbox_rows() {
+    let evento = json_safe!({ total: 10, pedido: "p-1" }).unwrap();
+    let linha = outbox::OutboxRow::new("p-1", "PedidoPago", &evento)
+        .unwrap()
+        .header("traceparent", "00-abc");
+    assert_eq!(linha.payload, r#"{"pedido":"p-1","total":10}"#);
+    assert_eq!(linha.payload_value(), evento);
+    assert_eq!(linha.payload_hash().len(), 64);
+
+    let padrao = outbox::Columns::new();
+    assert_eq!(
+        linha.to_value(&padrao),
+        json_safe!({
+            aggregate_id: "p-1",
+            "type": "PedidoPago",
+            payload: r#"{"pedido":"p-1","total":10}"#,
+            headers: { traceparent: "00-abc" },
+        })
+        .unwrap()
+    );
+
+    let colunas = outbox::Columns::new()
+        .event_type("event_type")
+        .map(|c| format!("outbox_{c}"));
+    let gravada = linha.to_value(&colunas);
+    let chaves: Vec<&String> = gravada.as_object().unwrap().keys().collect();
+    assert_eq!(
+        chaves,
+        [
+            "outbox_aggregate_id",
+            "outbox_event_type",
+            "outbox_headers",
+            "outbox_payload"
+        ]
+    );
+    assert_eq!(
+        outbox::OutboxRow::from_value(&gravada, &colunas).unwrap(),
+        linha
+    );
+
+    assert!(outbox::OutboxRow::new("", "X", &evento).is_err());
+    assert!(outbox::OutboxRow::from_value(&gravada, &padrao).is_err());
+}
+
+// Eventos de erro Sentry/Rollbar (feature "error-events")
+#[cfg(feature = "error-events")]
+#[test]
+fn error_events() {
+    use error_event::{ErrorEvent, Level};
+
+    #[derive(Debug)]
+    struct FalhaPagamento(std::num::ParseIntError);
+
+    impl std::fmt::Display for FalhaPagamento {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "falha ao processar pagamento")
+        }
+    }
+
+    impl std::error::Error for FalhaPagamento {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let falha = FalhaPagamento("abc".parse::<u32>().unwrap_err());
+    let evento = ErrorEvent::from_error(&falha)
+        .level(Level::Fatal)
+        .tag("servico", "billing")
+        .context("pedido", json_safe!({ id: 42 }).unwrap())
+        .breadcrumb("http", "POST /pagamentos")
+        .extra(json_safe!({ tentativa: 3 }).unwrap())
+        .unwrap();
+
+    let sentry = evento.to_sentry().unwrap();
+    assert_eq!(sentry["level"], Value::from("fatal"));
+    assert_eq!(
+        sentry["exception"]["values"][0],
+        json_safe!({ type: "ParseIntError", value: "invalid digit found in string" }).unwrap()
+    );
+    assert!(
+        sentry["exception"]["values"][1]["type"]
+            .as_str()
+            .unwrap()
+            .ends_with("FalhaPagamento")
+    );
+    assert_eq!(
+        sentry["breadcrumbs"]["values"][0]["category"],
+        Value::from("http")
+    );
+    assert_eq!(sentry["tags"]["servico"], Value::from("billing"));
+    assert_eq!(sentry["contexts"]["pedido"]["id"], Value::from(42));
+    assert_eq!(sentry["extra"]["tentativa"], Value::from(3));
+
+    let rollbar = evento.to_rollbar("producao").unwrap();
+    assert_eq!(rollbar["data"]["level"], Value::from("critical"));
+    assert_eq!(
+        rollbar["data"]["body"]["trace_chain"][1]["exception"]["class"],
+        Value::from("ParseIntError")
+    );
+    assert_eq!(rollbar["data"]["custom"]["tentativa"], Value::from(3));
+    assert_eq!(rollbar["data"]["context"], Value::from("servico:billing"));
+    assert!(evento.to_rollbar("").is_err());
+
+    for (nivel, nome) in [
+        (Level::Error, "error"),
+        (Level::Warning, "warning"),
+        (Level::Info, "info"),
+        (Level::Debug, "debug"),
+    ] {
+        let e = ErrorEvent::from_error(&falha)
+            .level(nivel)
+            .to_sentry()
+            .unwrap();
+        assert_eq!(e["level"], Value::from(nome));
+    }
+
+    assert!(
+        ErrorEvent::from_error(&falha)
+            .extra(json_safe!([1]).unwrap())
+            .is_err()
+    );
+    assert!(
+        ErrorEvent::from_error(&falha)
+            .tag("x".repeat(33), "v")
+            .to_sentry()
+            .is_err()
+    );
+    assert!(
+        ErrorEvent::from_error(&falha)
+            .tag("k", "v".repeat(201))
+            .to_sentry()
+            .is_err()
+    );
+    assert!(
+        ErrorEvent::from_error(&falha)
+            .context("c", Value::from(1))
+            .to_sentry()
+            .is_err()
+    );
+}
+
+// Slack Block Kit e embeds do Discord
+#[test]
+fn slack_and_discord() {
+    use slack::{Block, Button, ButtonStyle, Section};
+
+    let msg = slack::Message::new("Deploy concluído")
+        .block(Block::Header("Deploy".into()))
+        .block(Block::Section(
+            Section::new("*billing* v1.2.3")
+                .field("*Ambiente:* prod")
+                .accessory(Button::new("Abrir", "abrir").url("https://ci/1")),
+        ))
+        .block(Block::Divider)
+        .block(Block::Actions(vec![
+            Button::new("Rollback", "rollback")
+                .value("v1.2.2")
+                .style(ButtonStyle::Danger),
+            Button::new("Ok", "ok").style(ButtonStyle::Primary),
+        ]));
+    let slack_json = msg.to_value().expect("mensagem Slack válida");
+    assert_eq!(
+        slack_json["blocks"][0],
+        json_safe!({ type: "header", text: { type: "plain_text", text: "Deploy" } }).unwrap()
+    );
+    assert_eq!(
+        slack_json["blocks"][1]["fields"][0]["type"],
+        Value::from("mrkdwn")
+    );
+    assert_eq!(
+        slack_json["blocks"][1]["accessory"]["url"],
+        Value::from("https://ci/1")
+    );
+    assert_eq!(slack_json["blocks"][2]["type"], Value::from("divider"));
+    assert_eq!(
+        slack_json["blocks"][3]["elements"][0]["style"],
+        Value::from("danger")
+    );
+    assert_eq!(
+        slack_json["blocks"][3]["elements"][0]["value"],
+        Value::from("v1.2.2")
+    );
+
+    let erro_slack = |m: slack::Message| m.to_value().unwrap_err().to_string();
+    assert!(
+        erro_slack(slack::Message::new("x").block(Block::Header("h".repeat(151))))
+            .starts_with("blocks[0].text: 151")
+    );
+    assert!(
+        erro_slack(slack::Message::new("x").block(Block::Actions(vec![])))
+            .starts_with("blocks[0].elements")
+    );
+    assert!(
+        erro_slack(
+            slack::Message::new("x").block(Block::Section(Section::new("s").field("").field("ok")))
+        )
+        .starts_with("blocks[0].fields[0]: texto vazio")
+    );
+    assert!(
+        erro_slack(
+            slack::Message::new("x").block(Block::Actions(vec![Button::new("b".repeat(76), "id")]))
+        )
+        .starts_with("blocks[0].elements[0].text")
+    );
+    assert!(
+        (0..51)
+            .fold(slack::Message::new("x"), |m, _| m.block(Block::Divider))
+            .to_value()
+            .is_err()
+    );
+
+    let embed = discord::Embed::new()
+        .title("Deploy")
+        .description("billing v1.2.3")
+        .url("https://ci/1")
+        .color(0x00FF00)
+        .field("Ambiente", "prod", true)
+        .footer("CI")
+        .author("bot");
+    let webhook = discord::WebhookMessage::new()
+        .content("novo deploy")
+        .username("ci-bot")
+        .embed(embed.clone())
+        .to_value()
+        .expect("webhook Discord válido");
+    assert_eq!(
+        webhook["embeds"][0]["fields"][0],
+        json_safe!({ name: "Ambiente", value: "prod", inline: true }).unwrap()
+    );
+    assert_eq!(webhook["embeds"][0]["footer"]["text"], Value::from("CI"));
+    assert_eq!(webhook["embeds"][0]["color"], Value::from(0x00FF00));
+
+    let grande = discord::Embed::new().description("d".repeat(4000));
+    let total = discord::WebhookMessage::new()
+        .embed(grande.clone())
+        .embed(grande);
+    assert!(total.to_value().unwrap_err().to_string().contains("6000"));
+    assert!(discord::WebhookMessage::new().to_value().is_err());
+    assert!(
+        discord::WebhookMessage::new()
+            .embed(discord::Embed::new())
+            .to_value()
+            .is_err()
+    );
+    assert!(
+        discord::WebhookMessage::new()
+            .embed(discord::Embed::new().color(0x1000000))
+            .to_value()
+            .is_err()
+    );
+    assert!(
+        discord::WebhookMessage::new()
+            .embed(discord::Embed::new().field("", "v", false))
+            .to_value()
+            .is_err()
+    );
+}
+
+// Exposição OpenMetrics
+#[test]
+fn openmetrics() {
+    use openmetrics::{MappingRules, MetricType};
+
+    let metricas = json_safe!({
+        "http_requests_total": { "get": 10, "post": 2 },
+        "fila": { "profundidade": 3, "em-espera": 1.5 },
+        "pronto": true,
+        "versao": "1.2.3",
+        "9vidas": 9,
+    })
+    .unwrap();
+
+    let regras = MappingRules::new()
+        .prefix("billing_")
+        .label("instancia", "a\"1")
+        .metric_type("http_requests_total", MetricType::Counter)
+        .metric_type("pronto", MetricType::Unknown)
+        .help("http_requests_total", "Requisições HTTP\nrecebidas")
+        .label_key("http_requests_total", "method");
+
+    assert_eq!(
+        openmetrics::to_openmetrics(&metricas, regras),
+        "# TYPE billing_9vidas gauge\n\
+     billing_9vidas{instancia=\"a\\\"1\"} 9\n\
+     # TYPE billing_fila_em_espera gauge\n\
+     billing_fila_em_espera{instancia=\"a\\\"1\"} 1.5\n\
+     # TYPE billing_fila_profundidade gauge\n\
+     billing_fila_profundidade{instancia=\"a\\\"1\"} 3\n\
+     # TYPE billing_http_requests counter\n\
+     # HELP billing_http_requests Requisições HTTP\\nrecebidas\n\
+     billing_http_requests_total{instancia=\"a\\\"1\",method=\"get\"} 10\n\
+     billing_http_requests_total{instancia=\"a\\\"1\",method=\"post\"} 2\n\
+     # TYPE billing_pronto unknown\n\
+     billing_pronto{instancia=\"a\\\"1\"} 1\n\
+     # EOF\n"
+    );
+
+    assert_eq!(
+        openmetrics::to_openmetrics(
+            &json_safe!({ "9": 1, "erros": 0 }).unwrap(),
+            MappingRules::new().default_type(MetricType::Counter)
+        ),
+        "# TYPE _9 counter\n_9_total 1\n# TYPE erros counter\nerros_total 0\n# EOF\n"
+    );
+    // `:` vale no nome da métrica, não no do label; aninhados sob um
+    // label_key são ignorados
+    assert_eq!(
+        openmetrics::to_openmetrics(
+            &json_safe!({ "api:latencia": { "get": 5, "post": { "x": 1 } } }).unwrap(),
+            MappingRules::new()
+                .label("zona:regiao", "sa")
+                .label_key("api:latencia", "http:method")
+        ),
+        "# TYPE api:latencia gauge\napi:latencia{zona_regiao=\"sa\",http_method=\"get\"} 5\n# EOF\n"
+    );
+    assert_eq!(
+        openmetrics::to_openmetrics(&Value::Null, MappingRules::new()),
+        "# EOF\n"
+    );
+}
+
+// Health check / readiness
+#[test]
+fn health() {
+    use std::time::Duration;
+
+    let saude = health::HealthChecks::new()
+        .check("db", Duration::from_millis(500), || Ok(()))
+        .optional_check("cache", Duration::from_millis(500), || {
+            Err("conexão recusada".into())
+        })
+        .run();
+    assert_eq!(saude.status(), health::Status::Warn);
+    let saude_json = saude.to_value();
+    assert_eq!(saude_json["status"], Value::from("warn"));
+    assert_eq!(saude_json["checks"]["db"]["status"], Value::from("pass"));
+    assert!(saude_json["checks"]["db"].get("error").is_none());
+    assert_eq!(
+        saude_json["checks"]["cache"]["error"],
+        Value::from("conexão recusada")
+    );
+    assert!(saude_json["checks"]["cache"]["latency_ms"].is_u64());
+
+    let lento = health::HealthChecks::new()
+        .check("fila", Duration::from_millis(20), || {
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(())
+        })
+        .run();
+    assert_eq!(lento.status(), health::Status::Fail);
+    assert_eq!(
+        lento.to_value()["checks"]["fila"]["error"],
+        Value::from("timeout após 20ms")
+    );
+
+    let manual = health::HealthReport::new().record(
+        "externo",
+        health::CheckResult {
+            status: health::Status::Pass,
+            latency: Duration::from_millis(7),
+            error: None,
+        },
+    );
+    assert_eq!(
+        manual.to_value(),
+        json_safe!({ status: "pass", checks: { externo: { status: "pass", latency_ms: 7 } } })
+            .unwrap()
+    );
+    assert_eq!(health::HealthReport::new().status(), health::Status::Pass);
+}
+
+// Contexto de avaliação de feature flags
+#[test]
+fn flag_context() {
+    use flag_context::{FlagContext, KeyStyle};
+
+    let ctx = FlagContext::new("user-42")
+        .attr("Plano Atual", "pro")
+        .and_then(|c| c.attr("betaTester", true))
+        .and_then(|c| c.attr("grupos", ["a", "b"]))
+        .and_then(|c| c.attr("apelido", None::<String>))
+        .and_then(|c| {
+            c.attr(
+                "endereco",
+                json_safe!({ cidade: "Recife", cep: "50000" }).unwrap(),
+            )
+        })
+        .and_then(|c| c.private_attr("email", "alice@example.com"))
+        .expect("contexto válido");
+    assert_eq!(
+        ctx.to_value().unwrap(),
+        json_safe!({
+            "key": "user-42",
+            "plano_atual": "pro",
+            "beta_tester": true,
+            "grupos": ["a", "b"],
+            "endereco_cidade": "Recife",
+            "endereco_cep": "50000",
+            "email": "alice@example.com",
+            "_meta": { "privateAttributes": ["email"] },
+        })
+        .unwrap()
+    );
+    let redigido = ctx.to_redacted_value().unwrap();
+    assert!(redigido.get("email").is_none() && redigido.get("_meta").is_none());
+    assert_eq!(redigido["plano_atual"], Value::from("pro"));
+
+    let camel = FlagContext::new("u")
+        .key_style(KeyStyle::Camel)
+        .attr("plano_atual", 1)
+        .and_then(|c| c.attr("endereco", json_safe!({ cidade: "X" }).unwrap()))
+        .unwrap()
+        .to_value()
+        .unwrap();
+    assert_eq!(camel["planoAtual"], Value::from(1));
+    assert_eq!(camel["enderecoCidade"], Value::from("X"));
+
+    let preservado = FlagContext::new("u")
+        .key_style(KeyStyle::Preserve)
+        .attr("Plano-Atual", 1)
+        .and_then(|c| c.attr("e", json_safe!({ c: 2 }).unwrap()))
+        .unwrap()
+        .to_value()
+        .unwrap();
+    assert_eq!(preservado["Plano-Atual"], Value::from(1));
+    assert_eq!(preservado["e.c"], Value::from(2));
+
+    assert!(
+        FlagContext::new("u")
+            .attr("lista", json_safe!([{ a: 1 }]).unwrap())
+            .is_err()
+    );
+    assert!(
+        FlagContext::new("u")
+            .attr("plano_atual", 1)
+            .and_then(|c| c.attr("PlanoAtual", 2))
+            .is_err()
+    );
+    assert!(FlagContext::new("u").attr("key", 1).is_err());
+    assert!(FlagContext::new("u").attr("--", 1).is_err());
+    assert!(FlagContext::new(" ").to_value().is_err());
+}
+
+// Presets de schema para eventos de experimento
+#[test]
+fn event_schema() {
+    use event_schema::{EventSchema, FieldType};
+
+    let bytes = event_schema::emit_checked(
+        "experiment_exposure",
+        json_safe!({
+            experiment_id: "checkout-v2",
+            variant: "b",
+            user_id: "u-1",
+            timestamp: "2024-05-01T12:00:00Z",
+        })
+        .unwrap(),
+    )
+    .expect("evento de exposição válido");
+    assert_eq!(
+        serde_json::from_slice::<Value>(&bytes).unwrap()["variant"],
+        Value::from("b")
+    );
+
+    let falha = event_schema::emit_checked(
+        "conversion",
+        json_safe!({ experiment_id: 1, user_id: "u", timestamp: "t", value: "10", extra: true })
+            .unwrap(),
+    )
+    .unwrap_err()
+    .to_string();
+    assert_eq!(
+        falha,
+        "conversion: campo obrigatório ausente: event; campo experiment_id deve ser string; \
+     campo value deve ser number; campo não declarado: extra"
+    );
+    assert!(event_schema::emit_checked("inexistente", json_safe!({}).unwrap()).is_err());
+
+    event_schema::register(
+        "checkout",
+        EventSchema::new()
+            .required("pedido", FieldType::Integer)
+            .optional("itens", FieldType::Array)
+            .optional("pago", FieldType::Boolean)
+            .allow_additional(true),
+    );
+    assert!(
+        event_schema::emit_checked(
+            "checkout",
+            json_safe!({ pedido: 1, itens: [], pago: true, x: 1 }).unwrap()
+        )
+        .is_ok()
+    );
+    assert!(event_schema::emit_checked("checkout", json_safe!({ pedido: 1.5 }).unwrap()).is_err());
+    assert!(event_schema::emit_checked("checkout", json_safe!([]).unwrap()).is_err());
+
+    let local = event_schema::SchemaRegistry::new().register("x", EventSchema::new());
+    assert!(local.emit_checked("x", &json_safe!({}).unwrap()).is_ok());
+    assert!(
+        local
+            .emit_checked("conversion", &json_safe!({}).unwrap())
+            .is_err()
+    );
+}
+
+// SBOM CycloneDX a partir de `cargo metadata`
+#[test]
+fn sbom() {
+    use sbom::{Bom, Component, ComponentType};
+
+    let metadata: Value = serde_json::from_str(
+        r#"{
+        "packages": [
+            { "id": "app 0.1.0 (path+file:///app)", "name": "app", "version": "0.1.0",
+              "license": "MIT", "description": "Serviço\n",
+              "targets": [{ "kind": ["bin"], "name": "app" }] },
+            { "id": "serde 1.0.200 (registry+...)", "name": "serde", "version": "1.0.200",
+              "license": "MIT OR Apache-2.0", "targets": [{ "kind": ["lib"] }] },
+            { "id": "itoa 1.0.11 (registry+...)", "name": "itoa", "version": "1.0.11",
+              "license": "MIT OR Apache-2.0", "targets": [] },
+            { "id": "interno 0.3.0 (git+...)", "name": "interno", "version": "0.3.0",
+              "license": null, "targets": [] }
+        ],
+        "resolve": {
+            "root": "app 0.1.0 (path+file:///app)",
+            "nodes": [
+                { "id": "app 0.1.0 (path+file:///app)",
+                  "deps": [{ "pkg": "serde 1.0.200 (registry+...)" },
+                           { "pkg": "interno 0.3.0 (git+...)" }] },
+                { "id": "serde 1.0.200 (registry+...)", "deps": [] }
+            ]
+        }
+    }"#,
+    )
+    .unwrap();
+    let bom = Bom::from_cargo_metadata(&metadata).unwrap();
+    let doc = bom.to_value().unwrap();
+    assert_eq!(doc["bomFormat"], Value::from("CycloneDX"));
+    let raiz = &doc["metadata"]["component"];
+    assert_eq!(raiz["type"], Value::from("application"));
+    assert_eq!(raiz["purl"], Value::from("pkg:cargo/app@0.1.0"));
+    assert_eq!(raiz["description"], Value::from("Serviço"));
+    assert_eq!(doc["components"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        doc["components"][0]["licenses"][0]["expression"],
+        Value::from("MIT OR Apache-2.0")
+    );
+    assert!(doc["components"][2].get("licenses").is_none());
+    assert_eq!(
+        doc["dependencies"],
+        json_safe!([{
+            "ref": "pkg:cargo/app@0.1.0",
+            "dependsOn": ["pkg:cargo/serde@1.0.200", "pkg:cargo/interno@0.3.0"],
+        }])
+        .unwrap()
+    );
+    assert_eq!(
+        bom.license_report(),
+        json_safe!({
+            "MIT OR Apache-2.0": ["serde@1.0.200", "itoa@1.0.11"],
+            "UNKNOWN": ["interno@0.3.0"],
+        })
+        .unwrap()
+    );
+
+    let manual = Bom::new()
+        .root(Component::new("ferramenta", "2.0.0").kind(ComponentType::Application))
+        .component(Component::new("x", "1.0.0").depends_on("pkg:cargo/y@1.0.0"))
+        .to_value()
+        .unwrap();
+    assert_eq!(
+        manual["components"][0]["bom-ref"],
+        Value::from("pkg:cargo/x@1.0.0")
+    );
+    assert_eq!(
+        manual["dependencies"][0]["ref"],
+        Value::from("pkg:cargo/x@1.0.0")
+    );
+    assert!(
+        Bom::new()
+            .component(Component::new("", "1"))
+            .to_value()
+            .is_err()
+    );
+    assert!(Bom::from_cargo_metadata(&json_safe!({}).unwrap()).is_err());
+}
+
+// Gravação e reprodução de interações HTTP
+#[test]
+fn cassette() {
+    use cassette::Cassette;
+    use profile::Rules;
+
+    let regras = Rules::new().redact("/token").redact("/user/email");
+    let mut gravacao = Cassette::new().redact_with(regras.clone());
+    let login = json_safe!({ user: "ana", token: "segredo-1" }).unwrap();
+    gravacao.record(
+        "post",
+        "/login",
+        Some(&login),
+        200,
+        &json_safe!({ token: "sess-9", user: { id: 1, email: "ana@example.com" } }).unwrap(),
+    );
+    gravacao.record(
+        "GET",
+        "/jobs/1",
+        None,
+        202,
+        &json_safe!({ state: "queued" }).unwrap(),
+    );
+    gravacao.record(
+        "GET",
+        "/jobs/1",
+        None,
+        200,
+        &json_safe!({ state: "done" }).unwrap(),
+    );
+
+    let login_gravado = &gravacao.interactions()[0];
+    assert_eq!(login_gravado.method, "POST");
+    assert_eq!(
+        login_gravado.request.as_ref().unwrap()["token"],
+        Value::from(profile::MASK)
+    );
+    assert_eq!(
+        login_gravado.response["user"]["email"],
+        Value::from(profile::MASK)
+    );
+
+    let arquivo =
+        std::env::temp_dir().join(format!("json_safe_cassette_{}.json", std::process::id()));
+    gravacao.save(&arquivo).unwrap();
+    let mut reproducao = Cassette::load(&arquivo).unwrap().redact_with(regras);
+    std::fs::remove_file(&arquivo).unwrap();
+
+    // Token diferente casa, porque a comparação é feita já redigida
+    let outro_login = json_safe!({ user: "ana", token: "segredo-2" }).unwrap();
+    let (status, corpo) = reproducao
+        .replay("POST", "/login", Some(&outro_login))
+        .unwrap();
+    assert_eq!((status, corpo["user"]["id"].clone()), (200, Value::from(1)));
+    assert!(
+        reproducao
+            .replay(
+                "POST",
+                "/login",
+                Some(&json_safe!({ user: "bia" }).unwrap())
+            )
+            .is_none()
+    );
+
+    // Mesma requisição: respostas na ordem gravada, depois repete a última
+    assert_eq!(reproducao.replay("GET", "/jobs/1", None).unwrap().0, 202);
+    assert_eq!(reproducao.replay("GET", "/jobs/1", None).unwrap().0, 200);
+    assert_eq!(reproducao.replay("GET", "/jobs/1", None).unwrap().0, 200);
+    assert!(reproducao.replay("GET", "/jobs/2", None).is_none());
+    assert!(
+        Cassette::from_value(&json_safe!({ interactions: [{ method: "GET" }] }).unwrap()).is_err()
+    );
+}
+
+// Metadados de host/processo em eventos
+#[test]
+fn enrich_with_runtime() {
+    let mut evento = json_safe!({ tipo: "pedido.criado" }).unwrap();
+    runtime::enrich_with_runtime(&mut evento, &json_safe::runtime!()).unwrap();
+    assert_eq!(evento["runtime"]["pid"], Value::from(std::process::id()));
+    assert_eq!(
+        evento["runtime"]["version"],
+        Value::from(env!("CARGO_PKG_VERSION"))
+    );
+    assert_eq!(
+        evento["runtime"].get("git_sha").and_then(Value::as_str),
+        option_env!("GIT_SHA")
+    );
+
+    let meta = runtime::Runtime::detect()
+        .key("_meta")
+        .hostname("web-1")
+        .version("2.3.0")
+        .git_sha(Some("abc123"));
+    runtime::enrich_with_runtime(&mut evento, &meta).unwrap();
+    assert_eq!(
+        evento["_meta"],
+        json_safe!({ hostname: "web-1", pid: (std::process::id()), version: "2.3.0", git_sha: "abc123" })
+            .unwrap()
+    );
+    assert!(runtime::enrich_with_runtime(&mut json_safe!([1]).unwrap(), &meta).is_err());
+}
+
+// Propagação de contexto de trace W3C em documentos
+#[test]
+fn trace_context_propagation() {
+    use json_safe::clock::SequentialIds;
+    use trace_context::{TraceContext, TracePaths};
+
+    let cabecalho = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let ctx = TraceContext::from_headers(cabecalho, Some("congo=t61rcWkgMzE")).unwrap();
+    assert!(ctx.sampled());
+    assert_eq!(ctx.traceparent(), cabecalho);
+
+    let mut mensagem = json_safe!({ pedido: 42 }).unwrap();
+    trace_context::inject(&mut mensagem, &ctx, &TracePaths::new()).unwrap();
+    assert_eq!(mensagem["trace"]["traceparent"], Value::from(cabecalho));
+    assert_eq!(
+        trace_context::extract(&mensagem, &TracePaths::new()).unwrap(),
+        Some(ctx.clone())
+    );
+
+    // Caminhos configurados e próximo salto no mesmo trace
+    let caminhos = TracePaths::new()
+        .traceparent("/meta/headers/traceparent")
+        .tracestate("/meta/headers/tracestate");
+    let proximo = ctx.child();
+    assert_eq!(proximo.trace_id, ctx.trace_id);
+    assert_ne!(proximo.parent_id, ctx.parent_id);
+    let mut evento = json_safe!({ meta: { origem: "api" } }).unwrap();
+    trace_context::inject(&mut evento, &proximo, &caminhos).unwrap();
+    assert_eq!(evento["meta"]["origem"], Value::from("api"));
+    assert_eq!(
+        trace_context::extract(&evento, &caminhos).unwrap(),
+        Some(proximo)
+    );
+
+    assert_eq!(
+        trace_context::extract(&json_safe!({}).unwrap(), &caminhos).unwrap(),
+        None
+    );
+    let raiz = TraceContext::new_root();
+    assert!(TraceContext::from_headers(&raiz.traceparent(), None).is_ok());
+
+    // Ids sequenciais (só os bytes finais mudam) ainda dão spans distintos
+    let (raiz, filhos) = json_safe::clock::with_id_gen(SequentialIds::default(), || {
+        let raiz = TraceContext::new_root();
+        (raiz.clone(), [raiz.child(), raiz.child()])
+    });
+    assert_ne!(raiz.parent_id, filhos[0].parent_id);
+    assert_ne!(filhos[0].parent_id, filhos[1].parent_id);
+    assert_ne!(&raiz.trace_id[..16], raiz.parent_id);
+    for ctx in [&raiz, &filhos[0], &filhos[1]] {
+        assert!(TraceContext::from_headers(&ctx.traceparent(), None).is_ok());
+    }
+    for invalido in [
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+    ] {
+        assert!(
+            TraceContext::from_headers(invalido, None).is_err(),
+            "{invalido}"
+        );
+    }
+    assert!(
+        trace_context::inject(
+            &mut json_safe!({ trace: 1 }).unwrap(),
+            &ctx,
+            &TracePaths::new()
+        )
+        .is_err()
+    );
+}
+
+// Envelope reenviável com tentativa e hash do payload
+#[test]
+fn retryable_envelope() {
+    let mensagem = envelope::retryable(json_safe!({ pedido: 42, itens: [1, 2] }).unwrap());
+    assert_eq!(mensagem["attempt"], Value::from(1));
+    assert_eq!(mensagem["id"].as_str().unwrap().len(), 36);
+
+    let primeira = envelope::verify_retryable(&mensagem).unwrap();
+    assert_eq!(primeira.payload["pedido"], Value::from(42));
+    let reenvio = envelope::next_attempt(&mensagem).unwrap();
+    let segunda = envelope::verify_retryable(&reenvio).unwrap();
+    assert_eq!((segunda.id, segunda.attempt), (primeira.id, 2));
+
+    let mut dedup = envelope::Dedup::new();
+    assert!(dedup.first_delivery(&primeira));
+    assert!(!dedup.first_delivery(&segunda));
+    let outra = envelope::retryable(json_safe!({ pedido: 42, itens: [1, 2] }).unwrap());
+    assert_eq!(outra["payload_hash"], mensagem["payload_hash"]);
+    assert!(dedup.first_delivery(&envelope::verify_retryable(&outra).unwrap()));
+
+    let mut alterada = mensagem.clone();
+    alterada["payload"]["pedido"] = Value::from(43);
+    assert!(envelope::verify_retryable(&alterada).is_err());
+    let mut sem_tentativa = mensagem.clone();
+    sem_tentativa["attempt"] = Value::from(0);
+    assert!(envelope::verify_retryable(&sem_tentativa).is_err());
+    assert!(envelope::next_attempt(&json_safe!({ payload: 1 }).unwrap()).is_err());
+}
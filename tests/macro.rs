@@ -0,0 +1,934 @@
+use json_safe::error::PathSegment;
+#[cfg(feature = "proc-macro")]
+use json_safe::json_safe_pm;
+use json_safe::{JsonSafeError, json_safe, object_from_pairs};
+use serde_json::Value;
+
+// Objeto com chaves como identificadores
+#[test]
+fn object_with_ident_keys() {
+    let obj_ident = json_safe!({
+        foo: 1,
+        bar: "baz",
+        nested: {
+            answer: 42,
+        },
+    });
+
+    match obj_ident {
+        Ok(Value::Object(map)) => {
+            assert_eq!(map.get("foo"), Some(&Value::from(1)));
+            assert_eq!(map.get("bar"), Some(&Value::from("baz")));
+
+            match map.get("nested") {
+                Some(Value::Object(nested)) => {
+                    assert_eq!(nested.get("answer"), Some(&Value::from(42)));
+                }
+                _ => panic!("campo 'nested' não é objeto ou não existe"),
+            }
+        }
+        Ok(_) => panic!("obj_ident não é um objeto JSON"),
+        Err(e) => panic!("json_safe! com chaves ident retornou erro: {e}"),
+    }
+
+    // Identificadores raw perdem o prefixo r#, como no json_safe_pm!
+    let r#type = "admin";
+    assert_eq!(
+        json_safe!({ r#type: 1, nested: { r#type } }).unwrap(),
+        json_safe!({ "type": 1, "nested": { "type": "admin" } }).unwrap()
+    );
+}
+
+// Objeto com chaves literais (&str)
+#[test]
+fn object_with_literal_keys() {
+    let obj_literal = json_safe!({
+        "user": "alice",
+        "ativo": true,
+        "contador": 10u64,
+    });
+
+    match obj_literal {
+        Ok(Value::Object(map)) => {
+            assert_eq!(map.get("user"), Some(&Value::from("alice")));
+            assert_eq!(map.get("ativo"), Some(&Value::from(true)));
+            assert_eq!(map.get("contador"), Some(&Value::from(10u64)));
+        }
+        Ok(_) => panic!("obj_literal não é um objeto JSON"),
+        Err(e) => panic!("json_safe! com chaves literais retornou erro: {e}"),
+    }
+}
+
+// Objeto com chaves identificadores e literais misturadas
+#[test]
+fn object_with_mixed_keys() {
+    let misto = json_safe!({
+        foo: 1,
+        "bar-baz": 2,
+        "a.b": { inner: true, "x-y": null },
+        fim: "ok",
+    })
+    .expect("chaves misturadas devem funcionar");
+
+    let mut chaves: Vec<&str> = misto
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    chaves.sort();
+    assert_eq!(chaves, ["a.b", "bar-baz", "fim", "foo"]);
+    assert_eq!(misto["bar-baz"], Value::from(2));
+    assert_eq!(misto["a.b"]["x-y"], Value::Null);
+    assert_eq!(misto["a.b"]["inner"], Value::from(true));
+}
+
+// Chaves calculadas: (expr): valor
+#[test]
+fn computed_keys() {
+    let id = 7;
+    let campo = String::from("status");
+    let sufixo = "b";
+    let obj = json_safe!({
+        (format!("user_{id}")): true,
+        (campo.clone()): "ativo",
+        ("a-".to_string() + sufixo): [1, 2],
+        fixo: { (sufixo): null },
+    })
+    .unwrap();
+
+    assert_eq!(obj["user_7"], Value::from(true));
+    assert_eq!(obj["status"], Value::from("ativo"));
+    assert_eq!(obj["a-b"], json_safe!([1, 2]).unwrap());
+    assert_eq!(obj["fixo"]["b"], Value::Null);
+    assert_eq!(obj.as_object().unwrap().len(), 4);
+}
+
+// Atalho de campo: { foo, bar }
+#[test]
+fn field_shorthand() {
+    let id = 42;
+    let nome = String::from("Ana");
+    let tags = vec!["a", "b"];
+    let obj = json_safe!({ id, nome, "origem": "api", tags }).unwrap();
+
+    assert_eq!(
+        obj,
+        json_safe!({ id: 42, nome: "Ana", origem: "api", tags: ["a", "b"] }).unwrap()
+    );
+    // As variáveis continuam disponíveis (serializadas por referência)
+    assert_eq!(nome.len() + tags.len(), 5);
+}
+
+// Spread de objetos: { ..base, extra: 1 }
+#[test]
+fn object_spread() {
+    #[derive(serde::Serialize)]
+    struct Padrao {
+        pagina: u32,
+        limite: u32,
+    }
+
+    let base = json_safe!({ a: 1, b: 2 }).unwrap();
+    let obj = json_safe!({ ..base, b: 20, c: 3 }).unwrap();
+    assert_eq!(obj, json_safe!({ a: 1, b: 20, c: 3 }).unwrap());
+    // Spread depois das chaves: as entradas dele vencem
+    let obj = json_safe!({ b: 0, ..base }).unwrap();
+    assert_eq!(obj["b"], Value::from(2));
+
+    let padrao = Padrao {
+        pagina: 1,
+        limite: 50,
+    };
+    let consulta = json_safe!({ ..padrao, limite: 10, ..json_safe!({ q: "x" }).unwrap() }).unwrap();
+    assert_eq!(
+        consulta,
+        json_safe!({ pagina: 1, limite: 10, q: "x" }).unwrap()
+    );
+
+    let erro = json_safe!({ a: 1, ..vec![1, 2] }).unwrap_err();
+    assert!(erro.to_string().contains("esperado objeto"));
+}
+
+// Flatten de structs: { id: 1, ..flatten!(endereco) }
+#[test]
+fn flatten_marker() {
+    #[derive(serde::Serialize)]
+    struct Endereco {
+        cidade: &'static str,
+        cep: &'static str,
+    }
+
+    let endereco = Endereco {
+        cidade: "Recife",
+        cep: "50000-000",
+    };
+    let doc = json_safe!({ id: 1, ..flatten!(endereco), cep: "00000-000" }).unwrap();
+    assert_eq!(
+        doc,
+        json_safe!({ id: 1, cidade: "Recife", cep: "00000-000" }).unwrap()
+    );
+    // Serializado por referência: a struct continua disponível
+    assert_eq!(endereco.cidade, "Recife");
+
+    let erro = json_safe!({ dados: { ..flatten!(vec![1, 2]) } }).unwrap_err();
+    assert_eq!(erro.path(), "dados");
+    assert!(
+        erro.to_string().contains("flatten!: esperado objeto"),
+        "{erro}"
+    );
+
+    #[cfg(feature = "proc-macro")]
+    {
+        assert_eq!(
+            json_safe_pm!({ id: 1, ..flatten!(endereco), cep: "00000-000" }).unwrap(),
+            doc
+        );
+        assert!(json_safe_pm!({ ..flatten!(3) }).is_err());
+    }
+}
+
+// Spread de iteradores em arrays: [ 1, ..iter, "fim" ]
+#[test]
+fn array_spread() {
+    let usuarios = [("ana", 30), ("bia", 25)];
+    let resumo = |u: &(&str, u32)| json_safe!({ nome: (u.0), idade: (u.1) }).unwrap();
+    let arr = json_safe!([1, ..usuarios.iter().map(resumo), "fim"]).unwrap();
+    assert_eq!(
+        arr,
+        json_safe!([1, { nome: "ana", idade: 30 }, { nome: "bia", idade: 25 }, "fim"]).unwrap()
+    );
+
+    assert_eq!(
+        json_safe!([..1..4, ..Vec::<u8>::new()]).unwrap(),
+        json_safe!([1, 2, 3]).unwrap()
+    );
+    assert_eq!(
+        json_safe!({ ids: [0, ..vec![7, 8]] }).unwrap()["ids"],
+        json_safe!([0, 7, 8]).unwrap()
+    );
+
+    let mut falha = std::collections::HashMap::new();
+    falha.insert(vec![1], 1);
+    assert!(json_safe!([..vec![falha]]).is_err());
+}
+
+// Campos opcionais: chave?: expr omite a chave quando é None
+#[test]
+fn optional_fields() {
+    let nome: Option<&str> = Some("Ana");
+    let apelido: Option<String> = None;
+    let idade: Option<u32> = Some(30);
+    let patch = json_safe!({
+        nome?: nome,
+        apelido?: apelido,
+        "data-nascimento"?: (None::<String>),
+        ("id".to_string())?: idade,
+        nulo: null,
+    })
+    .unwrap();
+
+    assert_eq!(
+        patch,
+        json_safe!({ nome: "Ana", id: 30, nulo: null }).unwrap()
+    );
+    // Serializado por referência: a variável continua disponível
+    assert!(apelido.is_none());
+
+    // Variáveis internas da macro não capturam as do chamador
+    let item = 3;
+    assert_eq!(
+        json_safe!({ (format!("k{}", item))?: Some(10) }).unwrap(),
+        json_safe!({ k3: 10 }).unwrap()
+    );
+    #[cfg(feature = "proc-macro")]
+    assert_eq!(
+        json_safe_pm!({ (format!("k{}", item))?: Some(10) }).unwrap(),
+        json_safe!({ k3: 10 }).unwrap()
+    );
+}
+
+// Campos omitidos por predicado: chave: skip_if!(expr, |v| ...)
+#[test]
+fn skip_if_fields() {
+    let nome = String::new();
+    let tags: Vec<&str> = vec!["a"];
+    let saldo = 0;
+    let doc = json_safe!({
+        nome: skip_if!(nome, |v| v.is_empty()),
+        tags: skip_if!(tags, |v| v.is_empty()),
+        "saldo-atual": skip_if!(saldo, |v| *v == 0),
+        ("total".to_string()): skip_if!(saldo + 5, |v| *v == 0),
+        fim: true,
+    })
+    .unwrap();
+    assert_eq!(
+        doc,
+        json_safe!({ tags: ["a"], total: 5, fim: true }).unwrap()
+    );
+    // Serializado por referência: a variável continua disponível
+    assert!(nome.is_empty());
+
+    fn nunca(_: &std::collections::HashMap<Vec<i32>, i32>) -> bool {
+        false
+    }
+    let falha = std::collections::HashMap::from([(vec![1], 1)]);
+    assert_eq!(
+        json_safe!({ a: { b: skip_if!(falha, nunca) } })
+            .unwrap_err()
+            .path(),
+        "a.b"
+    );
+
+    #[cfg(feature = "proc-macro")]
+    assert_eq!(
+        json_safe_pm!({
+            nome: skip_if!(nome, |v| v.is_empty()),
+            total: skip_if!(saldo + 5, |v| *v == 0),
+        })
+        .unwrap(),
+        json_safe!({ total: 5 }).unwrap()
+    );
+
+    // O predicado pode capturar uma variável `item` do chamador
+    let item = 2;
+    assert_eq!(
+        json_safe!({ tags: skip_if!(tags, |v| v.len() < item) }).unwrap(),
+        json_safe!({}).unwrap()
+    );
+    #[cfg(feature = "proc-macro")]
+    assert_eq!(
+        json_safe_pm!({ tags: skip_if!(tags, |v| v.len() < item) }).unwrap(),
+        json_safe!({}).unwrap()
+    );
+}
+
+// Campos condicionais: if cond => chave: valor
+#[test]
+fn conditional_fields() {
+    let usuario = |admin: bool, email: Option<&str>| {
+        json_safe!({
+            name: "ana",
+            if admin => role: "admin",
+            if admin && email.is_some() => "admin-email"?: email,
+            if !admin => ..json_safe!({ quota: 10 }).unwrap(),
+            if admin => perms: ["read", "write"],
+        })
+        .unwrap()
+    };
+
+    assert_eq!(
+        usuario(true, Some("a@x.com")),
+        json_safe!({ name: "ana", role: "admin", "admin-email": "a@x.com", perms: ["read", "write"] })
+            .unwrap()
+    );
+    assert_eq!(
+        usuario(false, Some("a@x.com")),
+        json_safe!({ name: "ana", quota: 10 }).unwrap()
+    );
+
+    // Valor com vírgula fora de delimitadores: entre parênteses no json_safe!
+    fn par<A: Default, B: Default>() -> (A, B) {
+        Default::default()
+    }
+    let soma = |a: i32, b: i32| a + b;
+    let aplicar = |f: fn(i32, i32) -> i32| f(2, 3);
+    assert_eq!(
+        json_safe!({
+            if true => p: (par::<u8, bool>()),
+            if true => s: (soma(1, 2)),
+            if true => f: (aplicar(|a, b| a * b)),
+            fim: 1,
+        })
+        .unwrap(),
+        json_safe!({ p: [0, false], s: 3, f: 6, fim: 1 }).unwrap()
+    );
+    #[cfg(feature = "proc-macro")]
+    assert_eq!(
+        json_safe_pm!({ if true => p: par::<u8, bool>(), fim: 1 }).unwrap(),
+        json_safe!({ p: [0, false], fim: 1 }).unwrap()
+    );
+}
+
+// Compreensão em arrays: [ for u in users => { ... } ]
+#[test]
+fn array_comprehension() {
+    struct Usuario {
+        id: u32,
+        name: String,
+        tags: Vec<&'static str>,
+    }
+    let users = vec![
+        Usuario {
+            id: 1,
+            name: "ana".into(),
+            tags: vec!["a"],
+        },
+        Usuario {
+            id: 2,
+            name: "bia".into(),
+            tags: vec![],
+        },
+    ];
+
+    let arr = json_safe!([
+        for u in &users => { id: u.id, name: &u.name, "n-tags": u.tags.len() },
+        "fim",
+    ])
+    .unwrap();
+    assert_eq!(
+        arr,
+        json_safe!([
+            { id: 1, name: "ana", "n-tags": 1 },
+            { id: 2, name: "bia", "n-tags": 0 },
+            "fim",
+        ])
+        .unwrap()
+    );
+    assert_eq!(
+        json_safe!([0, for (i, u) in users.iter().enumerate() => i as u32 + u.id]).unwrap(),
+        json_safe!([0, 1, 3]).unwrap()
+    );
+    assert_eq!(
+        json_safe!({ ids: [for u in &users => u.id] }).unwrap()["ids"],
+        json_safe!([1, 2]).unwrap()
+    );
+
+    // Erro em um elemento interrompe a construção
+    let mut falha = std::collections::HashMap::new();
+    falha.insert(vec![1], 1);
+    let mapas = [falha];
+    assert!(json_safe!([for m in &mapas => { m }]).is_err());
+}
+
+// Objeto a partir de pares (chave, valor)
+#[test]
+fn object_from_pairs_iter() {
+    let contagens = vec![("a", 1), ("b", 2)];
+    assert_eq!(
+        object_from_pairs(contagens).unwrap(),
+        json_safe!({ a: 1, b: 2 }).unwrap()
+    );
+
+    let por_id = std::collections::BTreeMap::from([(1, "ana"), (2, "bia")]);
+    let obj =
+        object_from_pairs(por_id.iter().map(|(id, nome)| (format!("user_{id}"), nome))).unwrap();
+    assert_eq!(obj, json_safe!({ user_1: "ana", user_2: "bia" }).unwrap());
+    assert_eq!(
+        object_from_pairs(Vec::<(String, u8)>::new()).unwrap(),
+        json_safe!({}).unwrap()
+    );
+
+    let mut falha = std::collections::HashMap::new();
+    falha.insert(vec![1], 1);
+    let erro = object_from_pairs(vec![
+        ("ok", std::collections::HashMap::new()),
+        ("ruim", falha),
+    ])
+    .unwrap_err();
+    assert!(erro.to_string().starts_with("\"ruim\": "), "{erro}");
+}
+
+// Repetição em arrays: [valor; n]
+#[test]
+fn array_repetition() {
+    let linha = json_safe!({ id: null, valor: 0 }).unwrap();
+    let esperado = linha.clone();
+    let tabela = json_safe!([linha; 3]).unwrap();
+    assert_eq!(tabela.as_array().unwrap().len(), 3);
+    assert!(tabela.as_array().unwrap().iter().all(|l| *l == esperado));
+
+    assert_eq!(
+        json_safe!([{ ok: true }; 2]).unwrap(),
+        json_safe!([{ ok: true }, { ok: true }]).unwrap()
+    );
+    assert_eq!(json_safe!([0; 0]).unwrap(), json_safe!([]).unwrap());
+    let n = 2;
+    assert_eq!(
+        json_safe!({ pad: ["x".repeat(2); n + 1] }).unwrap()["pad"],
+        json_safe!(["xx", "xx", "xx"]).unwrap()
+    );
+    // Serializado uma única vez
+    let chamadas = std::cell::Cell::new(0);
+    let contar = || {
+        chamadas.set(chamadas.get() + 1);
+        "v"
+    };
+    json_safe!([contar(); 4]).unwrap();
+    assert_eq!(chamadas.get(), 1);
+}
+
+// Array
+#[test]
+fn array() {
+    let arr = json_safe!([
+        1,
+        "dois",
+        false,
+        { inner: "ok" },
+        null,
+    ]);
+
+    match arr {
+        Ok(Value::Array(vec)) => {
+            assert_eq!(vec.len(), 5);
+            assert_eq!(vec[0], Value::from(1));
+            assert_eq!(vec[1], Value::from("dois"));
+            assert_eq!(vec[2], Value::from(false));
+
+            match &vec[3] {
+                Value::Object(inner) => {
+                    assert_eq!(inner.get("inner"), Some(&Value::from("ok")));
+                }
+                _ => panic!("elemento 3 do array não é objeto"),
+            }
+
+            assert!(matches!(vec[4], Value::Null));
+        }
+        Ok(_) => panic!("arr não é um array JSON"),
+        Err(e) => panic!("json_safe! com array retornou erro: {e}"),
+    }
+}
+
+// null literal da macro
+#[test]
+fn null_literal() {
+    let n = json_safe!(null);
+
+    match n {
+        Ok(Value::Null) => {}
+        Ok(_) => panic!("null da macro não resultou em Value::Null"),
+        Err(e) => panic!("json_safe!(null) retornou erro: {e}"),
+    }
+}
+
+// Expressão genérica -> to_value (branch $other:expr)
+#[test]
+fn generic_expression() {
+    #[derive(serde::Serialize)]
+    struct MyStruct {
+        id: u32,
+        name: String,
+    }
+
+    let my_struct = MyStruct {
+        id: 7,
+        name: "bob".to_string(),
+    };
+
+    let expr_res = json_safe!(my_struct);
+
+    match expr_res {
+        Ok(Value::Object(map)) => {
+            assert_eq!(map.get("id"), Some(&Value::from(7)));
+            assert_eq!(map.get("name"), Some(&Value::from("bob")));
+        }
+        Ok(_) => panic!("expressão genérica não virou objeto como esperado"),
+        Err(e) => panic!("json_safe!(my_struct) retornou erro: {e}"),
+    }
+}
+
+// Composição aninhada de todos os tipos
+#[test]
+fn nested_composition() {
+    let complex = json_safe!({
+        meta: {
+            versao: 1,
+            descricao: "payload complexo",
+        },
+        dados: [
+            { id: 1, valor: 10 },
+            { id: 2, valor: 20 },
+            null,
+        ],
+        ok: true,
+    });
+
+    match complex {
+        Ok(Value::Object(map)) => {
+            // meta
+            match map.get("meta") {
+                Some(Value::Object(meta)) => {
+                    assert_eq!(meta.get("versao"), Some(&Value::from(1)));
+                    assert_eq!(
+                        meta.get("descricao"),
+                        Some(&Value::from("payload complexo"))
+                    );
+                }
+                _ => panic!("campo 'meta' inválido"),
+            }
+
+            // dados
+            match map.get("dados") {
+                Some(Value::Array(dados)) => {
+                    assert_eq!(dados.len(), 3);
+
+                    match &dados[0] {
+                        Value::Object(obj) => {
+                            assert_eq!(obj.get("id"), Some(&Value::from(1)));
+                            assert_eq!(obj.get("valor"), Some(&Value::from(10)));
+                        }
+                        _ => panic!("dados[0] inválido"),
+                    }
+
+                    match &dados[1] {
+                        Value::Object(obj) => {
+                            assert_eq!(obj.get("id"), Some(&Value::from(2)));
+                            assert_eq!(obj.get("valor"), Some(&Value::from(20)));
+                        }
+                        _ => panic!("dados[1] inválido"),
+                    }
+
+                    assert!(matches!(dados[2], Value::Null));
+                }
+                _ => panic!("campo 'dados' inválido"),
+            }
+
+            assert_eq!(map.get("ok"), Some(&Value::from(true)));
+        }
+        Ok(_) => panic!("complex não é objeto"),
+        Err(e) => panic!("json_safe! complexo retornou erro: {e}"),
+    }
+}
+
+// Versão proc-macro (feature "proc-macro"): mesma saída que json_safe!
+#[cfg(feature = "proc-macro")]
+#[test]
+fn proc_macro_equivalence() {
+    let map = [1, 2];
+    let pm = json_safe_pm!({
+        id: 7,
+        "content-type": "json",
+        r#type: null,
+        total: map.len() * 10,
+        itens: [map[0], -map[1], { fim: true }, ..map.iter().map(|x| x * 10)],
+        dobro: [for x in map => { x: x * 2 }],
+        zeros: [0u8; map.len()],
+        vazio: {},
+        (format!("k{}", map[0])): "calculada",
+        map,
+        ..json_safe_pm!({ spread: true }).unwrap(),
+        ausente?: None::<u8>,
+        presente?: map.first(),
+        if map.len() > 1 => condicional: { ok: true },
+        if map.is_empty() => nunca: 1,
+    })
+    .unwrap();
+    let declarativa = json_safe!({
+        "id": 7,
+        "content-type": "json",
+        r#type: null,
+        "total": 20,
+        "itens": [1, (-2), { "fim": true }, 10, 20],
+        "dobro": [{ "x": 2 }, { "x": 4 }],
+        "zeros": [0, 0],
+        "vazio": {},
+        "k1": "calculada",
+        "map": [1, 2],
+        "spread": true,
+        "presente": 1,
+        "condicional": { "ok": true },
+    })
+    .unwrap();
+    assert_eq!(pm, declarativa);
+    assert!(pm.as_object().unwrap().contains_key("type"));
+
+    assert_eq!(json_safe_pm!(null).unwrap(), Value::Null);
+    assert_eq!(json_safe_pm!([]).unwrap(), json_safe!([]).unwrap());
+    assert_eq!(json_safe_pm!("x".repeat(2)).unwrap(), Value::from("xx"));
+
+    let mut falha = std::collections::HashMap::new();
+    falha.insert(vec![1], 1);
+    assert!(json_safe_pm!({ ok: 1, falha: falha }).is_err());
+    assert!(json_safe_pm!({ ..[1, 2] }).is_err());
+
+    // Chave fixa repetida não compila ({ id: 1, id: 2 }); membros
+    // condicionais e chaves calculadas ainda podem sobrescrever
+    let sobrescrita = json_safe_pm!({ x: 1, if map.len() > 1 => x: 2, ("y"): 3, y: 4 }).unwrap();
+    assert_eq!(sobrescrita, json_safe!({ x: 2, y: 4 }).unwrap());
+
+    // null como elemento repetido e como valor comum
+    assert_eq!(
+        json_safe_pm!([null; 3]).unwrap(),
+        json_safe!([null; 3]).unwrap()
+    );
+    assert_eq!(
+        json_safe_pm!({ a: null, b: [null, 1] }).unwrap(),
+        json_safe!({ a: null, b: [null, 1] }).unwrap()
+    );
+}
+
+// Opções da raiz no json_safe_pm!: mesma saída e mesmos erros do json_safe!
+#[cfg(feature = "proc-macro")]
+#[test]
+fn proc_macro_options() {
+    let chave = "id";
+    assert_eq!(
+        json_safe_pm!(dotted { "a.b": 1, "a.c": [1, 2] }).unwrap(),
+        json_safe!(dotted { "a.b": 1, "a.c": [1, 2] }).unwrap()
+    );
+    assert_eq!(
+        json_safe_pm!(compact { a: 1, b: None::<u8>, c: [{ d: null }] }).unwrap(),
+        json_safe!(compact { a: 1, b: (None::<u8>), c: [{ d: null }] }).unwrap()
+    );
+    assert_eq!(
+        json_safe_pm!(compact [1, null]).unwrap(),
+        json_safe!(compact [1, null]).unwrap()
+    );
+    assert!(json_safe_pm!(strict { id: 1, nome: "a" }).is_ok());
+    assert_eq!(
+        json_safe_pm!(strict { id: 1, (chave): 2 })
+            .unwrap_err()
+            .to_string(),
+        json_safe!(strict { id: 1, (chave): 2 })
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        json_safe_pm!(nan_null { t: f64::NAN }).unwrap(),
+        json_safe!(nan_null { t: (f64::NAN) }).unwrap()
+    );
+    assert_eq!(
+        json_safe_pm!(nan_string { t: f64::INFINITY }).unwrap(),
+        json_safe!(nan_string { t: (f64::INFINITY) }).unwrap()
+    );
+    assert_eq!(
+        json_safe_pm!(int128_string { id: u128::MAX }).unwrap(),
+        json_safe!(int128_string { id: (u128::MAX) }).unwrap()
+    );
+    assert_eq!(
+        json_safe_pm!(strict_numbers { total: 0.1f32 }).is_err(),
+        json_safe!(strict_numbers { total: 0.1f32 }).is_err()
+    );
+
+    // all acumula os erros, também combinado com outra opção
+    let erros = json_safe_pm!(all {
+        a: f64::NAN,
+        b: 1,
+        c: f64::INFINITY
+    })
+    .unwrap_err();
+    assert_eq!(
+        erros.len(),
+        json_safe!(all {
+            a: (f64::NAN),
+            b: 1,
+            c: (f64::INFINITY)
+        })
+        .unwrap_err()
+        .len()
+    );
+    assert_eq!(erros.len(), 2);
+    assert_eq!(
+        json_safe_pm!(all nan_null { a: f64::NAN }).unwrap(),
+        json_safe!({ a: null }).unwrap()
+    );
+
+    // Nomes de opção como variáveis comuns continuam valendo
+    let all = 3;
+    let strict = [1];
+    assert_eq!(json_safe_pm!(all).unwrap(), Value::from(3));
+    assert_eq!(
+        json_safe_pm!([strict.len(), all]).unwrap(),
+        json_safe!([1, 3]).unwrap()
+    );
+}
+
+// Modo strict: chaves duplicadas viram erro
+#[test]
+fn strict_duplicate_keys() {
+    let base = json_safe!({ id: 1, nome: "a" }).unwrap();
+    let chave = "id";
+    assert!(json_safe!(strict { id: 1, nome: "a" }).is_ok());
+    assert_eq!(json_safe!(strict {}).unwrap(), json_safe!({}).unwrap());
+
+    let erro = json_safe!(strict { id: 1, (chave): 2 }).unwrap_err();
+    assert!(
+        erro.to_string().contains("chave duplicada: \"id\""),
+        "{erro}"
+    );
+    assert!(json_safe!(strict { ..base, nome: "b" }).is_err());
+    assert!(json_safe!(strict { x: 1, if true => x: 2 }).is_err());
+    assert!(json_safe!(strict { ..base, extra: true }).is_ok());
+
+    // Sem o opt-in, a última vence
+    assert_eq!(json_safe!({ id: 1, (chave): 2 }).unwrap()["id"], 2);
+}
+
+// Caminho do campo que falhou (JsonSafeError)
+#[test]
+fn error_path() {
+    let mut falha = std::collections::HashMap::new();
+    falha.insert(vec![1], 1);
+    let ok = 1;
+
+    let erro = json_safe!({ dados: [{ valor: 1 }, { valor: (&falha) }] }).unwrap_err();
+    assert_eq!(erro.path(), "dados[1].valor");
+    assert!(erro.to_string().starts_with("dados[1].valor: "), "{erro}");
+    assert_eq!(
+        erro.segments(),
+        [
+            PathSegment::Key("dados".into()),
+            PathSegment::Index(1),
+            PathSegment::Key("valor".into())
+        ]
+    );
+
+    let erro = json_safe!({ "content-type": [ok, ..[&falha]] }).unwrap_err();
+    assert_eq!(erro.path(), r#"["content-type"][1]"#);
+    let chave = String::from("k");
+    assert_eq!(
+        json_safe!({ (chave): { x?: Some(&falha) } })
+            .unwrap_err()
+            .path(),
+        "k.x"
+    );
+    assert_eq!(
+        json_safe!([for _ in 0..2 => (&falha)]).unwrap_err().path(),
+        "[0]"
+    );
+    assert_eq!(json_safe!(&falha).unwrap_err().path(), "");
+
+    // Interoperabilidade com serde_json::Error, mantendo o caminho na mensagem
+    fn montar(falha: &std::collections::HashMap<Vec<i32>, i32>) -> serde_json::Result<Value> {
+        Ok(json_safe!({ a: { b: falha } })?)
+    }
+    let convertido = montar(&falha).unwrap_err();
+    assert!(convertido.to_string().starts_with("a.b: "), "{convertido}");
+    let erro: JsonSafeError = serde_json::from_str::<Value>("{").unwrap_err().into();
+    assert_eq!(erro.path(), "");
+    assert!(std::error::Error::source(&erro).is_some());
+
+    #[cfg(feature = "proc-macro")]
+    assert_eq!(
+        json_safe_pm!({ dados: [{ valor: 1 }, { valor: &falha }] })
+            .unwrap_err()
+            .path(),
+        "dados[1].valor"
+    );
+}
+
+// Números sem perda (feature arbitrary-precision)
+#[cfg(feature = "arbitrary-precision")]
+#[test]
+#[allow(clippy::excessive_precision, clippy::approx_constant)]
+fn arbitrary_precision_numbers() {
+    let pi = "3.14159265358979323846264338327950288";
+    let grande: serde_json::Number = "123456789012345678901234567890.000000001".parse().unwrap();
+    let lido: Value = serde_json::from_str(pi).unwrap();
+
+    let doc = json_safe!({
+        preco: 1.10,
+        pi: 3.14159265358979323846264338327950288,
+        negativo: -0.000000000000000000012345678901234567890,
+        grande: (&grande),
+        lido: (&lido),
+        itens: [1e2, 10, 0x10, 1_000],
+    })
+    .unwrap();
+    assert_eq!(
+        doc.to_string(),
+        format!(
+            r#"{{"grande":{grande},"itens":[1e2,10,16,1000],"lido":{pi},"negativo":-0.000000000000000000012345678901234567890,"pi":{pi},"preco":1.10}}"#
+        )
+    );
+
+    #[cfg(feature = "proc-macro")]
+    assert_eq!(
+        json_safe_pm!({ preco: 1.10, pi: 3.14159265358979323846264338327950288, n: -7 }).unwrap(),
+        json_safe!({ preco: 1.10, pi: 3.14159265358979323846264338327950288, n: -7 }).unwrap()
+    );
+
+    // i128/u128 fora de i64/u64 saem como número exato, salvo política explícita
+    let ids = [u128::MAX];
+    let doc = json_safe!({ x: u128::MAX, minimo: i128::MIN, ids }).unwrap();
+    assert_eq!(
+        doc.to_string(),
+        format!(
+            r#"{{"ids":[{0}],"minimo":{1},"x":{0}}}"#,
+            u128::MAX,
+            i128::MIN
+        )
+    );
+    assert_eq!(
+        json_safe!(int128_string { x: u128::MAX }).unwrap()["x"],
+        Value::from(u128::MAX.to_string())
+    );
+    assert!(json_safe!(strict_numbers { x: u128::MAX }).is_err());
+}
+
+// Caminho serde dentro de structs (feature path-to-error)
+#[cfg(feature = "path-to-error")]
+#[test]
+fn serde_path_in_error() {
+    use std::collections::HashMap;
+
+    #[derive(serde::Serialize)]
+    struct Item {
+        sku: &'static str,
+        meta: HashMap<Vec<i32>, i32>,
+    }
+    #[derive(serde::Serialize)]
+    struct Pedido {
+        itens: Vec<Item>,
+    }
+
+    let pedido = Pedido {
+        itens: vec![
+            Item {
+                sku: "a",
+                meta: HashMap::new(),
+            },
+            Item {
+                sku: "b",
+                meta: HashMap::from([(vec![1], 1)]),
+            },
+        ],
+    };
+    let erro = json_safe!({ pedido: (&pedido) }).unwrap_err();
+    assert_eq!(erro.path(), "pedido.itens[1].meta");
+    assert_eq!(
+        json_safe::path_to_error::to_value(&pedido)
+            .unwrap_err()
+            .path(),
+        "itens[1].meta"
+    );
+
+    let ok = json_safe!({ pedido: { itens: [] } }).unwrap();
+    assert_eq!(ok["pedido"]["itens"], serde_json::json!([]));
+}
+
+// Modo all: todos os campos que falharam, de uma vez
+#[test]
+fn accumulate_errors() {
+    let mut falha = std::collections::HashMap::new();
+    falha.insert(vec![1], 1);
+
+    let erros = json_safe!(all {
+        ok: 1,
+        a: (&falha),
+        lista: [1, (&falha), { b: (&falha) }],
+        ..[1],
+    })
+    .unwrap_err();
+    let caminhos: Vec<String> = erros.iter().map(JsonSafeError::path).collect();
+    assert_eq!(caminhos, ["a", "lista[1]", "lista[2].b", ""]);
+    assert!(erros[3].to_string().contains("spread"));
+
+    assert_eq!(
+        json_safe!(all { ok: 1 }).unwrap(),
+        json_safe!({ ok: 1 }).unwrap()
+    );
+    assert_eq!(json_safe!(all [&falha, &falha]).unwrap_err().len(), 2);
+    assert_eq!(json_safe!(all strict { x: 1, x: 2 }).unwrap_err().len(), 1);
+
+    // Um json_safe! comum dentro do `all` continua parando no primeiro erro
+    // e não contamina a coleta externa
+    let erros = json_safe!(all {
+        tratado: (json_safe!({ x: &falha }).unwrap_or(Value::Null)),
+        ruim: (&falha),
+    })
+    .unwrap_err();
+    assert_eq!(erros.len(), 1);
+    assert_eq!(erros[0].path(), "ruim");
+}
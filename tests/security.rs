@@ -0,0 +1,772 @@
+use json_safe::{
+    audit, base64, canonical, cursor, digest, duplicate_keys, event_schema, feature, field_crypto,
+    geo_privacy, idempotency, json_safe, json_safe_tenant, jws, merkle, point, policy, profile,
+    pseudo_id, pseudonym, stream, tenant, tokenize, untrusted,
+};
+use serde_json::Value;
+
+// Log de auditoria encadeado (hash canônico)
+#[test]
+fn audit_chain() {
+    assert_eq!(
+        digest::hex(&digest::sha256(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        digest::hex(&digest::sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        digest::hex(&digest::sha256(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        )),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+
+    assert_eq!(
+        canonical::to_canonical_string(
+            &json_safe!({ "b": [1, { "d": null, "c": "x" }], "a": true }).unwrap()
+        ),
+        r#"{"a":true,"b":[1,{"c":"x","d":null}]}"#
+    );
+
+    let mut cadeia = audit::AuditChain::new();
+    let e1 = cadeia
+        .append(
+            "2024-05-01T12:00:00Z",
+            "admin@ex.com",
+            "user.update",
+            "users/42",
+            json_safe!({ campo: "email", antes: "a@ex.com", depois: "b@ex.com" }).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(e1["prev_hash"], Value::from(audit::GENESIS));
+    let e2 = cadeia
+        .append(
+            "2024-05-01T12:01:00Z",
+            "admin@ex.com",
+            "user.delete",
+            "users/42",
+            Value::Null,
+        )
+        .unwrap();
+    assert_eq!(e2["prev_hash"], Value::from(canonical::canonical_hash(&e1)));
+    assert_eq!(cadeia.head(), canonical::canonical_hash(&e2));
+    assert!(audit::verify_chain(&[e1.clone(), e2.clone()]).is_ok());
+
+    let mut adulterada = e1.clone();
+    adulterada["details"]["depois"] = Value::from("c@ex.com");
+    let falha = audit::verify_chain(&[adulterada, e2.clone()]).unwrap_err();
+    assert_eq!(falha.index, 1);
+    assert_eq!(
+        audit::verify_chain(std::slice::from_ref(&e2))
+            .unwrap_err()
+            .index,
+        0
+    );
+
+    let mut retomada = audit::AuditChain::resume(&e2).unwrap();
+    let e3 = retomada
+        .append(
+            "2024-05-01T12:02:00Z",
+            "job",
+            "purge",
+            "users",
+            json_safe!({}).unwrap(),
+        )
+        .unwrap();
+    assert!(audit::verify_chain(&[e1, e2.clone(), e3.clone()]).is_ok());
+    assert!(audit::verify_chain_from(&canonical::canonical_hash(&e2), &[e3]).is_ok());
+    assert!(audit::AuditChain::resume(&json_safe!({}).unwrap()).is_err());
+    assert!(cadeia.append(" ", "a", "b", "c", Value::Null).is_err());
+}
+
+// Criptografia por campo (JSON Pointer + cifra plugável)
+#[test]
+fn field_crypto() {
+    assert_eq!(base64::encode(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64::encode(b"fo"), "Zm8=");
+    assert_eq!(base64::decode("Zm8=").unwrap(), b"fo");
+    assert_eq!(base64::encode_url(&[0xfb, 0xff]), "-_8");
+    assert_eq!(base64::decode_url("-_8").unwrap(), vec![0xfb, 0xff]);
+    assert!(base64::decode("Zm*=").is_none());
+
+    struct Xor(u8);
+    impl field_crypto::Cipher for Xor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+            Ok(plaintext.iter().map(|b| b ^ self.0).collect())
+        }
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+            if ciphertext.is_empty() {
+                return Err("vazio".into());
+            }
+            self.encrypt(ciphertext)
+        }
+    }
+
+    let original = json_safe!({
+        "id": 7,
+        "cliente": { "nome": "Ana", "cpf": "123.456.789-00" },
+        "cartoes": [{ "numero": 4111111111111111u64, "bandeira": "visa" }],
+    })
+    .unwrap();
+    let caminhos = ["/cliente/cpf", "/cartoes/0/numero", "/inexistente"];
+    let mut doc = original.clone();
+    field_crypto::encrypt_paths(&mut doc, &caminhos, &Xor(0x5a)).unwrap();
+    let cpf = doc["cliente"]["cpf"].as_str().unwrap();
+    assert!(cpf.starts_with(field_crypto::TAG));
+    assert!(
+        doc["cartoes"][0]["numero"]
+            .as_str()
+            .unwrap()
+            .starts_with("enc:v1:")
+    );
+    assert_eq!(doc["cliente"]["nome"], Value::from("Ana"));
+
+    // Cifrar de novo não muda nada
+    let antes = doc.clone();
+    field_crypto::encrypt_paths(&mut doc, &caminhos, &Xor(0x5a)).unwrap();
+    assert_eq!(doc, antes);
+
+    field_crypto::decrypt_paths(&mut doc, &caminhos, &Xor(0x5a)).unwrap();
+    assert_eq!(doc, original);
+    assert!(field_crypto::decrypt_paths(&mut doc, &["/id"], &Xor(0x5a)).is_err());
+
+    let mut invalido = json_safe!({ "x": "enc:v1:" }).unwrap();
+    let msg = field_crypto::decrypt_paths(&mut invalido, &["/x"], &Xor(1))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(msg, "/x: vazio");
+}
+
+// Tokenização de PII preservando formato
+#[test]
+fn tokenize() {
+    use tokenize::{HmacTokenizer, TokenVault, Tokenizer};
+
+    assert_eq!(
+        digest::hex(&digest::hmac_sha256(
+            b"Jefe",
+            b"what do ya want for nothing?"
+        )),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+
+    let tokenizador = HmacTokenizer::new("segredo");
+    let email = tokenizador
+        .tokenize(&Value::from("Ana.Silva@ex.com"))
+        .unwrap();
+    let email = email.as_str().unwrap();
+    assert_eq!(email.len(), "Ana.Silva@ex.com".len());
+    assert_eq!(&email[3..4], ".");
+    assert_eq!(&email[9..10], "@");
+    assert!(email[0..1].chars().all(|c| c.is_ascii_uppercase()));
+    assert_eq!(
+        tokenizador
+            .tokenize(&Value::from("Ana.Silva@ex.com"))
+            .unwrap(),
+        Value::from(email)
+    );
+    assert_ne!(
+        HmacTokenizer::new("outra")
+            .tokenize(&Value::from("Ana.Silva@ex.com"))
+            .unwrap(),
+        Value::from(email)
+    );
+
+    // Letras acentuadas não passam em claro
+    let nome = tokenizador.tokenize(&Value::from("João Ñúñez")).unwrap();
+    let nome = nome.as_str().unwrap();
+    assert_eq!(nome.chars().count(), "João Ñúñez".chars().count());
+    assert!(
+        nome.chars().all(|c| c.is_ascii_alphabetic() || c == ' '),
+        "{nome}"
+    );
+    assert!(nome.starts_with(|c: char| c.is_ascii_uppercase()));
+    assert_eq!(
+        nome.chars().nth(5).map(|c| c.is_ascii_uppercase()),
+        Some(true)
+    );
+
+    let numero = tokenizador.tokenize(&Value::from(-12345)).unwrap();
+    assert!(numero.as_i64().unwrap() <= -10000 && numero.as_i64().unwrap() > -100000);
+    let grande = tokenizador.tokenize(&Value::from(u64::MAX)).unwrap();
+    assert!(grande.as_u64().unwrap().to_string().len() == 20);
+    // Token de 20 dígitos começando em 19..., acima de u64::MAX mesmo com o
+    // primeiro dígito forçado para 1: continua inteiro, com 20 dígitos
+    let perto = tokenizador.tokenize(&Value::from(u64::MAX - 3)).unwrap();
+    assert!(perto.as_u64().unwrap() >= 10u64.pow(19), "{perto}");
+    assert_eq!(
+        tokenizador.tokenize(&Value::from(u64::MAX - 3)).unwrap(),
+        perto
+    );
+    assert!(tokenizador.tokenize(&Value::from(1.5)).is_err());
+
+    let original = json_safe!({
+        "usuario": { "email": "ana@ex.com", "telefone": "+55 11 91234-5678", "idade": 31 },
+        "pedidos": [{ "cliente_id": 90210 }],
+        "apelido": null,
+    })
+    .unwrap();
+    let caminhos = [
+        "/usuario/email",
+        "/usuario/telefone",
+        "/pedidos/0/cliente_id",
+        "/apelido",
+        "/nao/existe",
+    ];
+    let mut cofre = tokenize::MemoryVault::new();
+    let mut doc = original.clone();
+    tokenize::tokenize_paths(&mut doc, &caminhos, &tokenizador, Some(&mut cofre)).unwrap();
+    assert_eq!(cofre.len(), 3);
+    assert!(!cofre.is_empty());
+    assert_ne!(doc["usuario"]["email"], original["usuario"]["email"]);
+    assert!(doc["pedidos"][0]["cliente_id"].is_u64());
+    assert_eq!(doc["usuario"]["idade"], Value::from(31));
+    assert_eq!(
+        cofre.lookup(&doc["usuario"]["email"]),
+        Some(Value::from("ana@ex.com"))
+    );
+
+    // Determinístico: o mesmo documento gera os mesmos tokens, mesmo sem cofre
+    let mut outro = original.clone();
+    tokenize::tokenize_paths(&mut outro, &caminhos, &tokenizador, None).unwrap();
+    assert_eq!(outro, doc);
+
+    tokenize::detokenize_paths(&mut doc, &caminhos, &cofre).unwrap();
+    assert_eq!(doc, original);
+    assert!(tokenize::detokenize_paths(&mut doc, &["/usuario/idade"], &cofre).is_err());
+    assert!(tokenize::tokenize_paths(&mut doc, &["/usuario"], &tokenizador, None).is_err());
+
+    // Colisão: dois originais com o mesmo token não sobrescrevem o cofre
+    struct Constante;
+    impl Tokenizer for Constante {
+        fn tokenize(&self, _: &Value) -> Result<Value, String> {
+            Ok(Value::from("x"))
+        }
+    }
+    let mut cofre = tokenize::MemoryVault::new();
+    let mut doc = json_safe!({ a: "p", b: "p", c: "q" }).unwrap();
+    let erro =
+        tokenize::tokenize_paths(&mut doc, &["/a", "/b", "/c"], &Constante, Some(&mut cofre))
+            .unwrap_err();
+    assert!(erro.to_string().starts_with("/c: colisão"), "{erro}");
+    assert_eq!(cofre.lookup(&Value::from("x")), Some(Value::from("p")));
+}
+
+// JWS compacto (HS256)
+#[test]
+fn jws() {
+    // Token de exemplo do jwt.io, gerado fora deste crate
+    let externo = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+               eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+               SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+    let hs = jws::Hs256::new("your-256-bit-secret");
+    let claims = jws::verify(externo, &hs).unwrap();
+    assert_eq!(claims["name"], Value::from("John Doe"));
+    assert!(jws::verify(externo, &jws::Hs256::new("outro")).is_err());
+
+    let claims = json_safe!({ "sub": "u-1", "iat": 1700000000, "roles": ["admin"] }).unwrap();
+    let token = jws::sign(&claims, &hs).unwrap();
+    assert!(token.starts_with("eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9."));
+    assert_eq!(token.matches('.').count(), 2);
+    assert!(!token.contains('='));
+    assert_eq!(jws::verify(&token, &hs).unwrap(), claims);
+
+    let mut adulterado = token.clone();
+    adulterado.insert(token.find('.').unwrap() + 2, 'x');
+    assert!(jws::verify(&adulterado, &hs).is_err());
+    assert!(jws::verify("a.b", &hs).is_err());
+    assert!(jws::sign(&json_safe!([1]).unwrap(), &hs).is_err());
+
+    // Header com alg "none" é rejeitado mesmo sem assinatura
+    let none = format!(
+        "{}.{}.",
+        base64::encode_url(br#"{"alg":"none"}"#),
+        base64::encode_url(br#"{"sub":"x"}"#)
+    );
+    assert_eq!(
+        jws::verify(&none, &hs).unwrap_err().to_string(),
+        "alg não suportado: none"
+    );
+}
+
+// Ids pseudônimos estáveis
+#[test]
+fn pseudo_id() {
+    assert!(json_safe!([(pseudo_id!(42, "analytics"))]).is_err());
+    pseudonym::set_key("chave-de-teste");
+
+    let evento = json_safe!({
+        "user": (pseudo_id!(42, "analytics")),
+        "mesmo_user": (pseudo_id!("42", "analytics")),
+        "outro_ns": (pseudo_id!(42, "billing")),
+    })
+    .unwrap();
+    let pseudo = evento["user"].as_str().unwrap();
+    assert_eq!(pseudo.len(), 32);
+    assert!(pseudo.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(evento["mesmo_user"], evento["user"]);
+    assert_ne!(evento["outro_ns"], evento["user"]);
+    assert_eq!(
+        pseudonym::pseudonymize(b"chave-de-teste", &Value::from(42), "analytics").unwrap(),
+        pseudo
+    );
+    assert_ne!(
+        pseudonym::pseudonymize(b"outra", &Value::from(42), "analytics").unwrap(),
+        pseudo
+    );
+    assert!(json_safe!([(pseudo_id!(true, "x"))]).is_err());
+
+    // Debug não mostra o id real
+    assert_eq!(
+        format!("{:?}", pseudo_id!("ana@example.com", "analytics")),
+        "PseudoId { namespace: \"analytics\", .. }"
+    );
+}
+
+// Arredondamento de coordenadas (privacidade)
+#[test]
+fn geo_privacy() {
+    use geo_privacy::GeoFuzz;
+
+    let mut doc = json_safe!({
+    "checkin": { "lat": (-23.561684), "lng": (-46.655981), "nome": "Paulista" },
+    "rota": { "type": "LineString", "coordinates": [[(-46.6333), (-23.5505), 760], [(-46.64), (-23.55)]] },
+    "ultimo": [(-46.655981), (-23.561684)],
+})
+.unwrap();
+    geo_privacy::fuzz_locations(
+        &mut doc,
+        &["/checkin", "/rota", "/ultimo", "/ausente"],
+        GeoFuzz::Round(2),
+    )
+    .unwrap();
+    assert_eq!(doc["checkin"]["lat"], Value::from(-23.56));
+    assert_eq!(doc["checkin"]["lng"], Value::from(-46.66));
+    assert_eq!(doc["checkin"]["nome"], Value::from("Paulista"));
+    assert_eq!(
+        doc["rota"]["coordinates"][0],
+        json_safe!([(-46.63), (-23.55), 760]).unwrap()
+    );
+    assert_eq!(doc["ultimo"], json_safe!([(-46.66), (-23.56)]).unwrap());
+
+    let mut ponto = json_safe!({ "latitude": 10.26, "longitude": (-0.74) }).unwrap();
+    geo_privacy::fuzz_locations(&mut ponto, &[""], GeoFuzz::Grid(0.5)).unwrap();
+    assert_eq!(
+        ponto,
+        json_safe!({ "latitude": 10.25, "longitude": (-0.75) }).unwrap()
+    );
+
+    let mut feicao = feature!(point!(1.23456, 2.34567).unwrap()).unwrap();
+    geo_privacy::fuzz_locations(&mut feicao, &["/geometry"], GeoFuzz::Round(1)).unwrap();
+    assert_eq!(
+        feicao["geometry"]["coordinates"],
+        json_safe!([1.2, 2.3]).unwrap()
+    );
+
+    // Nos polos e no antimeridiano o centro da célula continua válido
+    let mut extremos = json_safe!({
+        "polo": { "lat": 90, "lon": 180 },
+        "sul": [(-180), (-90)],
+        "leste": [179.9, 0.1],
+    })
+    .unwrap();
+    geo_privacy::fuzz_locations(
+        &mut extremos,
+        &["/polo", "/sul", "/leste"],
+        GeoFuzz::Grid(1.0),
+    )
+    .unwrap();
+    assert_eq!(
+        extremos,
+        json_safe!({
+            "polo": { "lat": 90.0, "lon": (-179.5) },
+            "sul": [(-179.5), (-89.5)],
+            "leste": [179.5, 0.5],
+        })
+        .unwrap()
+    );
+
+    assert!(geo_privacy::fuzz_locations(&mut doc, &["/checkin/nome"], GeoFuzz::Round(2)).is_err());
+    assert!(geo_privacy::fuzz_locations(&mut doc, &[], GeoFuzz::Grid(0.0)).is_err());
+}
+
+// Perfis de saída por público (mascaramento por padrão)
+#[test]
+fn render_profiles() {
+    use profile::{MASK, Profile, Render, Rules};
+
+    let pedido = json_safe!({
+        id: 10,
+        customer: { name: "Ana", email: "ana@example.com", cpf: "123.456.789-00" },
+        items: [{ sku: "A1", cost: 3 }, { sku: "B2", cost: 4 }],
+        internal_notes: "cliente VIP",
+    })
+    .unwrap();
+
+    // Sem configuração: internal passa tudo, public mascara tudo
+    assert_eq!(pedido.render(Profile::Internal), pedido);
+    assert_eq!(pedido.render(Profile::Public)["id"], Value::from(MASK));
+    assert_eq!(
+        pedido.render(Profile::Public)["items"][1]["sku"],
+        Value::from(MASK)
+    );
+
+    profile::set_rules(
+        Profile::Public,
+        Rules::mask_by_default()
+            .allow("/id")
+            .allow("/items/*/sku")
+            .allow("/customer")
+            .redact("/customer/cpf")
+            .drop("/internal_notes")
+            .rename("/customer/name", "display_name"),
+    );
+    profile::set_rules(
+        Profile::Partner,
+        Rules::new().drop("/internal_notes").redact("/customer"),
+    );
+
+    assert_eq!(
+        pedido.render(Profile::Public),
+        json_safe!({
+            id: 10,
+            customer: { display_name: "Ana", email: "ana@example.com", cpf: MASK },
+            items: [{ sku: "A1", cost: MASK }, { sku: "B2", cost: MASK }],
+        })
+        .unwrap()
+    );
+    let parceiro = pedido.render(Profile::Partner);
+    assert_eq!(parceiro["customer"], Value::from(MASK));
+    assert_eq!(parceiro["items"], pedido["items"]);
+    assert!(parceiro.get("internal_notes").is_none());
+}
+
+// Visões por papel (políticas de acesso por caminho)
+#[test]
+fn access_policy() {
+    let politica = policy::Policy::new()
+        .allow("admin", "/internal/**")
+        .allow("admin", "/users/*/email")
+        .allow("support", "/users/*/email")
+        .deny("support", "/users/*/email/verified");
+    let doc = json_safe!({
+        id: 7,
+        internal: { cost: 3, notes: ["x"] },
+        users: [{ name: "ana", email: { addr: "a@x", verified: true } }],
+    })
+    .unwrap();
+
+    assert_eq!(politica.view(&doc, &["admin"]), doc);
+    assert_eq!(
+        politica.view(&doc, &["guest"]),
+        json_safe!({ id: 7, users: [{ name: "ana" }] }).unwrap()
+    );
+    assert_eq!(
+        politica.view(&doc, &["support"]),
+        json_safe!({ id: 7, users: [{ name: "ana", email: { addr: "a@x" } }] }).unwrap()
+    );
+    assert_eq!(
+        politica.view(&doc, &["support", "admin"])["internal"]["cost"],
+        3
+    );
+
+    assert!(politica.can_read(&["admin"], "/internal/deep/er"));
+    assert!(!politica.can_read(&[], "/internal"));
+    assert!(politica.can_read(&[], "/id"));
+}
+
+// Chave de idempotência derivada do conteúdo
+#[test]
+fn idempotency_key() {
+    use idempotency::KeyPolicy;
+
+    let pedido = json_safe!({
+        cliente: 7,
+        itens: [{ sku: "A1", qtd: 2 }],
+        enviado_em: "2024-05-01T12:00:00Z",
+        request_id: "r-1",
+    })
+    .unwrap();
+    let reenvio = json_safe!({
+        request_id: "r-2",
+        enviado_em: "2024-05-01T12:00:05Z",
+        itens: [{ qtd: 2, sku: "A1" }],
+        cliente: 7,
+    })
+    .unwrap();
+
+    let inteiro = idempotency::idempotency_key(&pedido, KeyPolicy::Whole);
+    assert_eq!(inteiro, canonical::canonical_hash(&pedido));
+    assert_eq!(inteiro.len(), 64);
+    assert_ne!(
+        inteiro,
+        idempotency::idempotency_key(&reenvio, KeyPolicy::Whole)
+    );
+
+    let volateis = KeyPolicy::Excluding(&["/enviado_em", "/request_id", "/nao/existe"]);
+    assert_eq!(
+        idempotency::idempotency_key(&pedido, volateis),
+        idempotency::idempotency_key(&reenvio, volateis)
+    );
+    let caminhos = KeyPolicy::Paths(&["/cliente", "/itens"]);
+    let chave = idempotency::idempotency_key(&pedido, caminhos);
+    assert_eq!(chave, idempotency::idempotency_key(&reenvio, caminhos));
+    let outro = json_safe!({ cliente: 8, itens: [{ sku: "A1", qtd: 2 }] }).unwrap();
+    assert_ne!(chave, idempotency::idempotency_key(&outro, caminhos));
+    // Ausente conta como null, sem colidir com a seleção completa
+    assert_ne!(
+        idempotency::idempotency_key(&json_safe!({ cliente: 7 }).unwrap(), caminhos),
+        chave
+    );
+}
+
+// Injeção e validação do tenant
+#[test]
+fn tenant_isolation() {
+    assert!(json_safe_tenant!({ x: 1 }).is_err());
+
+    let guard = tenant::enter("acme");
+    let doc = json_safe_tenant!({ pedido: 1 }).unwrap();
+    assert_eq!(doc["tenant_id"], "acme");
+    assert!(tenant::validate(&doc).is_ok());
+    assert!(tenant::validate(&json_safe!({ pedido: 1 }).unwrap()).is_err());
+    assert!(json_safe_tenant!({ tenant_id: "outro" }).is_err());
+
+    {
+        let _interno = tenant::enter("globex");
+        assert!(tenant::validate(&doc).is_err());
+    }
+    assert_eq!(tenant::current().as_deref(), Some("acme"));
+
+    tenant::set_tenant_path("/meta/tenant");
+    let doc = json_safe_tenant!({ meta: { origem: "api" } }).unwrap();
+    assert_eq!(
+        doc["meta"],
+        json_safe!({ origem: "api", tenant: "acme" }).unwrap()
+    );
+    assert!(tenant::validate(&doc).is_ok());
+    tenant::set_tenant_path("/tenant_id");
+
+    // Outras threads não herdam o tenant
+    std::thread::spawn(|| assert!(tenant::current().is_none()))
+        .join()
+        .unwrap();
+    drop(guard);
+    assert!(tenant::current().is_none());
+}
+
+// Cursores de paginação (canônicos, opcionalmente assinados)
+#[test]
+fn pagination_cursors() {
+    let estado = json_safe!({ sort: "id", after: 42 }).unwrap();
+    let cursor = cursor::encode(&estado);
+    assert_eq!(cursor, base64::encode_url(br#"{"after":42,"sort":"id"}"#));
+    assert_eq!(
+        cursor,
+        cursor::encode(&json_safe!({ after: 42, sort: "id" }).unwrap())
+    );
+    assert_eq!(cursor::decode(&cursor).unwrap(), estado);
+    assert!(cursor::decode("não é base64").is_err());
+
+    let segredo = b"segredo";
+    let assinado = cursor::encode_signed(&estado, segredo);
+    assert!(assinado.starts_with(&format!("{cursor}.")));
+    assert_eq!(cursor::decode_signed(&assinado, segredo).unwrap(), estado);
+
+    let adulterado = assinado.replacen(
+        &cursor,
+        &cursor::encode(&json_safe!({ sort: "id", after: 0 }).unwrap()),
+        1,
+    );
+    assert!(cursor::decode_signed(&adulterado, segredo).is_err());
+    assert!(cursor::decode_signed(&assinado, b"outro").is_err());
+    assert!(cursor::decode_signed(&cursor, segredo).is_err());
+}
+
+// Árvore de Merkle (verificação parcial de subárvores)
+#[test]
+fn merkle_subtrees() {
+    let doc = json_safe!({
+        id: 7,
+        "a/b": { x: 1 },
+        chunks: [{ dados: [1, 2, 3] }, { dados: [4, 5] }, { dados: [] }],
+    })
+    .unwrap();
+    let arvore = merkle::merkle(&doc);
+    let raiz = arvore.root();
+    assert_eq!(raiz, merkle::subtree_hash(&doc));
+    assert_eq!(
+        arvore.hash("/chunks/1").unwrap(),
+        merkle::subtree_hash(&doc["chunks"][1])
+    );
+    assert_eq!(arvore.hash(""), Some(raiz.clone()));
+    assert!(arvore.hash("/chunks/9").is_none());
+    assert_ne!(
+        merkle::subtree_hash(&json_safe!([1, [2]]).unwrap()),
+        merkle::subtree_hash(&json_safe!([[1], 2]).unwrap())
+    );
+
+    let prova = arvore.proof("/chunks/1").unwrap();
+    let pedaco = json_safe!({ dados: [4, 5] }).unwrap();
+    assert!(merkle::verify(&pedaco, &prova, &raiz));
+    assert!(!merkle::verify(
+        &json_safe!({ dados: [4, 6] }).unwrap(),
+        &prova,
+        &raiz
+    ));
+
+    // A prova viaja como JSON junto com o pedaço
+    let recebida = merkle::Proof::from_value(&prova.to_value()).unwrap();
+    assert_eq!(recebida, prova);
+    assert_eq!(recebida.pointer(), "/chunks/1");
+    assert!(merkle::verify(&pedaco, &recebida, &raiz));
+
+    let prova = arvore.proof("/a~1b/x").unwrap();
+    assert!(merkle::verify(&Value::from(1), &prova, &raiz));
+    assert!(!merkle::verify(
+        &Value::from(1),
+        &prova,
+        &arvore.hash("/a~1b").unwrap()
+    ));
+    assert!(arvore.proof("/id/x").is_none());
+    assert!(merkle::Proof::from_value(&json_safe!({ pointer: "/a" }).unwrap()).is_err());
+}
+
+// Validação em streaming de entrada não confiável
+#[test]
+fn stream_validation() {
+    use event_schema::{EventSchema, FieldType};
+    use stream::{Limits, validate_stream};
+
+    let limites = Limits::new().max_depth(3).max_string_len(8).max_entries(3);
+    let ok = br#"{ "a": [1, 2.5, -3e2], "b": { "c": "\u00e9\ud83d\ude00" }, "d": null }"#;
+    assert!(validate_stream(&ok[..], &limites, None).is_ok());
+
+    // Entradas infinitas são recusadas logo no limite, sem serem lidas inteiras
+    let erro = validate_stream(std::io::repeat(b'['), &limites, None).unwrap_err();
+    assert!(
+        erro.to_string().contains("profundidade máxima (3)"),
+        "{erro}"
+    );
+    let erro =
+        validate_stream(std::io::repeat(b' '), &Limits::new().max_bytes(1000), None).unwrap_err();
+    assert!(erro.to_string().contains("excede 1000 bytes"), "{erro}");
+
+    let erro =
+        validate_stream(&b"{\n  \"nome\": \"muito comprido\"}"[..], &limites, None).unwrap_err();
+    assert!(
+        erro.to_string().contains("na linha 2, coluna 11 (byte 12)"),
+        "{erro}"
+    );
+    for invalido in [
+        &b"[1, 2, 3, 4]"[..],
+        b"{\"a\" 1}",
+        b"[1,]",
+        b"[01]",
+        b"\"\\x\"",
+        b"nul",
+        b"{} {}",
+        b"\"\xff\"",
+        b"[1",
+        &[b'9'; 101],
+    ] {
+        assert!(
+            validate_stream(invalido, &Limits::new().max_entries(3), None).is_err(),
+            "{}",
+            String::from_utf8_lossy(invalido)
+        );
+    }
+
+    let schema = EventSchema::new()
+        .required("id", FieldType::Integer)
+        .optional("tags", FieldType::Array);
+    let valida = |json: &str| validate_stream(json.as_bytes(), &Limits::new(), Some(&schema));
+    assert!(valida(r#"{ "id": 1, "tags": ["a", { "x": 1 }] }"#).is_ok());
+    assert!(valida(r#"{ "id": 1, "tags": null }"#).is_ok());
+    let erro = valida(r#"{ "id": 1.5 }"#).unwrap_err();
+    assert!(
+        erro.to_string().contains("campo id deve ser integer"),
+        "{erro}"
+    );
+    let erro = valida(r#"{ "tags": [] }"#).unwrap_err();
+    assert!(
+        erro.to_string().contains("campo obrigatório ausente: id"),
+        "{erro}"
+    );
+    assert!(valida(r#"{ "id": 1, "extra": 0 }"#).is_err());
+    assert!(valida("[]").is_err());
+}
+
+// Parse endurecido de entrada da internet
+#[test]
+fn untrusted_parse() {
+    use stream::Limits;
+    use untrusted::{UntrustedProfile, parse_untrusted};
+
+    let perfil = UntrustedProfile::default();
+    let entrada = br#"{ "a": [1, "x", { "b": null }], "c": -2.5e3 }"#;
+    assert_eq!(
+        parse_untrusted(entrada, &perfil).unwrap(),
+        serde_json::from_slice::<Value>(entrada).unwrap()
+    );
+
+    let erro = parse_untrusted(b"{\"id\": 1,\n \"id\": 2}", &perfil).unwrap_err();
+    assert!(
+        erro.to_string()
+            .contains("chave duplicada \"id\" em /id na linha 2, coluna 2 (byte 11)"),
+        "{erro}"
+    );
+    let ultima = perfil.reject_duplicate_keys(false);
+    assert_eq!(
+        parse_untrusted(br#"{"id": 1, "id": 2}"#, &ultima).unwrap()["id"],
+        2
+    );
+
+    let fundo = "[".repeat(33) + &"]".repeat(33);
+    assert!(parse_untrusted(fundo.as_bytes(), &perfil).is_err());
+    assert!(parse_untrusted(&[b'1'; 41], &perfil).is_err());
+    let longa = format!("\"{}\"", "x".repeat(65 << 10));
+    assert!(parse_untrusted(longa.as_bytes(), &perfil).is_err());
+    let apertado = UntrustedProfile::new().limits(Limits::new().max_bytes(8));
+    let erro = parse_untrusted(br#"{"a": 12345}"#, &apertado).unwrap_err();
+    assert!(erro.to_string().contains("excede 8 bytes"), "{erro}");
+    assert!(parse_untrusted(b"", &perfil).is_err());
+    assert!(parse_untrusted(b"[] x", &perfil).is_err());
+}
+
+// Chaves duplicadas no parse (erro ou aviso)
+#[test]
+fn duplicate_keys() {
+    use duplicate_keys::{DuplicateKey, parse_strict, parse_warn};
+
+    let entrada = br#"{"user": {"role": "guest", "role": "admin"}, "itens": [{"id": 1, "id": 2}]}"#;
+    assert_eq!(
+        serde_json::from_slice::<Value>(entrada).unwrap()["user"]["role"],
+        "admin"
+    );
+    let erro = parse_strict(entrada).unwrap_err();
+    assert!(
+        erro.to_string()
+            .contains("chave duplicada \"role\" em /user/role na linha 1, coluna 28"),
+        "{erro}"
+    );
+
+    let mut avisos: Vec<DuplicateKey> = Vec::new();
+    let doc = parse_warn(entrada, |d| avisos.push(d.clone())).unwrap();
+    assert_eq!(doc["user"]["role"], "admin");
+    assert_eq!(doc["itens"][0]["id"], 2);
+    let ponteiros: Vec<&str> = avisos.iter().map(|d| d.pointer.as_str()).collect();
+    assert_eq!(ponteiros, ["/user/role", "/itens/0/id"]);
+    assert_eq!((avisos[1].key.as_str(), avisos[1].line), ("id", 1));
+
+    let unicas = br#"{"a/b": {"x": 1, "y": [{"x": 2}]}, "x": 3}"#;
+    assert_eq!(
+        parse_strict(unicas).unwrap(),
+        serde_json::from_slice::<Value>(unicas).unwrap()
+    );
+    let erro = parse_strict(br#"[{}, {"a~b": {"k": 1, "k": 2}}]"#).unwrap_err();
+    assert!(erro.to_string().contains("em /1/a~0b/k"), "{erro}");
+    assert!(parse_strict(b"{\"a\": }").is_err());
+}
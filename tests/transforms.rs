@@ -0,0 +1,1421 @@
+use json_safe::non_finite::NonFinite;
+use json_safe::{
+    arrays, base64, clock, contract, delta, deprecations, dotted, fidelity, float_format, inspect,
+    json_safe, localized_date, localized_number, money, new_uuid, non_finite, now, numeric,
+    overrides, projection, quantity, raw, report, seq, shape, today, versioned, with,
+};
+use serde_json::Value;
+
+// Overrides de CLI (--set a.b=1 --set tags[]=x)
+#[test]
+fn overrides() {
+    let base = json_safe!({
+        server: { host: "localhost", port: 80 },
+        tags: ["base"],
+    })
+    .expect("base válida");
+
+    let sets = vec![
+        "server.port=8080".to_string(),
+        "server.debug=true".to_string(),
+        "tags[]=x".to_string(),
+        "build.id:str=0042".to_string(),
+        "build.ratio:float=0.5".to_string(),
+        "build.retries:int=3".to_string(),
+        "build.flag:bool=false".to_string(),
+        "build.extra:json={\"a\":[1,2]}".to_string(),
+        "nome=alice".to_string(),
+    ];
+
+    match overrides::apply_overrides(base, &sets) {
+        Ok(doc) => {
+            assert_eq!(doc["server"]["host"], Value::from("localhost"));
+            assert_eq!(doc["server"]["port"], Value::from(8080));
+            assert_eq!(doc["server"]["debug"], Value::from(true));
+            assert_eq!(doc["tags"], json_safe!(["base", "x"]).unwrap());
+            assert_eq!(doc["build"]["id"], Value::from("0042"));
+            assert_eq!(doc["build"]["ratio"], Value::from(0.5));
+            assert_eq!(doc["build"]["retries"], Value::from(3));
+            assert_eq!(doc["build"]["flag"], Value::from(false));
+            assert_eq!(doc["build"]["extra"]["a"], json_safe!([1, 2]).unwrap());
+            assert_eq!(doc["nome"], Value::from("alice"));
+        }
+        Err(e) => panic!("apply_overrides retornou erro: {e}"),
+    }
+
+    match overrides::overrides_to_value(["a.b=null"]) {
+        Ok(doc) => assert!(doc["a"]["b"].is_null()),
+        Err(e) => panic!("overrides_to_value retornou erro: {e}"),
+    }
+
+    assert!(overrides::overrides_to_value(["sem_igual"]).is_err());
+    assert!(overrides::overrides_to_value(["a:int=abc"]).is_err());
+    assert!(overrides::overrides_to_value(["a:data=1"]).is_err());
+    assert!(overrides::overrides_to_value(["a[].b=1"]).is_err());
+    assert!(overrides::overrides_to_value(["a..b=1"]).is_err());
+}
+
+// Relatório em texto / Markdown
+#[test]
+fn report() {
+    let relatorio = json_safe!({
+        servico: "billing",
+        falhas: 2,
+        jobs: [
+            { id: 1, status: "ok" },
+            { id: 10, status: "erro", detalhe: "a|b" },
+        ],
+    })
+    .expect("relatório válido");
+
+    assert_eq!(
+        report::render(&relatorio, report::Layout::PlainText),
+        "falhas:  2\n\
+     servico: billing\n\
+     \n\
+     jobs\n\
+     id  status  detalhe\n\
+     --  ------  -------\n\
+     1   ok\n\
+     10  erro    a|b\n"
+    );
+    assert_eq!(
+        report::render(&relatorio, report::Layout::Markdown),
+        "- **falhas**: 2\n\
+     - **servico**: billing\n\
+     \n\
+     ### jobs\n\
+     \n\
+     | id  | status | detalhe |\n\
+     | --- | ------ | ------- |\n\
+     | 1   | ok     |         |\n\
+     | 10  | erro   | a\\|b    |\n"
+    );
+
+    // Quebras de linha e `|` nas células e nos cabeçalhos não quebram a tabela
+    let multilinha = json_safe!([{ "a|b": "linha 1\nlinha 2", "c\nd": "x\r\ny" }]).unwrap();
+    assert_eq!(
+        report::render(&multilinha, report::Layout::Markdown),
+        "| a\\|b               | c<br>d |\n\
+     | ------------------ | ------ |\n\
+     | linha 1<br>linha 2 | x<br>y |\n"
+    );
+    assert_eq!(
+        report::render(&multilinha, report::Layout::PlainText),
+        "a|b              c d\n\
+     ---------------  ---\n\
+     linha 1 linha 2  x y\n"
+    );
+    assert_eq!(
+        report::render(&json_safe!("solto").unwrap(), report::Layout::PlainText),
+        "solto\n"
+    );
+    assert_eq!(
+        report::render(&json_safe!([{ a: 1 }]).unwrap(), report::Layout::PlainText),
+        "a\n-\n1\n"
+    );
+}
+
+// Snapshot de contrato e checagem de compatibilidade
+#[test]
+fn contract() {
+    use contract::{Change, Contract};
+
+    let v1 = Contract::new()
+        .observe(
+            &json_safe!({
+                "id": 1,
+                "nome": "Ana",
+                "preco": 9.5,
+                "endereco": { "cidade": "SP", "cep": "01000" },
+                "tags": ["a"],
+            })
+            .unwrap(),
+        )
+        .observe(&json_safe!({ "id": 2, "nome": null, "preco": 10, "tags": [] }).unwrap());
+    assert_eq!(
+        v1.to_value()["$.nome"],
+        json_safe!(["null", "string"]).unwrap()
+    );
+    assert_eq!(
+        v1.to_value()["$.preco"],
+        json_safe!(["integer", "number"]).unwrap()
+    );
+    assert_eq!(Contract::from_value(&v1.to_value()).unwrap(), v1);
+
+    let v2 = Contract::new().observe(
+        &json_safe!({
+            "id": "2",
+            "nome": "Ana",
+            "preco": 3,
+            "tags": [1],
+            "novo": true,
+        })
+        .unwrap(),
+    );
+    assert_eq!(
+        contract::compare(&v1, &v2),
+        vec![
+            Change::Removed {
+                path: "$.endereco".into()
+            },
+            Change::TypeChanged {
+                path: "$.id".into(),
+                before: vec!["integer".into()],
+                after: vec!["string".into()],
+            },
+            Change::TypeChanged {
+                path: "$.tags[]".into(),
+                before: vec!["string".into()],
+                after: vec!["integer".into()],
+            },
+            Change::Added {
+                path: "$.novo".into()
+            },
+        ]
+    );
+    assert!(!Change::Added { path: "$.x".into() }.is_breaking());
+    assert!(contract::compare(&v1, &v1).is_empty());
+
+    let arquivo =
+        std::env::temp_dir().join(format!("json_safe_contrato_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&arquivo);
+    assert!(contract::check_snapshot(&arquivo, &v1).unwrap().is_empty());
+    assert!(arquivo.exists());
+    let quebras = contract::check_snapshot(&arquivo, &v2).unwrap();
+    assert_eq!(quebras.len(), 3);
+    assert!(quebras.iter().all(Change::is_breaking));
+    std::fs::remove_file(&arquivo).unwrap();
+    assert!(Contract::from_value(&json_safe!({ "$": "object" }).unwrap()).is_err());
+}
+
+// Envelopes versionados e migração por versão
+#[test]
+fn versioned() {
+    let v1 = versioned!(1, { "nome": "Ana Silva", "idade": 31 }).unwrap();
+    assert_eq!(
+        v1,
+        json_safe!({ "schema_version": 1, "payload": { "nome": "Ana Silva", "idade": 31 } })
+            .unwrap()
+    );
+    let v3 = versioned!(3, { "primeiro_nome": "Bia", "sobrenome": "Lima", "idade": 20 }).unwrap();
+    assert_eq!(versioned::unwrap(&v3).unwrap().0, 3);
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct PessoaV3 {
+        primeiro_nome: String,
+        sobrenome: String,
+        idade: u32,
+    }
+
+    let leitor = versioned::Dispatcher::new(3)
+        .migration(1, |mut p| {
+            // v1 -> v2: `nome` vira `nome_completo`
+            let nome = p["nome"].take();
+            p["nome_completo"] = nome;
+            p.as_object_mut().unwrap().remove("nome");
+            Ok(p)
+        })
+        .migration(2, |mut p| {
+            // v2 -> v3: separa primeiro nome e sobrenome
+            let completo = p["nome_completo"].as_str().unwrap_or_default().to_string();
+            let (primeiro, resto) = completo.split_once(' ').unwrap_or((&completo, ""));
+            p["primeiro_nome"] = Value::from(primeiro);
+            p["sobrenome"] = Value::from(resto);
+            p.as_object_mut().unwrap().remove("nome_completo");
+            Ok(p)
+        });
+    assert_eq!(
+        leitor.parse_as::<PessoaV3>(&v1).unwrap(),
+        PessoaV3 {
+            primeiro_nome: "Ana".into(),
+            sobrenome: "Silva".into(),
+            idade: 31
+        }
+    );
+    assert_eq!(leitor.parse(&v3).unwrap(), v3["payload"]);
+    assert!(leitor.parse(&versioned!(4, {}).unwrap()).is_err());
+    assert!(versioned::Dispatcher::new(2).parse(&v1).is_err());
+    assert!(
+        leitor
+            .parse(&json_safe!({ "payload": {} }).unwrap())
+            .is_err()
+    );
+    assert!(versioned::unwrap(&json_safe!({ "schema_version": 1 }).unwrap()).is_err());
+}
+
+// Campos obsoletos: avisos ao montar documentos
+#[test]
+fn deprecations() {
+    use deprecations::Warning;
+
+    let obsoletos = deprecations::Deprecations::new()
+        .deprecate("/user/legacy_id", "use user.id")
+        .deprecate("/items/*/sku", "use items[].product_id")
+        .deprecate("/a~1b", "chave com barra");
+    let doc = json_safe!({
+        "user": { "id": 1, "legacy_id": "u-1" },
+        "items": [{ "sku": "X" }, { "product_id": 2 }, { "sku": "Z" }],
+        "a/b": true,
+    })
+    .unwrap();
+    assert_eq!(
+        obsoletos.check(&doc),
+        vec![
+            Warning {
+                path: "/user/legacy_id".into(),
+                message: "use user.id".into()
+            },
+            Warning {
+                path: "/items/0/sku".into(),
+                message: "use items[].product_id".into()
+            },
+            Warning {
+                path: "/items/2/sku".into(),
+                message: "use items[].product_id".into()
+            },
+            Warning {
+                path: "/a~1b".into(),
+                message: "chave com barra".into()
+            },
+        ]
+    );
+    assert!(
+        obsoletos
+            .check(&json_safe!({ "user": { "id": 1 } }).unwrap())
+            .is_empty()
+    );
+
+    let logados = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let contador = logados.clone();
+    deprecations::on_warning(move |_| {
+        contador.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    });
+    deprecations::deprecate("/old", "removido na v3");
+    let montado = deprecations::checked(json_safe!({ "old": 1, "new": 2 })).unwrap();
+    assert_eq!(montado["new"], Value::from(2));
+    assert!(deprecations::checked(json_safe!({ "new": 2 })).is_ok());
+    assert_eq!(
+        deprecations::take_warnings(),
+        vec![Warning {
+            path: "/old".into(),
+            message: "removido na v3".into()
+        }]
+    );
+    assert!(deprecations::take_warnings().is_empty());
+    assert_eq!(logados.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // Um sink pode trocar a si mesmo sem travar
+    let contador = logados.clone();
+    deprecations::on_warning(move |_| {
+        let contador = contador.clone();
+        deprecations::on_warning(move |_| {
+            contador.fetch_add(10, std::sync::atomic::Ordering::SeqCst);
+        });
+    });
+    for _ in 0..2 {
+        deprecations::checked(json_safe!({ "old": 1 })).unwrap();
+    }
+    assert_eq!(logados.load(std::sync::atomic::Ordering::SeqCst), 11);
+    assert_eq!(deprecations::take_warnings().len(), 2);
+}
+
+// Números e datas formatados por locale
+#[test]
+fn locale() {
+    let tela = json_safe!({
+        "total": (localized_number!(1234567.891, "pt-BR")),
+        "total_en": (localized_number!(1234567.891, "en-US")),
+        "preco": (localized_number!(9.5, "de-DE", 2)),
+        "saldo": (localized_number!(-1500, "fr-FR")),
+        "emitido": (localized_date!((2024, 5, 1), "pt-BR", "d 'de' MMMM 'de' yyyy")),
+        "vence": (localized_date!("2024-12-31T23:05:09Z", "en-US", "MMMM d, yyyy HH:mm:ss")),
+        "curto": (localized_date!((2024, 2, 9, 8, 0, 0), "es-ES", "dd/MM/yy")),
+    })
+    .unwrap();
+    assert_eq!(tela["total"], Value::from("1.234.567,891"));
+    assert_eq!(tela["total_en"], Value::from("1,234,567.891"));
+    assert_eq!(tela["preco"], Value::from("9,50"));
+    assert_eq!(tela["saldo"], Value::from("-1\u{202f}500"));
+    assert_eq!(tela["emitido"], Value::from("1 de maio de 2024"));
+    assert_eq!(tela["vence"], Value::from("December 31, 2024 23:05:09"));
+    assert_eq!(tela["curto"], Value::from("09/02/24"));
+
+    assert_eq!(
+        localized_number!(0.1 + 0.2, "en-US").format().unwrap(),
+        "0.3"
+    );
+    assert_eq!(localized_number!(-0.0001, "en-US").format().unwrap(), "0");
+    assert_eq!(localized_number!(999, "pt-BR").format().unwrap(), "999");
+    assert!(json_safe!({ "x": (localized_number!(1, "xx-XX")) }).is_err());
+    assert!(json_safe!([(localized_number!(f64::NAN, "pt-BR"))]).is_err());
+    assert!(
+        localized_date!((2023, 2, 29), "pt-BR", "dd")
+            .format()
+            .is_err()
+    );
+    assert!(
+        localized_date!("2024-13-01", "pt-BR", "dd")
+            .format()
+            .is_err()
+    );
+    assert!(
+        localized_date!((2024, 2, 29), "pt-BR", "'aberto")
+            .format()
+            .is_err()
+    );
+    assert_eq!(
+        localized_date!((2024, 3, 1), "de-DE", "d. MMMM yyyy")
+            .format()
+            .unwrap(),
+        "1. März 2024"
+    );
+}
+
+// Dinheiro em unidades mínimas
+#[test]
+fn money() {
+    use money::MoneyShape;
+
+    let pedido = json_safe!({
+        "total": (money!(1050, "BRL")),
+        "frete": (money!(-5, "USD", MoneyShape::DecimalString)),
+        "yen": (money!(1500, "JPY", MoneyShape::Combined)),
+        "dinar": (money!(12345, "KWD", MoneyShape::DecimalString)),
+    })
+    .unwrap();
+    assert_eq!(
+        pedido,
+        json_safe!({
+            "total": { "amount": 1050, "currency": "BRL" },
+            "frete": { "amount": "-0.05", "currency": "USD" },
+            "yen": "1500 JPY",
+            "dinar": { "amount": "12.345", "currency": "KWD" },
+        })
+        .unwrap()
+    );
+    // money!(10.5, "BRL") não compila: o valor precisa ser inteiro
+    assert_eq!(money!(7u8, "EUR").decimal_string().unwrap(), "0.07");
+    assert!(json_safe!([(money!(1, "XYZ"))]).is_err());
+    assert_eq!(money::minor_unit_digits("CLP"), Some(0));
+
+    money::set_default_shape(MoneyShape::Combined);
+    assert_eq!(
+        json_safe!(money!(199900, "BRL")).unwrap(),
+        Value::from("1999.00 BRL")
+    );
+    money::set_default_shape(MoneyShape::MinorUnits);
+}
+
+// Quantidades com unidade
+#[test]
+fn quantity() {
+    use quantity::QuantityShape;
+
+    let telemetria = json_safe!({
+        "latencia": (quantity!(250, "ms")),
+        "payload": (quantity!(1024u64, "bytes", QuantityShape::Suffixed)),
+        "cpu": (quantity!(12.5, "%", QuantityShape::Suffixed)),
+    })
+    .unwrap();
+    assert_eq!(
+        telemetria,
+        json_safe!({
+            "latencia": { "value": 250, "unit": "ms" },
+            "payload": "1024 bytes",
+            "cpu": "12.5%",
+        })
+        .unwrap()
+    );
+    assert!(json_safe!([(quantity!(f64::NAN, "ms"))]).is_err());
+    assert!(json_safe!([(quantity!("10", "ms"))]).is_err());
+    assert!(json_safe!([(quantity!(1, "milli seconds"))]).is_err());
+
+    quantity::set_default_shape(QuantityShape::Suffixed);
+    assert_eq!(json_safe!(quantity!(3, "s")).unwrap(), Value::from("3 s"));
+    quantity::set_default_shape(QuantityShape::Object);
+}
+
+// Truncamento de payloads grandes para debug
+#[test]
+fn truncate_for_debug() {
+    use inspect::Budget;
+
+    let grande = json_safe!({
+        "log": ("x".repeat(40)),
+        "ids": ((1..=10).collect::<Vec<i32>>()),
+        "fundo": { "a": { "b": { "c": 1 } } },
+        "curto": "ok",
+    })
+    .unwrap();
+    let reduzido = inspect::truncate_for_debug(
+        &grande,
+        Budget::new()
+            .max_string(5)
+            .max_items(4)
+            .max_depth(2)
+            .max_keys(10),
+    );
+    assert_eq!(
+        reduzido,
+        json_safe!({
+            "log": "xxxxx…truncated (35 more)",
+            "ids": [1, 2, "…truncated (6 more)", 9, 10],
+            "fundo": { "a": "…{1 keys}" },
+            "curto": "ok",
+        })
+        .unwrap()
+    );
+    let chaves = inspect::truncate_for_debug(&grande, Budget::new().max_keys(1));
+    assert_eq!(chaves.as_object().unwrap().len(), 2);
+    assert_eq!(chaves["…"], Value::from("…truncated (3 more)"));
+    assert_eq!(
+        inspect::truncate_for_debug(&grande, Budget::default()),
+        grande
+    );
+    assert_eq!(
+        inspect::truncate_for_debug(&grande["ids"], Budget::new().max_depth(0)),
+        Value::from("…[10 items]")
+    );
+}
+
+// Esqueleto estrutural (outline)
+#[test]
+fn outline() {
+    let upstream = json_safe!({
+        "user": { "id": 7, "email": "a@ex.com", "score": 9.5 },
+        "items": [{ "sku": "A", "qty": 1 }, { "sku": "B", "desconto": null }, null],
+        "tags": ["a", "b", 3],
+        "vazio": [],
+        "ok": true,
+    })
+    .unwrap();
+    assert_eq!(
+        inspect::outline(&upstream, 3),
+        json_safe!({
+            "user": { "id": "integer", "email": "string", "score": "number" },
+            "items": { "array(3)": { "sku": "string", "qty": "integer", "desconto": "null" } },
+            "tags": "array(3)<string|integer>",
+            "vazio": "array(0)<>",
+            "ok": "boolean",
+        })
+        .unwrap()
+    );
+    assert_eq!(
+        inspect::outline(&upstream, 1),
+        json_safe!({
+            "user": "object(3 keys)",
+            "items": "array(3)<object|null>",
+            "tags": "array(3)<string|integer>",
+            "vazio": "array(0)<>",
+            "ok": "boolean",
+        })
+        .unwrap()
+    );
+    assert_eq!(
+        inspect::outline(&upstream, 0),
+        Value::from("object(5 keys)")
+    );
+}
+
+// Mapa de tipos (typeof tree)
+#[test]
+fn type_map() {
+    let esperado =
+        json_safe!({ "id": 1, "nome": "a", "tags": ["x", 2], "extra": { "v": 1.5, "n": null } })
+            .unwrap();
+    let recebido =
+        json_safe!({ "id": "1", "nome": "b", "tags": ["y", 3], "extra": { "v": 2.5, "n": null } })
+            .unwrap();
+    assert_eq!(
+        inspect::type_map(&esperado),
+        json_safe!({
+            "id": "integer",
+            "nome": "string",
+            "tags": ["string", "integer"],
+            "extra": { "v": "number", "n": "null" },
+        })
+        .unwrap()
+    );
+    let (a, b) = (inspect::type_map(&esperado), inspect::type_map(&recebido));
+    assert_ne!(a, b);
+    assert_eq!(b["id"], Value::from("string"));
+    assert_eq!(a["extra"], b["extra"]);
+    assert_eq!(
+        inspect::type_map(&json_safe!(true).unwrap()),
+        Value::from("boolean")
+    );
+}
+
+// Ordenação e deduplicação de arrays por chave
+#[test]
+fn sort_and_dedup() {
+    let mut pedido = json_safe!({
+        "items": [
+            { "id": 3, "sku": "C" },
+            { "id": 1, "sku": "A" },
+            { "sku": "sem-id" },
+            { "id": 2, "sku": "B" },
+            { "id": 1, "sku": "A2" },
+        ],
+        "cliente": { "enderecos": [{ "geo": { "cep": "02000" } }, { "geo": { "cep": "01000" } }] },
+    })
+    .unwrap();
+    arrays::sort_array_by(&mut pedido, "/items", "id").unwrap();
+    let skus: Vec<&str> = pedido["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["sku"].as_str().unwrap())
+        .collect();
+    assert_eq!(skus, ["sem-id", "A", "A2", "B", "C"]);
+
+    arrays::dedup_array_by(&mut pedido, "/items", "id").unwrap();
+    assert_eq!(pedido["items"].as_array().unwrap().len(), 4);
+    assert_eq!(pedido["items"][1]["sku"], Value::from("A"));
+
+    arrays::sort_array_by(&mut pedido, "/cliente/enderecos", "geo.cep").unwrap();
+    assert_eq!(
+        pedido["cliente"]["enderecos"][0]["geo"]["cep"],
+        Value::from("01000")
+    );
+
+    assert!(arrays::sort_array_by(&mut pedido, "/cliente", "id").is_err());
+    assert!(arrays::dedup_array_by(&mut pedido, "/nada", "id").is_err());
+    assert_eq!(
+        arrays::compare_values(&Value::from(u64::MAX), &Value::from(-1)),
+        std::cmp::Ordering::Greater
+    );
+    assert_eq!(
+        arrays::compare_values(&Value::from(1.5), &Value::from(2)),
+        std::cmp::Ordering::Less
+    );
+    assert_eq!(
+        arrays::compare_values(&json_safe!([1, 2]).unwrap(), &json_safe!([1]).unwrap()),
+        std::cmp::Ordering::Greater
+    );
+}
+
+// Agrupamento e agregações
+#[test]
+fn group_and_aggregate() {
+    use arrays::Agg;
+
+    let eventos = json_safe!([
+        { "tipo": "compra", "valor": 100, "user": { "pais": "BR" } },
+        { "tipo": "compra", "valor": 50.5, "user": { "pais": "US" } },
+        { "tipo": "reembolso", "valor": (-20), "user": { "pais": "BR" } },
+        { "tipo": "visita", "user": { "pais": "BR" } },
+        { "valor": 1 },
+    ])
+    .unwrap();
+    let grupos = arrays::group_by(&eventos, "tipo").unwrap();
+    assert_eq!(grupos["\"compra\""].as_array().unwrap().len(), 2);
+    assert_eq!(grupos["null"].as_array().unwrap().len(), 1);
+
+    // Valores de tipos diferentes com o mesmo texto ficam em grupos distintos
+    let mistos = json_safe!([{ k: 1 }, { k: "1" }, { k: true }, { k: "true" }, { k: null }, { k: "null" }, {}])
+        .unwrap();
+    let grupos = arrays::group_by(&mistos, "k").unwrap();
+    assert_eq!(grupos.as_object().unwrap().len(), 6);
+    assert_eq!(grupos["1"], json_safe!([{ k: 1 }]).unwrap());
+    assert_eq!(grupos["\"1\""], json_safe!([{ k: "1" }]).unwrap());
+    assert_eq!(grupos["null"], json_safe!([{ k: null }, {}]).unwrap());
+    assert_eq!(grupos["\"null\""], json_safe!([{ k: "null" }]).unwrap());
+
+    assert_eq!(
+        arrays::aggregate(
+            &eventos,
+            &[
+                ("n", Agg::Count),
+                ("total", Agg::Sum("valor")),
+                ("menor", Agg::Min("valor"))
+            ]
+        )
+        .unwrap(),
+        json_safe!({ "n": 5, "total": 131.5, "menor": (-20) }).unwrap()
+    );
+    assert_eq!(
+        arrays::aggregate_by(
+            &eventos,
+            "user.pais",
+            &[
+                ("n", Agg::Count),
+                ("total", Agg::Sum("valor")),
+                ("maior", Agg::Max("valor"))
+            ]
+        )
+        .unwrap(),
+        json_safe!({
+            "\"BR\"": { "n": 3, "total": 80, "maior": 100 },
+            "\"US\"": { "n": 1, "total": 50.5, "maior": 50.5 },
+            "null": { "n": 1, "total": 1, "maior": 1 },
+        })
+        .unwrap()
+    );
+    assert_eq!(
+        arrays::aggregate(&json_safe!([]).unwrap(), &[("m", Agg::Max("x"))]).unwrap(),
+        json_safe!({ "m": null }).unwrap()
+    );
+    assert!(arrays::aggregate(&eventos, &[("s", Agg::Sum("tipo"))]).is_err());
+    assert!(arrays::group_by(&json_safe!({}).unwrap(), "x").is_err());
+}
+
+// Join entre arrays de objetos
+#[test]
+fn join() {
+    use arrays::JoinKind;
+
+    let pedidos = json_safe!([
+        { "id": 1, "cliente_id": 10, "total": 99 },
+        { "id": 2, "cliente_id": 20, "total": 15 },
+        { "id": 3, "total": 1 },
+    ])
+    .unwrap();
+    let clientes = json_safe!([
+        { "cliente_id": 10, "nome": "Ana", "total": "ignorado" },
+        { "cliente_id": 30, "nome": "Caio" },
+    ])
+    .unwrap();
+    assert_eq!(
+        arrays::join(&pedidos, &clientes, "cliente_id", JoinKind::Inner).unwrap(),
+        json_safe!([{ "id": 1, "cliente_id": 10, "total": 99, "nome": "Ana" }]).unwrap()
+    );
+    let esquerda = arrays::join(&pedidos, &clientes, "cliente_id", JoinKind::Left).unwrap();
+    assert_eq!(esquerda.as_array().unwrap().len(), 3);
+    assert_eq!(esquerda[1], pedidos[1]);
+    assert_eq!(esquerda[2], pedidos[2]);
+
+    let enderecos = json_safe!([
+        { "cliente_id": 10, "cidade": "SP" },
+        { "cliente_id": 10, "cidade": "RJ" },
+    ])
+    .unwrap();
+    let multiplos = arrays::join(&pedidos, &enderecos, "cliente_id", JoinKind::Inner).unwrap();
+    assert_eq!(multiplos.as_array().unwrap().len(), 2);
+    assert_eq!(multiplos[1]["cidade"], Value::from("RJ"));
+    assert!(arrays::join(&pedidos, &json_safe!([1]).unwrap(), "id", JoinKind::Left).is_err());
+}
+
+// Paginação de arrays copiando o envelope
+#[test]
+fn chunk_array() {
+    let lote = json_safe!({
+        "meta": { "fonte": "crm", "lote": 7 },
+        "items": [1, 2, 3, 4, 5],
+    })
+    .unwrap();
+    let paginas = arrays::chunk_array(&lote, 2).unwrap();
+    assert_eq!(paginas.len(), 3);
+    assert_eq!(paginas[0]["items"], json_safe!([1, 2]).unwrap());
+    assert_eq!(paginas[2]["items"], json_safe!([5]).unwrap());
+    assert!(paginas.iter().all(|p| p["meta"] == lote["meta"]));
+
+    assert_eq!(
+        arrays::chunk_array(&json_safe!([1, 2, 3]).unwrap(), 3).unwrap(),
+        vec![json_safe!([1, 2, 3]).unwrap()]
+    );
+    let aninhado =
+        json_safe!({ "data": { "rows": [1, 2, 3] }, "tags": ["a"], "extra": [] }).unwrap();
+    assert!(arrays::chunk_array(&aninhado, 2).is_err());
+    let paginas = arrays::chunk_array_at(&aninhado, "/data/rows", 2).unwrap();
+    assert_eq!(paginas[1]["data"]["rows"], json_safe!([3]).unwrap());
+    assert_eq!(
+        arrays::chunk_array_at(&aninhado, "/extra", 10).unwrap(),
+        vec![aninhado.clone()]
+    );
+    assert!(arrays::chunk_array(&lote, 0).is_err());
+    assert!(arrays::chunk_array_at(&lote, "/meta", 1).is_err());
+}
+
+// Projeção de campos estilo GraphQL
+#[test]
+fn project() {
+    let resposta = json_safe!({
+        user: {
+            id: 1,
+            name: "Ana",
+            email: "ana@example.com",
+            address: { city: "Recife", zip: "50000-000" },
+        },
+        items: [
+            { sku: "A1", price: 10 },
+            { sku: "B2", price: 20 },
+        ],
+        debug: { trace: "..." },
+    })
+    .unwrap();
+
+    let publica =
+        projection::project(&resposta, "user { id name address { city } } items { sku }").unwrap();
+    assert_eq!(
+        publica,
+        json_safe!({
+            user: { id: 1, name: "Ana", address: { city: "Recife" } },
+            items: [{ sku: "A1" }, { sku: "B2" }],
+        })
+        .unwrap()
+    );
+
+    // Campo sem sub-seleção vem inteiro; ausentes são omitidos
+    assert_eq!(
+        projection::project(&resposta, "debug, missing").unwrap(),
+        json_safe!({ debug: { trace: "..." } }).unwrap()
+    );
+    assert!(projection::project(&resposta, "user { id").is_err());
+    assert!(projection::project(&resposta, "user }").is_err());
+    assert!(projection::project(&resposta, "user { }").is_err());
+    assert!(projection::project(&resposta, "{ id }").is_err());
+}
+
+// Sparse fieldsets e expansão via query string
+#[test]
+fn sparse_fieldsets() {
+    let post = json_safe!({
+        id: 1,
+        title: "Olá",
+        body: "...",
+        author: { id: "u1", name: "Ana", email: "a@x" },
+        comments: [{ id: "c1", text: "oi" }, { id: "c2", text: "tchau" }],
+    })
+    .unwrap();
+    let expansiveis = ["author", "comments"];
+
+    let recolhido = projection::sparse_fieldsets(&post, "", &expansiveis).unwrap();
+    assert_eq!(recolhido["author"], "u1");
+    assert_eq!(recolhido["comments"], json_safe!(["c1", "c2"]).unwrap());
+
+    let pedido = "?fields=title,author.name,comments&expand=author%2Ccomments";
+    assert_eq!(
+        projection::sparse_fieldsets(&post, pedido, &expansiveis).unwrap(),
+        json_safe!({
+            title: "Olá",
+            author: { name: "Ana" },
+            comments: [{ id: "c1", text: "oi" }, { id: "c2", text: "tchau" }],
+        })
+        .unwrap()
+    );
+
+    let parametros = projection::Fieldsets::parse("page=2&fields=id&fields=title&expand=").unwrap();
+    assert_eq!(
+        parametros.fields.as_deref(),
+        Some(&["id".to_string(), "title".to_string()][..])
+    );
+    assert!(parametros.expand.is_empty());
+
+    // Já recolhidos (id ou null) ficam como estão; objetos sem id somem
+    let parcial = json_safe!({
+        author: "u1",
+        editor: null,
+        revisor: { name: "sem id" },
+        comments: ["c1", { id: "c2" }, { text: "sem id" }],
+    })
+    .unwrap();
+    assert_eq!(
+        projection::sparse_fieldsets(&parcial, "", &["author", "editor", "revisor", "comments"])
+            .unwrap(),
+        json_safe!({ author: "u1", editor: null, comments: ["c1", "c2"] }).unwrap()
+    );
+
+    assert!(projection::sparse_fieldsets(&post, "expand=body", &expansiveis).is_err());
+    assert!(projection::sparse_fieldsets(&post, "fields=a..b", &expansiveis).is_err());
+    assert!(projection::sparse_fieldsets(&post, "fields=%zz", &expansiveis).is_err());
+}
+
+// Tempo e ids determinísticos: now!() e new_uuid!()
+#[test]
+fn clock_and_ids() {
+    use clock::{FixedClock, SequentialIds};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    assert_eq!(clock::rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    assert_eq!(
+        clock::rfc3339(UNIX_EPOCH + Duration::from_millis(951_782_400_123)),
+        "2000-02-29T00:00:00.123Z"
+    );
+    assert_eq!(
+        clock::rfc3339(UNIX_EPOCH - Duration::from_secs(1)),
+        "1969-12-31T23:59:59.000Z"
+    );
+
+    // Padrão: relógio do sistema e UUIDs v4 aleatórios
+    let a = json_safe!({ id: (new_uuid!()), em: (now!()) }).unwrap();
+    let b = json_safe!({ id: (new_uuid!()), em: (now!()) }).unwrap();
+    let id = a["id"].as_str().unwrap();
+    assert_eq!(id.len(), 36);
+    assert_eq!(&id[14..15], "4");
+    assert_ne!(a["id"], b["id"]);
+    assert!(a["em"].as_str().unwrap().ends_with('Z'));
+
+    clock::set_clock(FixedClock::at(1_714_564_800_000).step(Duration::from_secs(1)));
+    clock::set_id_gen(SequentialIds::default());
+    let evento = json_safe!({
+        id: (new_uuid!()),
+        criado_em: (now!()),
+        itens: [{ id: (new_uuid!()), em: (now!()) }],
+    })
+    .unwrap();
+    clock::reset();
+
+    assert_eq!(
+        evento,
+        json_safe!({
+            id: "00000000-0000-4000-8000-000000000001",
+            criado_em: "2024-05-01T12:00:00.000Z",
+            itens: [{ id: "00000000-0000-4000-8000-000000000002", em: "2024-05-01T12:00:01.000Z" }],
+        })
+        .unwrap()
+    );
+
+    // today!(), seq!() e as políticas de formato de now!()
+    clock::set_clock(FixedClock::at(1_714_564_800_000));
+    clock::set_sequence(41);
+    let evento = json_safe!({ dia: (today!()), n: (seq!()), m: (seq!()) }).unwrap();
+    assert_eq!(
+        evento,
+        json_safe!({ dia: "2024-05-01", n: 41, m: 42 }).unwrap()
+    );
+    clock::set_time_policy(clock::TimePolicy::Rfc3339Seconds);
+    assert_eq!(
+        json_safe!(now!()).unwrap(),
+        Value::from("2024-05-01T12:00:00Z")
+    );
+    clock::set_time_policy(clock::TimePolicy::UnixSeconds);
+    assert_eq!(json_safe!(now!()).unwrap(), Value::from(1_714_564_800));
+    clock::set_time_policy(clock::TimePolicy::UnixMillis);
+    assert_eq!(
+        json_safe!(now!()).unwrap(),
+        Value::from(1_714_564_800_000u64)
+    );
+    clock::reset();
+    assert_eq!(json_safe!(seq!()).unwrap(), Value::from(1));
+}
+
+// Relógio e ids só da thread, chamados fora de qualquer lock
+#[test]
+fn scoped_clock_and_ids() {
+    use json_safe::clock::{FixedClock, IdGen, SequentialIds};
+
+    // Um gerador que usa os ids da thread por dentro não trava nem reentra
+    struct Aninhado;
+    impl IdGen for Aninhado {
+        fn new_id(&self) -> String {
+            clock::with_id_gen(SequentialIds::default(), clock::new_id).to_uppercase()
+        }
+    }
+
+    let evento = clock::with_clock(FixedClock::at(1_714_564_800_000), || {
+        clock::with_id_gen(Aninhado, || json_safe!({ id: (new_uuid!()), em: (now!()) }))
+    })
+    .unwrap();
+    assert_eq!(
+        evento,
+        json_safe!({ id: "00000000-0000-4000-8000-000000000001", em: "2024-05-01T12:00:00.000Z" })
+            .unwrap()
+    );
+
+    // Outras threads não veem a troca
+    clock::with_clock(FixedClock::at(0), || {
+        let outra = std::thread::spawn(clock::now).join().unwrap();
+        assert_ne!(outra, std::time::UNIX_EPOCH);
+        assert_eq!(clock::now(), std::time::UNIX_EPOCH);
+    });
+}
+
+// Chaves com pontos expandidas em objetos aninhados
+#[test]
+fn dotted_keys() {
+    let porta = 8080;
+    let config = json_safe!(dotted {
+        "config.server.port": porta,
+        "config.server.host": "0.0.0.0",
+        "config.debug": false,
+        config: { name: "api" },
+        items: [{ "a.b": 1 }],
+    })
+    .unwrap();
+    assert_eq!(
+        config,
+        json_safe!({
+            config: { server: { port: 8080, host: "0.0.0.0" }, debug: false, name: "api" },
+            items: [{ a: { b: 1 } }],
+        })
+        .unwrap()
+    );
+
+    // Sem o opt-in, a chave fica como está
+    assert_eq!(json_safe!({ "a.b": 1 }).unwrap()["a.b"], 1);
+
+    assert!(json_safe!(dotted { "a": 1, "a.b": 2 }).is_err());
+    assert!(json_safe!(dotted { "a..b": 1 }).is_err());
+    assert!(dotted::expand_dotted_keys(json_safe!({ "x.y": 1, "x.y.z": 2 }).unwrap()).is_err());
+}
+
+// Saída sem campos null
+#[test]
+fn compact_output() {
+    let apelido: Option<&str> = None;
+    let doc = json_safe!(compact {
+        nome: "ana",
+        apelido,
+        extra: null,
+        endereco: { rua: null, cidade: "SP" },
+        tags: [null, { x: null }],
+    })
+    .unwrap();
+    assert_eq!(
+        doc,
+        json_safe!({ nome: "ana", endereco: { cidade: "SP" }, tags: [null, {}] }).unwrap()
+    );
+    assert_eq!(
+        json_safe!(compact [{ a: null, b: 1 }]).unwrap(),
+        json_safe!([{ b: 1 }]).unwrap()
+    );
+    assert_eq!(json_safe!({ a: null }).unwrap()["a"], Value::Null);
+    assert!(
+        json_safe!({ a: null })
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .contains_key("a")
+    );
+}
+
+// Política para NaN/Infinity
+#[test]
+fn non_finite_floats() {
+    #[derive(serde::Serialize)]
+    struct Amostra {
+        latencia: f64,
+        pico: f32,
+    }
+
+    let amostra = Amostra {
+        latencia: f64::NAN,
+        pico: f32::NEG_INFINITY,
+    };
+    let erro = json_safe!({ cpu: 0.5, amostra: (&amostra) }).unwrap_err();
+    assert!(erro.to_string().contains("não finito: NaN"), "{erro}");
+    assert!(json_safe!([f64::INFINITY]).is_err());
+
+    let doc = json_safe!(nan_null {
+        cpu: 0.5,
+        amostra: (&amostra),
+        v: [f64::NAN]
+    })
+    .unwrap();
+    assert_eq!(
+        doc,
+        json_safe!({ cpu: 0.5, amostra: { latencia: null, pico: null }, v: [null] }).unwrap()
+    );
+    let doc = json_safe!(nan_string [(&amostra), f64::INFINITY]).unwrap();
+    assert_eq!(
+        doc,
+        json_safe!([{ latencia: "NaN", pico: "-Infinity" }, "Infinity"]).unwrap()
+    );
+
+    let doc = non_finite::with_policy(NonFinite::CoerceToNull, || json_safe!({ x: f64::NAN }));
+    assert_eq!(doc.unwrap()["x"], Value::Null);
+    assert_eq!(non_finite::policy(), NonFinite::Error);
+    assert!(json_safe!(all nan_null { a: f64::NAN, b: (&amostra) }).is_ok());
+}
+
+// Modo numérico estrito (sem conversões com perda)
+#[test]
+fn strict_numbers() {
+    use json_safe::numeric::NumericError;
+
+    #[derive(serde::Serialize)]
+    struct Lancamento {
+        valor: f32,
+        centavos: i128,
+    }
+
+    let ok = Lancamento {
+        valor: 0.5,
+        centavos: 1 << 40,
+    };
+    let doc = json_safe!(strict_numbers {
+        itens: [(&ok)],
+        taxa: 0.1
+    })
+    .unwrap();
+    assert_eq!(doc["itens"][0]["centavos"], 1u64 << 40);
+
+    let lancamentos = [
+        ok,
+        Lancamento {
+            valor: 0.1,
+            centavos: 0,
+        },
+    ];
+    let erro = json_safe!(strict_numbers {
+        itens: (&lancamentos)
+    })
+    .unwrap_err();
+    assert_eq!(erro.path(), "itens[1].valor");
+    assert_eq!(erro.numeric(), Some(&NumericError::LossyFloat(0.1)));
+    assert!(
+        erro.to_string().contains("viraria 0.10000000149011612"),
+        "{erro}"
+    );
+    // Fora do modo estrito, o f32 passa
+    assert!(json_safe!({ itens: (&lancamentos) }).is_ok());
+
+    let erro = json_safe!(strict_numbers[u128::MAX]).unwrap_err();
+    assert_eq!(erro.path(), "[0]");
+    assert!(
+        matches!(erro.numeric(), Some(NumericError::OutOfRange(v)) if *v == u128::MAX.to_string())
+    );
+    assert!(json_safe!([0.1f32]).unwrap()[0].is_f64());
+
+    let erro = non_finite::with_policy(NonFinite::CoerceToNull, || {
+        numeric::strict(|| json_safe!({ x: f64::NAN }))
+    })
+    .unwrap_err();
+    assert!(matches!(erro.numeric(), Some(NumericError::NonFinite(v)) if v.is_nan()));
+    assert!(!numeric::is_strict());
+}
+
+// i128/u128 com política de overflow
+#[test]
+fn int128_overflow() {
+    use json_safe::int128::{self, Overflow};
+    use json_safe::numeric::NumericError;
+
+    let doc = json_safe!({ a: 7u128, b: -7i128, c: (u64::MAX as u128) }).unwrap();
+    assert_eq!(doc, json_safe!({ a: 7, b: -7, c: u64::MAX }).unwrap());
+
+    // Com arbitrary-precision, só a política explícita recusa
+    let erro = int128::with_policy(Overflow::Error, || json_safe!({ id: u128::MAX })).unwrap_err();
+    assert_eq!(erro.path(), "id");
+    assert!(matches!(erro.numeric(), Some(NumericError::OutOfRange(_))));
+
+    let doc = json_safe!(int128_string {
+        id: u128::MAX,
+        minimo: i128::MIN,
+        ok: 1u128
+    })
+    .unwrap();
+    assert_eq!(doc["id"], "340282366920938463463374607431768211455");
+    assert_eq!(doc["minimo"], i128::MIN.to_string());
+    assert_eq!(doc["ok"], 1);
+    assert_eq!(
+        int128::with_policy(Overflow::Stringify, || json_safe!([(1u128 << 64)])).unwrap()[0],
+        "18446744073709551616"
+    );
+    assert_eq!(int128::policy(), Overflow::Error);
+    // O modo estrito recusa mesmo com Stringify
+    let estrito = int128::with_policy(Overflow::Stringify, || {
+        numeric::strict(|| json_safe!([u128::MAX]))
+    });
+    assert!(estrito.is_err());
+}
+
+// Formas de saída declarativas (shape!/Transform)
+#[test]
+fn response_shapes() {
+    let interno = json_safe!({
+        id: 7,
+        name: "Ana",
+        secret: "x",
+        address: { city: "SP", zip: "01000" },
+        items: [{ sku: "a", qty: 1 }, { sku: "b", qty: 2 }],
+    })
+    .unwrap();
+
+    let publico = shape! {
+        id,
+        nome <- name,
+        cidade: "$.address.city",
+        skus: ["$.items[*].sku"],
+        ultimo: "$.items[-1].sku",
+        ausente: "$.nada",
+    }
+    .unwrap();
+    assert_eq!(
+        publico.apply(&interno),
+        json_safe!({ id: 7, nome: "Ana", cidade: "SP", skus: ["a", "b"], ultimo: "b" }).unwrap()
+    );
+
+    // Mesma forma, montada à mão
+    let manual = shape::Transform::new()
+        .pick("id")
+        .path_all("qtds", "$..qty")
+        .unwrap();
+    assert_eq!(
+        manual.apply(&interno),
+        json_safe!({ id: 7, qtds: [1, 2] }).unwrap()
+    );
+
+    assert_eq!(
+        shape::select(&interno, "$['address'].*").unwrap(),
+        [&Value::from("SP"), &Value::from("01000")]
+    );
+    assert!(shape! { x: "address.city" }.is_err());
+    assert!(shape::select(&interno, "$.items[x]").is_err());
+    assert!(shape::select(&interno, "$.items[0").is_err());
+}
+
+// Feed de deltas (RFC 6902) para sincronização
+#[test]
+fn delta_feed() {
+    let v0 = json_safe!({ titulo: "a", tags: ["x", "y", "z"], meta: { n: 1, "a/b": 0 } }).unwrap();
+    let v1 = json_safe!({ titulo: "b", tags: ["x"], meta: { n: 1 }, novo: true }).unwrap();
+
+    let ops = delta::diff(&v0, &v1);
+    assert_eq!(
+        ops,
+        json_safe!([
+            { op: "remove", path: "/meta/a~1b" },
+            { op: "remove", path: "/tags/2" },
+            { op: "remove", path: "/tags/1" },
+            { op: "replace", path: "/titulo", value: "b" },
+            { op: "add", path: "/novo", value: true },
+        ])
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .clone()
+    );
+
+    let feed = delta::feed(0, &v0, &v1);
+    assert_eq!(feed["since"], 0);
+    let mut cliente = v0.clone();
+    delta::apply_feed(&mut cliente, &feed).unwrap();
+    assert_eq!(cliente, v1);
+    assert!(delta::diff(&v1, &v1).is_empty());
+
+    // Operações de JSON Patch além das geradas pelo diff
+    let mut doc = json_safe!({ a: [1, 2], b: { c: 3 } }).unwrap();
+    let patch = json_safe!([
+        { op: "test", path: "/b/c", value: 3 },
+        { op: "move", from: "/b/c", path: "/d" },
+        { op: "copy", from: "/a/0", path: "/a/-" },
+        { op: "add", path: "/a/0", value: 0 },
+    ])
+    .unwrap();
+    delta::apply_patch(&mut doc, patch.as_array().unwrap()).unwrap();
+    assert_eq!(doc, json_safe!({ a: [0, 1, 2, 1], b: {}, d: 3 }).unwrap());
+    let falha =
+        json_safe!([{ op: "add", path: "/x", value: 1 }, { op: "remove", path: "/nada" }]).unwrap();
+    assert!(delta::apply_patch(&mut doc, falha.as_array().unwrap()).is_err());
+    assert!(doc.get("x").is_none());
+
+    // Histórico no servidor
+    let mut historico = delta::Tracked::new(v0.clone()).capacity(2);
+    assert_eq!(historico.update(v0.clone()), 0);
+    assert_eq!(historico.update(v1.clone()), 1);
+    let feed = historico.feed_since(0).unwrap();
+    assert_eq!(feed["version"], 1);
+    let mut cliente = v0.clone();
+    delta::apply_feed(&mut cliente, &feed).unwrap();
+    assert_eq!(cliente, *historico.current());
+    historico.update(json_safe!({}).unwrap());
+    assert!(historico.feed_since(0).is_none());
+    assert_eq!(
+        historico.feed_since(2).unwrap()["ops"],
+        json_safe!([]).unwrap()
+    );
+}
+
+// Delta binário por hash de subárvores, com fallback para o documento
+#[test]
+fn binary_delta() {
+    let servicos: Vec<Value> = (0..50)
+        .map(|i| {
+            json_safe!({ nome: (format!("svc-{i}")), réplicas: 2, limites: { cpu: 500 } }).unwrap()
+        })
+        .collect();
+    let v0 = json_safe!({
+        versao: 1,
+        servicos: (&servicos),
+        flags: { beta: false, "a/b": 1, velho: true },
+    })
+    .unwrap();
+    let mut v1 = v0.clone();
+    v1["versao"] = Value::from(2);
+    v1["flags"]["beta"] = Value::from(true);
+    v1["flags"].as_object_mut().unwrap().remove("velho");
+    v1["flags"]["a/b"] = json_safe!({ x: 1 }).unwrap();
+
+    // O cliente só manda o manifesto; o servidor não precisa de v0
+    let manifesto = delta::Manifest::of(&v0, 2);
+    let recebido = delta::Manifest::from_bytes(&manifesto.to_bytes()).unwrap();
+    assert_eq!(recebido, manifesto);
+
+    let bytes = delta::binary_delta(&recebido, &v1);
+    assert!(bytes.len() < serde_json::to_vec(&v1).unwrap().len() / 10);
+    let mut cliente = v0.clone();
+    delta::apply_binary_delta(&mut cliente, &bytes).unwrap();
+    assert_eq!(cliente, v1);
+
+    // Base diferente da do manifesto: erro, documento intacto
+    let mut outro = json_safe!({ versao: 9 }).unwrap();
+    assert!(delta::apply_binary_delta(&mut outro, &bytes).is_err());
+    assert_eq!(outro, json_safe!({ versao: 9 }).unwrap());
+    assert!(delta::apply_binary_delta(&mut cliente, &bytes[..bytes.len() - 3]).is_err());
+
+    // Mudança total: vai o documento inteiro
+    let novo = json_safe!([1, 2, 3]).unwrap();
+    let inteiro = delta::binary_delta(&manifesto, &novo);
+    assert_eq!(&inteiro[..5], b"JSD1\0");
+    let mut cliente = v0.clone();
+    delta::apply_binary_delta(&mut cliente, &inteiro).unwrap();
+    assert_eq!(cliente, novo);
+    assert!(delta::Manifest::from_bytes(b"JSM").is_err());
+}
+
+// Números no texto original através do round trip
+#[test]
+fn number_fidelity() {
+    let texto = r#"{"a":1.10,"b":[1e2,-0.0,7],"c":1E+2,"d":"1.10"}"#;
+    let doc = fidelity::parse(texto.as_bytes()).unwrap();
+    assert_eq!(doc.to_string(), texto);
+    assert_eq!(doc.lexeme("/b/0"), Some("1e2"));
+    assert_eq!(doc.lexeme("/d"), None);
+
+    let mut editado = doc.clone();
+    editado.value_mut()["a"] = Value::from(2.5);
+    assert_eq!(editado.lexeme("/a"), None);
+    assert!(editado.to_string().starts_with(r#"{"a":2.5,"b":[1e2,"#));
+
+    let valor = doc.with_number("/b/2", "7.000").unwrap();
+    assert_eq!(valor.value()["b"][2].as_f64(), Some(7.0));
+    assert_eq!(
+        valor.to_string(),
+        r#"{"a":1.10,"b":[1e2,-0.0,7.000],"c":1E+2,"d":"1.10"}"#
+    );
+    assert!(valor.clone().with_number("/a", "1.1.0").is_err());
+    assert!(valor.with_number("/x", "1").is_err());
+    assert!(fidelity::parse(b"[1.10,").is_err());
+}
+
+// Notação científica e casas decimais dos floats na saída
+#[test]
+fn float_formatting() {
+    let padrao = float_format::FloatFormat::new();
+    for x in [
+        0.0, -0.0, 1.0, 1.5, -0.1, 1e-5, 1e-6, 1.25e-7, 1e15, 1e16, 1.5e300, 123.456,
+    ] {
+        assert_eq!(padrao.format(x).unwrap(), Value::from(x).to_string());
+    }
+    assert_eq!(padrao.format(f64::NAN), None);
+
+    let planilha = float_format::FloatFormat::new()
+        .min_exponent(-2)
+        .max_exponent(5)
+        .min_fraction_digits(2);
+    assert_eq!(planilha.format(1.5).unwrap(), "1.50");
+    assert_eq!(planilha.format(-0.01).unwrap(), "-0.01");
+    assert_eq!(planilha.format(0.001).unwrap(), "1e-3");
+    assert_eq!(planilha.format(123456.0).unwrap(), "123456.00");
+    assert_eq!(planilha.format(1234567.0).unwrap(), "1.234567e6");
+    assert_eq!(
+        float_format::FloatFormat::new()
+            .min_fraction_digits(0)
+            .format(3.0)
+            .unwrap(),
+        "3"
+    );
+
+    let doc = json_safe!({ preco: 2.5, qtd: 3, taxa: 0.0001, nome: "x" }).unwrap();
+    let texto = planilha.to_string(&doc);
+    assert_eq!(texto, r#"{"nome":"x","preco":2.50,"qtd":3,"taxa":1e-4}"#);
+    let volta: Value = serde_json::from_str(&texto).unwrap();
+    assert_eq!(
+        (volta["preco"].as_f64(), volta["taxa"].as_f64()),
+        (Some(2.5), Some(0.0001))
+    );
+}
+
+// Função serialize_with inline, sem newtype
+#[test]
+fn serialize_with_marker() {
+    mod formatos {
+        use serde::{Serializer, ser::Error};
+
+        pub fn data_br<S: Serializer>(data: &(u32, u32, u32), s: S) -> Result<S::Ok, S::Error> {
+            let (ano, mes, dia) = *data;
+            s.serialize_str(&format!("{dia:02}/{mes:02}/{ano}"))
+        }
+
+        pub fn positivo<S: Serializer>(n: &i64, s: S) -> Result<S::Ok, S::Error> {
+            if *n < 0 {
+                return Err(S::Error::custom(format!("{n} é negativo")));
+            }
+            s.serialize_i64(*n)
+        }
+    }
+
+    let criado = (2024, 5, 1);
+    let doc = json_safe!({
+        criado_em: with!(criado, formatos::data_br),
+        itens: [with!(3, formatos::positivo)],
+    })
+    .unwrap();
+    assert_eq!(doc["criado_em"], Value::from("01/05/2024"));
+    assert_eq!(doc["itens"][0], Value::from(3));
+
+    let erro = json_safe!({ saldo: { total: with!(-1i64, formatos::positivo) } }).unwrap_err();
+    assert_eq!(erro.path(), "saldo.total");
+    assert!(erro.to_string().contains("-1 é negativo"), "{erro}");
+}
+
+// Bytes como string base64 em vez de array de números
+#[test]
+fn base64_bytes() {
+    let anexo: Vec<u8> = b"hello".to_vec();
+    let assinatura = [0xfbu8, 0xff];
+    let doc = json_safe!({
+        anexo: base64!(&anexo),
+        assinatura: base64!(url assinatura),
+        vazio: (base64::Base64::new(&[][..])),
+        partes: [for parte in anexo.chunks(3) => base64!(parte)],
+    })
+    .unwrap();
+    assert_eq!(doc["anexo"], Value::from("aGVsbG8="));
+    assert_eq!(doc["assinatura"], Value::from("-_8"));
+    assert_eq!(doc["vazio"], Value::from(""));
+    assert_eq!(doc["partes"], json_safe!(["aGVs", "bG8="]).unwrap());
+    assert_eq!(
+        base64::decode(doc["anexo"].as_str().unwrap()).unwrap(),
+        anexo
+    );
+}
+
+// Fragmentos JSON já serializados dentro do payload
+#[test]
+fn raw_fragments() {
+    let perfil = String::from(r#"{"id":7,"tags":["a"]}"#);
+    let itens = [r#"{"n":1}"#, "2", "null"];
+    let doc = json_safe!({
+        perfil: raw!(&perfil),
+        vazio: raw!("[]"),
+        itens: [for item in itens => raw!(item)],
+    })
+    .unwrap();
+    assert_eq!(
+        doc,
+        json_safe!({ perfil: { id: 7, tags: ["a"] }, vazio: [], itens: [{ n: 1 }, 2, null] })
+            .unwrap()
+    );
+
+    let erro = json_safe!({ cache: { perfil: raw!("{\"id\":") } }).unwrap_err();
+    assert_eq!(erro.path(), "cache.perfil");
+    assert!(erro.to_string().contains("JSON inválido"), "{erro}");
+    assert!(json_safe!([raw!("1 2")]).is_err());
+
+    #[cfg(feature = "raw-value")]
+    {
+        let fragmento = serde_json::value::RawValue::from_string(perfil.clone()).unwrap();
+        assert_eq!(
+            json_safe!({ perfil: raw!(&fragmento) }).unwrap()["perfil"]["id"],
+            Value::from(7)
+        );
+        assert_eq!(
+            json_safe!(raw!(fragmento)).unwrap()["tags"][0],
+            Value::from("a")
+        );
+    }
+}